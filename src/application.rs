@@ -16,6 +16,7 @@ use search_provider::{IconData, ResultID, ResultMeta, SearchProvider, SearchProv
 
 use crate::colors::color::Color;
 use crate::config::{self, APP_ID, PKGDATADIR, PROFILE, VERSION};
+use crate::model::history::ColorSource;
 use crate::widgets::about_window::EyedropperAbout;
 use crate::widgets::preferences::preferences_window::PreferencesWindow;
 use crate::window::AppWindow;
@@ -128,7 +129,8 @@ impl App {
         let action_random_color = gio::ActionEntry::builder("random-color")
             .activate(|app: &Self, _, _| {
                 // Set the color to a random color
-                app.active_app_window().set_color(Color::random());
+                app.active_app_window()
+                    .set_color(Color::random(), ColorSource::Random);
             })
             .build();
 
@@ -323,7 +325,7 @@ impl SearchProviderImpl for App {
         let window = self.active_app_window();
 
         if let Ok(rgba) = gdk::RGBA::parse(identifier) {
-            window.set_color(rgba.into());
+            window.set_color(rgba.into(), ColorSource::SearchProvider);
         }
 
         window.present();
@@ -0,0 +1,361 @@
+use std::io::BufRead;
+use std::str::FromStr;
+
+use glib::ExitCode;
+use gtk::glib;
+
+use crate::colors::Notation;
+use crate::colors::color::Color;
+use crate::colors::color_names::ColorNameSources;
+use crate::colors::gamut::GamutMapping;
+use crate::colors::illuminant::{Illuminant, ReferenceWhite, StandardObserver};
+use crate::colors::parser;
+use crate::colors::position::AlphaPosition;
+
+/// Name of the subcommand used to convert colors.
+pub const CONVERT_COMMAND: &str = "convert";
+
+const USAGE: &str = "Usage: eyedropper convert [OPTIONS] [COLOR…]
+
+Convert colors to other notations without opening a window.
+If no color is given, colors are read line by line from the standard input.
+The notation of each color is detected automatically.
+
+Options:
+  --to <FORMATS>             Comma separated list of formats to convert to [default: hex]
+                             hex, rgb, hsl, hsv, cmyk, xyz, cielab, hwb, hcl, name,
+                             lms, hunterlab, oklab, oklch, srgb-linear, display-p3,
+                             rec2020, a98-rgb, prophoto-rgb, android, swiftui, flutter,
+                             qt, glsl, dotnet, rust-palette, latex
+  --precision <DIGITS>       Number of digits after the decimal point [default: 2]
+  --alpha-position <POS>     Position of the alpha value: none, end or start [default: none]
+  --rgb-notation <NOTATION>  RGB values as integer or decimal [default: integer]
+  --name-sources <SOURCES>   Comma separated list of name sources: html, svg, gnome, xkcd
+                             [default: html,svg,gnome,xkcd]
+  --illuminant <ILLUMINANT>  CIE illuminant: A, B, C, D50, D55, D65, D75, E, F2, F7, F11 [default: D65]
+  --observer <DEGREES>       CIE standard observer: 2 or 10 [default: 2]
+  --gamut-mapping <METHOD>   Mapping of colors outside of the sRGB gamut in sRGB based formats:
+                             chroma or clip [default: chroma]
+  -h, --help                 Print this help";
+
+/// Options of the convert command.
+///
+/// The defaults mirror the default values of the settings.
+#[derive(Debug)]
+struct ConvertOptions {
+    targets: Vec<Notation>,
+    precision: usize,
+    alpha_position: AlphaPosition,
+    rgb_decimal_notation: bool,
+    name_sources: ColorNameSources,
+    reference_white: ReferenceWhite,
+    gamut_mapping: GamutMapping,
+    colors: Vec<String>,
+}
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        Self {
+            targets: vec![Notation::Hex],
+            precision: 2,
+            alpha_position: AlphaPosition::None,
+            rgb_decimal_notation: false,
+            name_sources: ColorNameSources::all(),
+            reference_white: ReferenceWhite::default(),
+            gamut_mapping: GamutMapping::default(),
+            colors: Vec::new(),
+        }
+    }
+}
+
+impl ConvertOptions {
+    /// Parses the options from the command line arguments following the subcommand.
+    ///
+    /// Returns [`None`] if the help should be printed instead.
+    fn parse(args: &[String]) -> Result<Option<Self>, String> {
+        let mut options = Self::default();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            if arg == "-h" || arg == "--help" {
+                return Ok(None);
+            }
+
+            if !arg.starts_with("--") {
+                options.colors.push(arg.to_owned());
+                continue;
+            }
+
+            // values can be passed as `--option value` or `--option=value`
+            let (option, value) = match arg.split_once('=') {
+                Some((option, value)) => (option, value.to_owned()),
+                None => (
+                    arg.as_str(),
+                    args.next()
+                        .ok_or_else(|| format!("Missing value for {}", arg))?
+                        .to_owned(),
+                ),
+            };
+
+            match option {
+                "--to" => {
+                    options.targets = value
+                        .split(',')
+                        .map(|format| {
+                            Notation::from_str(format)
+                                .map_err(|_| format!("Unknown format: {}", format))
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+                "--precision" => {
+                    options.precision = value
+                        .parse()
+                        .map_err(|_| format!("Invalid precision: {}", value))?;
+                }
+                "--alpha-position" => {
+                    options.alpha_position = match value.to_lowercase().as_str() {
+                        "none" => AlphaPosition::None,
+                        "end" => AlphaPosition::End,
+                        "start" => AlphaPosition::Start,
+                        _ => return Err(format!("Invalid alpha position: {}", value)),
+                    };
+                }
+                "--rgb-notation" => {
+                    options.rgb_decimal_notation = match value.to_lowercase().as_str() {
+                        "integer" => false,
+                        "decimal" => true,
+                        _ => return Err(format!("Invalid RGB notation: {}", value)),
+                    };
+                }
+                "--name-sources" => {
+                    options.name_sources = value.split(',').try_fold(
+                        ColorNameSources::empty(),
+                        |sources, source| {
+                            Ok(sources
+                                | match source.trim().to_lowercase().as_str() {
+                                    "html" => ColorNameSources::Html,
+                                    "svg" => ColorNameSources::Svg,
+                                    "gnome" => ColorNameSources::Gnome,
+                                    "xkcd" => ColorNameSources::Xkcd,
+                                    _ => return Err(format!("Unknown name source: {}", source)),
+                                })
+                        },
+                    )?;
+                }
+                "--illuminant" => {
+                    options.reference_white.illuminant = match value.to_uppercase().as_str() {
+                        "A" => Illuminant::A,
+                        "B" => Illuminant::B,
+                        "C" => Illuminant::C,
+                        "D50" => Illuminant::D50,
+                        "D55" => Illuminant::D55,
+                        "D65" => Illuminant::D65,
+                        "D75" => Illuminant::D75,
+                        "E" => Illuminant::E,
+                        "F2" => Illuminant::F2,
+                        "F7" => Illuminant::F7,
+                        "F11" => Illuminant::F11,
+                        _ => return Err(format!("Unknown illuminant: {}", value)),
+                    };
+                }
+                "--observer" => {
+                    options.reference_white.observer = match value.trim_end_matches('°') {
+                        "2" => StandardObserver::Two,
+                        "10" => StandardObserver::Ten,
+                        _ => return Err(format!("Invalid observer: {}", value)),
+                    };
+                }
+                "--gamut-mapping" => {
+                    options.gamut_mapping = match value.to_lowercase().as_str() {
+                        "chroma" => GamutMapping::Chroma,
+                        "clip" => GamutMapping::Clip,
+                        _ => return Err(format!("Invalid gamut mapping: {}", value)),
+                    };
+                }
+                _ => return Err(format!("Unknown option: {}", option)),
+            }
+        }
+
+        Ok(Some(options))
+    }
+
+    /// Parses the input in any supported notation.
+    fn detect_color(&self, input: &str) -> Option<Color> {
+        parser::any_color(
+            input,
+            self.alpha_position,
+            self.name_sources,
+            self.reference_white,
+        )
+        .ok()
+        .map(|(_, (_notation, color))| color)
+    }
+
+    /// Formats the color in all target notations, separated by tabs.
+    ///
+    /// Colors outside of the sRGB gamut are mapped into it for sRGB based notations.
+    fn format(&self, color: Color) -> String {
+        self.targets
+            .iter()
+            .map(|notation| {
+                notation.as_str(
+                    color,
+                    self.alpha_position,
+                    self.rgb_decimal_notation,
+                    self.precision,
+                    self.name_sources,
+                    self.reference_white,
+                    self.gamut_mapping,
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\t")
+    }
+}
+
+/// Converts the colors given on the command line, or read from the standard input.
+///
+/// Each color is printed on its own line, with the target formats separated by tabs.
+/// Colors that cannot be parsed are reported on the standard error, in which case
+/// the command fails after converting all other colors.
+pub fn convert(args: &[String]) -> ExitCode {
+    let options = match ConvertOptions::parse(args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let inputs: Box<dyn Iterator<Item = String>> = if options.colors.is_empty() {
+        Box::new(std::io::stdin().lock().lines().map_while(Result::ok))
+    } else {
+        Box::new(options.colors.clone().into_iter())
+    };
+
+    let mut success = true;
+    for input in inputs.filter(|input| !input.trim().is_empty()) {
+        match options.detect_color(input.trim()) {
+            Some(color) => {
+                if !color.is_in_gamut() && options.targets.iter().any(Notation::is_srgb) {
+                    eprintln!(
+                        "Color is out of the sRGB gamut and has been mapped into it: {}",
+                        input.trim()
+                    );
+                }
+                println!("{}", options.format(color));
+            }
+            None => {
+                eprintln!("Failed to parse color: {}", input.trim());
+                success = false;
+            }
+        }
+    }
+
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(test)]
+mod parse_options {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<ConvertOptions>, String> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        ConvertOptions::parse(&args)
+    }
+
+    #[test]
+    fn it_uses_the_defaults() {
+        let options = parse(&["#2e3440"]).unwrap().unwrap();
+        assert_eq!(vec![Notation::Hex], options.targets);
+        assert_eq!(2, options.precision);
+        assert_eq!(GamutMapping::default(), options.gamut_mapping);
+        assert_eq!(vec!["#2e3440".to_owned()], options.colors);
+    }
+
+    #[test]
+    fn it_parses_flags() {
+        let options = parse(&[
+            "--to",
+            "rgb,hsl",
+            "--precision=4",
+            "--alpha-position",
+            "end",
+            "--rgb-notation=decimal",
+            "--name-sources",
+            "html,xkcd",
+            "--illuminant=d50",
+            "--observer",
+            "10°",
+            "red",
+            "blue",
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(vec![Notation::Rgb, Notation::Hsl], options.targets);
+        assert_eq!(4, options.precision);
+        assert_eq!(AlphaPosition::End, options.alpha_position);
+        assert!(options.rgb_decimal_notation);
+        assert_eq!(
+            ColorNameSources::Html | ColorNameSources::Xkcd,
+            options.name_sources
+        );
+        assert_eq!(Illuminant::D50, options.reference_white.illuminant);
+        assert_eq!(StandardObserver::Ten, options.reference_white.observer);
+        assert_eq!(vec!["red".to_owned(), "blue".to_owned()], options.colors);
+    }
+
+    #[test]
+    fn it_parses_the_gamut_mapping() {
+        let options = parse(&["--gamut-mapping", "clip"]).unwrap().unwrap();
+        assert_eq!(GamutMapping::Clip, options.gamut_mapping);
+        let options = parse(&["--gamut-mapping=Chroma"]).unwrap().unwrap();
+        assert_eq!(GamutMapping::Chroma, options.gamut_mapping);
+        assert_eq!(
+            Err("Invalid gamut mapping: none".to_owned()),
+            parse(&["--gamut-mapping", "none"]).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn it_requests_the_help() {
+        assert!(parse(&["--to", "rgb", "-h"]).unwrap().is_none());
+        assert!(parse(&["--help"]).unwrap().is_none());
+    }
+
+    #[test]
+    fn it_rejects_missing_values() {
+        assert_eq!(
+            Err("Missing value for --precision".to_owned()),
+            parse(&["red", "--precision"]).map(|_| ())
+        );
+        assert_eq!(
+            Err("Invalid precision: ".to_owned()),
+            parse(&["--precision="]).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn it_rejects_unknown_flags() {
+        assert_eq!(
+            Err("Unknown option: --format".to_owned()),
+            parse(&["--format", "hex"]).map(|_| ())
+        );
+        assert_eq!(
+            Err("Unknown format: cmy".to_owned()),
+            parse(&["--to=hex,cmy"]).map(|_| ())
+        );
+        assert_eq!(
+            Err("Unknown name source: pantone".to_owned()),
+            parse(&["--name-sources", "html,pantone"]).map(|_| ())
+        );
+    }
+}
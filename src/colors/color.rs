@@ -3,14 +3,27 @@ use std::{borrow::Cow, str::FromStr};
 
 use glib::variant::{FromVariant, ToVariant};
 use gtk::glib::{self, Variant, variant::StaticVariantType};
-use palette::IntoColor;
+use palette::{
+    Clamp, Lab, LinSrgba, Oklab, WithAlpha, Xyz,
+    convert::{FromColorUnclamped, IntoColorUnclamped},
+    white_point::Any,
+};
 
-use super::parser;
+use super::{illuminant::ReferenceWhite, parser};
+
+/// Threshold of the CIELAB conversion, from the CIE standard.
+const LAB_EPSILON: f32 = 216.0 / 24389.0;
+/// Scaling factor of the CIELAB conversion, from the CIE standard.
+const LAB_KAPPA: f32 = 24389.0 / 27.0;
+
+/// Tolerance of the sRGB gamut check.
+const GAMUT_EPSILON: f32 = 0.0001;
 
 /// Eyedropper's internal color representation.
 ///
 /// Utility struct to
-#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, glib::Boxed)]
+#[boxed_type(name = "Color")]
 pub struct Color(palette::Srgba);
 
 impl std::ops::Deref for Color {
@@ -55,6 +68,27 @@ impl Color {
         Self(color.into_color())
     }
 
+    /// Create a color from sRGB values, without clamping them to the sRGB gamut.
+    ///
+    /// Used for colors from wider color spaces, see [`Color::is_in_gamut`].
+    pub fn unclamped(color: palette::Srgba) -> Self {
+        Self(color)
+    }
+
+    /// Clips the color to the sRGB gamut, by clamping all values between 0.0 and 1.0.
+    pub fn clip(self) -> Self {
+        Self(self.0.clamp())
+    }
+
+    /// Whether the color can be represented in sRGB, i.e. all values are between 0.0 and 1.0.
+    ///
+    /// A small tolerance is allowed for rounding errors of the conversion.
+    pub fn is_in_gamut(&self) -> bool {
+        [self.red, self.green, self.blue]
+            .iter()
+            .all(|value| (-GAMUT_EPSILON..=1.0 + GAMUT_EPSILON).contains(value))
+    }
+
     pub fn hex(&self) -> String {
         format!(
             "#{:02x}{:02x}{:02x}{:02x}",
@@ -65,18 +99,122 @@ impl Color {
         )
     }
 
+    /// Formats the color as CSS `color(srgb …)` function, which keeps the exact channels,
+    /// including values outside of the sRGB gamut.
+    pub fn css(&self) -> String {
+        if self.alpha < 1.0 {
+            format!(
+                "color(srgb {} {} {} / {})",
+                self.red, self.green, self.blue, self.alpha
+            )
+        } else {
+            format!("color(srgb {} {} {})", self.red, self.green, self.blue)
+        }
+    }
+
+    /// Convert the color to linear sRGB.
+    ///
+    /// Values outside of the sRGB gamut are kept, with negative values mirrored by the
+    /// transfer function, as specified by CSS.
+    pub fn to_linear(self) -> LinSrgba {
+        LinSrgba::new(
+            srgb_decode(self.red),
+            srgb_decode(self.green),
+            srgb_decode(self.blue),
+            self.alpha,
+        )
+    }
+
+    /// Create a color from linear sRGB values, without clamping them to the sRGB gamut.
+    pub fn from_linear(linear: LinSrgba) -> Self {
+        Self::unclamped(palette::Srgba::new(
+            srgb_encode(linear.red),
+            srgb_encode(linear.green),
+            srgb_encode(linear.blue),
+            linear.alpha,
+        ))
+    }
+
+    /// Convert the color to Oklab, keeping values outside of the sRGB gamut.
+    pub fn to_oklab(self) -> Oklab {
+        self.to_linear().color.into_color_unclamped()
+    }
+
+    /// Convert the color to XYZ values under the given reference white.
+    ///
+    /// The values are normalized, so that the reference white has `Y = 1.0`.
+    pub fn to_xyz(self, white: ReferenceWhite) -> Xyz<Any, f32> {
+        let xyz: palette::Xyz = self.to_linear().color.into_color_unclamped();
+        white.adapt_from_d65(xyz.with_white_point())
+    }
+
+    /// Create a color from XYZ values under the given reference white.
+    ///
+    /// The color is not clamped, so it may be out of the sRGB gamut.
+    pub fn from_xyz(xyz: Xyz<Any, f32>, white: ReferenceWhite, alpha: f32) -> Self {
+        let xyz: palette::Xyz = white.adapt_to_d65(xyz).with_white_point();
+        Self::from_linear(palette::LinSrgb::from_color_unclamped(xyz).with_alpha(alpha))
+    }
+
+    /// Convert the color to CIELAB, relative to the given reference white.
+    pub fn to_lab(self, white: ReferenceWhite) -> Lab<Any, f32> {
+        let xyz = self.to_xyz(white);
+        let white_xyz = white.xyz();
+
+        let f = |t: f32| {
+            if t > LAB_EPSILON {
+                t.cbrt()
+            } else {
+                (LAB_KAPPA * t + 16.0) / 116.0
+            }
+        };
+        let (fx, fy, fz) = (
+            f(xyz.x / white_xyz.x),
+            f(xyz.y / white_xyz.y),
+            f(xyz.z / white_xyz.z),
+        );
+
+        Lab::new(116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+    }
+
+    /// Create a color from CIELAB values, relative to the given reference white.
+    pub fn from_lab(lab: Lab<Any, f32>, white: ReferenceWhite, alpha: f32) -> Self {
+        let fy = (lab.l + 16.0) / 116.0;
+        let fx = fy + lab.a / 500.0;
+        let fz = fy - lab.b / 200.0;
+
+        let f_inverse = |t: f32| {
+            if t.powi(3) > LAB_EPSILON {
+                t.powi(3)
+            } else {
+                (116.0 * t - 16.0) / LAB_KAPPA
+            }
+        };
+        let white_xyz = white.xyz();
+        let xyz = Xyz::new(
+            f_inverse(fx) * white_xyz.x,
+            f_inverse(fy) * white_xyz.y,
+            f_inverse(fz) * white_xyz.z,
+        );
+
+        Self::from_xyz(xyz, white, alpha)
+    }
+
     /// Convert the color to the LMS color space.
     ///
     /// LMS (long, medium short) is a a color space, that
     /// represents the cones in the human eyes.
     ///
     /// The conversion uses the formula form [Fundamentals of Imaging Colour Spaces](https://www.uni-weimar.de/fileadmin/user/fak/medien/professuren/Computer_Graphics/3-ima-color-spaces17.pdf)
-    /// The matrix is assumed to be under [Illuminant::E].
-    pub fn to_lms(self) -> (f32, f32, f32) {
+    /// The matrix is normalized to [`Illuminant::E`](super::illuminant::Illuminant::E), so the
+    /// color is first adapted to the given reference white.
+    pub fn to_lms(self, white: ReferenceWhite) -> (f32, f32, f32) {
         //TODO: remove this once palette supports LMS in the next version
-        let xyz: palette::Xyz = self.color.into_color();
+        Self::xyz_to_lms(self.to_xyz(white))
+    }
 
-        //TODO: use illuminant depended matrices, found here http://brucelindbloom.com/index.html?Eqn_ChromAdapt.html
+    /// Convert XYZ values to the LMS color space, using the same matrix as [`Color::to_lms`].
+    pub fn xyz_to_lms(xyz: Xyz<Any, f32>) -> (f32, f32, f32) {
         let long = xyz.x * 0.3897 + xyz.y * 0.6890 + xyz.z * -0.0787;
         let medium = xyz.x * -0.2298 + xyz.y * 1.1834 + xyz.z * 0.0464;
         let short = xyz.x * 0.0 + xyz.y * 0.0 + xyz.z * 1.0;
@@ -84,12 +222,32 @@ impl Color {
         (long, medium, short)
     }
 
-    pub fn from_lms(long: f32, medium: f32, short: f32, alpha: u8) -> Self {
+    pub fn from_lms(long: f32, medium: f32, short: f32, alpha: u8, white: ReferenceWhite) -> Self {
         let x = long * 1.9102 + medium * -1.1121 + short * 0.2019;
         let y = long * 0.3710 + medium * 0.6291 + short * 0.0;
         let z = long * 0.0 + medium * 0.0 + short * 1.0;
 
-        Color::from_palette(palette::Xyza::new(x, y, z, alpha as f32 / 255.0))
+        Color::from_xyz(Xyz::new(x, y, z), white, alpha as f32 / 255.0)
+    }
+}
+
+/// The sRGB transfer function, extended to values outside of the range from 0.0 to 1.0.
+pub(super) fn srgb_decode(value: f32) -> f32 {
+    let abs = value.abs();
+    if abs <= 0.04045 {
+        value / 12.92
+    } else {
+        value.signum() * ((abs + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// The inverse sRGB transfer function, extended to values outside of the range from 0.0 to 1.0.
+pub(super) fn srgb_encode(value: f32) -> f32 {
+    let abs = value.abs();
+    if abs > 0.0031308 {
+        value.signum() * (1.055 * abs.powf(1.0 / 2.4) - 0.055)
+    } else {
+        value * 12.92
     }
 }
 
@@ -1,5 +1,7 @@
 use gtk::glib;
+use palette::IntoColor;
 use std::str::FromStr;
+use std::sync::LazyLock;
 
 use crate::colors::color::Color;
 
@@ -23,6 +25,28 @@ pub enum ColorNameSources {
     Xkcd = 8,
 }
 
+/// Palettes mapping hex values to their names, in the order they are searched in.
+const NAMES: [(ColorNameSources, &phf::Map<&str, &str>); 4] = [
+    (ColorNameSources::Html, &BASIC_VALUES),
+    (ColorNameSources::Svg, &SVG_VALUES),
+    (ColorNameSources::Gnome, &GNOME_VALUES),
+    (ColorNameSources::Xkcd, &XKCD_VALUES),
+];
+
+/// Named colors of all palettes with their Oklab values, in the order they are searched in.
+///
+/// Computed once, as finding the nearest name compares the color against every named color.
+static NAMED_OKLAB: LazyLock<Vec<(ColorNameSources, &str, palette::Oklab)>> = LazyLock::new(|| {
+    NAMES
+        .iter()
+        .flat_map(|&(flag, palette)| palette.entries().map(move |entry| (flag, entry)))
+        .filter_map(|(flag, (hex, name))| {
+            let color = Color::from_str(hex).ok()?;
+            Some((flag, *name, color.color.into_color()))
+        })
+        .collect()
+});
+
 /// Returns the corresponding name for a given [`Color`].
 ///
 /// The color is searched in all the enabled palettes, in the order they are listed in.
@@ -30,19 +54,34 @@ pub enum ColorNameSources {
 pub fn name(color: Color, sources: ColorNameSources) -> Option<String> {
     let hex = color.hex().to_ascii_lowercase();
 
-    let palettes = [
-        (ColorNameSources::Html, &BASIC_VALUES),
-        (ColorNameSources::Svg, &SVG_VALUES),
-        (ColorNameSources::Gnome, &GNOME_VALUES),
-        (ColorNameSources::Xkcd, &XKCD_VALUES),
-    ];
-
-    palettes
+    NAMES
         .iter()
         .filter(|&&(flag, _)| sources.contains(flag))
         .find_map(|&(_, palette)| palette.get(&hex).map(|val| val.to_string()))
 }
 
+/// Returns the name of the perceptually closest named color, together with its distance.
+///
+/// The distance is the euclidean distance in the Oklab color space, an exact match has a distance of `0.0`.
+/// The alpha value of the color is ignored.
+/// If no palette is enabled, [`None`] is returned.
+pub fn nearest_name(color: Color, sources: ColorNameSources) -> Option<(String, f32)> {
+    let oklab: palette::Oklab = color.color.into_color();
+
+    NAMED_OKLAB
+        .iter()
+        .filter(|&&(flag, _, _)| sources.contains(flag))
+        .map(|&(_, name, named)| {
+            let distance = ((oklab.l - named.l).powi(2)
+                + (oklab.a - named.a).powi(2)
+                + (oklab.b - named.b).powi(2))
+            .sqrt();
+            (name, distance)
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(name, distance)| (name.to_string(), distance))
+}
+
 /// Returns the corresponding [`Color`] for a given name.
 ///
 /// The color is searched in all the enabled palettes, in the order they are listed in.
@@ -61,3 +100,44 @@ pub fn color(name: &str, sources: ColorNameSources) -> Option<Color> {
         .filter_map(|&(_, palette)| palette.get(&name.to_ascii_lowercase()))
         .find_map(|val| Color::from_str(val).ok())
 }
+
+#[cfg(test)]
+mod find_nearest_name {
+    use super::*;
+
+    #[test]
+    fn it_finds_exact_name() {
+        let color = Color::from_str("#3584e4").unwrap();
+        assert_eq!(
+            Some(("Blue 3".to_owned(), 0.0)),
+            nearest_name(color, ColorNameSources::Gnome)
+        );
+    }
+
+    #[test]
+    fn it_finds_nearest_name() {
+        let (name, distance) =
+            nearest_name(Color::rgba(250, 5, 5, 255), ColorNameSources::Html).unwrap();
+        assert_eq!("red", name);
+        assert!(distance > 0.0 && distance < 0.05, "distance {distance}");
+    }
+
+    #[test]
+    fn it_finds_no_name_without_sources() {
+        assert_eq!(
+            None,
+            nearest_name(Color::rgba(255, 0, 0, 255), ColorNameSources::empty())
+        );
+    }
+
+    #[test]
+    fn it_ignores_disabled_sources() {
+        // exactly matches a GNOME color
+        let color = Color::from_str("#3584e4").unwrap();
+        let sources = ColorNameSources::all() - ColorNameSources::Gnome;
+        let (name, distance) = nearest_name(color, sources).unwrap();
+        assert_ne!("Blue 3", name);
+        assert!(distance > 0.0);
+        assert!(self::color(&name, sources).is_some());
+    }
+}
@@ -0,0 +1,207 @@
+use super::color::Color;
+
+/// Conformance levels of the WCAG 2.x contrast requirements.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WcagLevel {
+    /// Minimum contrast, success criterion 1.4.3.
+    Aa,
+    /// Enhanced contrast, success criterion 1.4.6.
+    Aaa,
+}
+
+/// Size of the text, as the WCAG 2.x requirements are lower for large text.
+///
+/// Large text is at least 18pt, or 14pt when bold.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TextSize {
+    Normal,
+    Large,
+}
+
+/// Contrast between a text and its background color.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Contrast {
+    /// WCAG 2.x contrast ratio, between 1.0 and 21.0.
+    pub ratio: f32,
+    /// APCA lightness contrast (Lc), roughly between -108 and 106.
+    ///
+    /// Positive values indicate dark text on a light background,
+    /// negative values light text on a dark background.
+    pub lightness_contrast: f32,
+}
+
+impl Contrast {
+    /// Calculates the contrast of the text color on the background color.
+    ///
+    /// A transparent text color is blended onto the background first,
+    /// the alpha value of the background is ignored.
+    pub fn new(text: Color, background: Color) -> Self {
+        let text = blend(text, background);
+        Self {
+            ratio: contrast_ratio(text, background),
+            lightness_contrast: apca_contrast(text, background),
+        }
+    }
+
+    /// Whether the contrast ratio satisfies the WCAG 2.x level for text of the given size.
+    pub fn passes(&self, level: WcagLevel, size: TextSize) -> bool {
+        let minimum = match (level, size) {
+            (WcagLevel::Aa, TextSize::Normal) => 4.5,
+            (WcagLevel::Aa, TextSize::Large) => 3.0,
+            (WcagLevel::Aaa, TextSize::Normal) => 7.0,
+            (WcagLevel::Aaa, TextSize::Large) => 4.5,
+        };
+        self.ratio >= minimum
+    }
+}
+
+/// Blends the (transparent) color onto the opaque background.
+fn blend(color: Color, background: Color) -> Color {
+    let mix = |value: f32, background: f32| value * color.alpha + background * (1.0 - color.alpha);
+    Color::unclamped(palette::Srgba::new(
+        mix(color.red, background.red),
+        mix(color.green, background.green),
+        mix(color.blue, background.blue),
+        1.0,
+    ))
+}
+
+/// Relative luminance of the color.
+///
+/// Based on <https://www.w3.org/TR/WCAG21/#dfn-relative-luminance>
+pub fn relative_luminance(color: Color) -> f32 {
+    let linear = |value: f32| {
+        if value <= 0.04045 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(color.red) + 0.7152 * linear(color.green) + 0.0722 * linear(color.blue)
+}
+
+/// WCAG 2.x contrast ratio between two colors.
+///
+/// The order of the colors does not matter.
+///
+/// Based on <https://www.w3.org/TR/WCAG21/#dfn-contrast-ratio>
+pub fn contrast_ratio(color: Color, other: Color) -> f32 {
+    let luminance = relative_luminance(color);
+    let other_luminance = relative_luminance(other);
+    (luminance.max(other_luminance) + 0.05) / (luminance.min(other_luminance) + 0.05)
+}
+
+/// APCA lightness contrast (Lc) of the text color on the background color.
+///
+/// Uses the constants of APCA-W3 0.0.98G-4g.
+///
+/// Based on <https://github.com/Myndex/apca-w3/blob/master/src/apca-w3.js>
+pub fn apca_contrast(text: Color, background: Color) -> f32 {
+    const BLACK_THRESHOLD: f32 = 0.022;
+    const BLACK_CLAMP: f32 = 1.414;
+    const DELTA_Y_MIN: f32 = 0.0005;
+    const SCALE: f32 = 1.14;
+    const OFFSET: f32 = 0.027;
+    const LOW_CLIP: f32 = 0.1;
+
+    // screen luminance, using a simple power curve instead of the piecewise sRGB transfer function,
+    // which is mirrored for negative values of colors outside of the sRGB gamut
+    let linear = |value: f32| value.abs().powf(2.4).copysign(value);
+    let luminance = |color: Color| {
+        let y = (0.2126729 * linear(color.red)
+            + 0.7151522 * linear(color.green)
+            + 0.0721750 * linear(color.blue))
+        .max(0.0);
+        // soft clamp near black
+        if y < BLACK_THRESHOLD {
+            y + (BLACK_THRESHOLD - y).powf(BLACK_CLAMP)
+        } else {
+            y
+        }
+    };
+    let text_y = luminance(text);
+    let background_y = luminance(background);
+
+    if (background_y - text_y).abs() < DELTA_Y_MIN {
+        return 0.0;
+    }
+
+    let contrast = if background_y > text_y {
+        // dark text on a light background
+        let contrast = (background_y.powf(0.56) - text_y.powf(0.57)) * SCALE;
+        if contrast < LOW_CLIP {
+            0.0
+        } else {
+            contrast - OFFSET
+        }
+    } else {
+        // light text on a dark background
+        let contrast = (background_y.powf(0.65) - text_y.powf(0.62)) * SCALE;
+        if contrast > -LOW_CLIP {
+            0.0
+        } else {
+            contrast + OFFSET
+        }
+    };
+
+    contrast * 100.0
+}
+
+#[cfg(test)]
+mod calculate_contrast {
+    use super::*;
+
+    fn assert_close(expected: f32, actual: f32) {
+        assert!(
+            (expected - actual).abs() < 0.05,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn it_calculates_wcag_ratio() {
+        let black = Color::rgba(0, 0, 0, 255);
+        let white = Color::rgba(255, 255, 255, 255);
+        assert_close(21.0, contrast_ratio(black, white));
+        assert_close(21.0, contrast_ratio(white, black));
+        assert_close(1.0, contrast_ratio(white, white));
+        assert_close(4.54, contrast_ratio(Color::rgba(118, 118, 118, 255), white));
+    }
+
+    #[test]
+    fn it_checks_wcag_levels() {
+        let white = Color::rgba(255, 255, 255, 255);
+        let contrast = Contrast::new(Color::rgba(118, 118, 118, 255), white);
+        assert!(contrast.passes(WcagLevel::Aa, TextSize::Normal));
+        assert!(contrast.passes(WcagLevel::Aaa, TextSize::Large));
+        assert!(!contrast.passes(WcagLevel::Aaa, TextSize::Normal));
+
+        // fully transparent text is invisible
+        let contrast = Contrast::new(Color::rgba(0, 0, 0, 0), white);
+        assert_close(1.0, contrast.ratio);
+        assert!(!contrast.passes(WcagLevel::Aa, TextSize::Large));
+    }
+
+    #[test]
+    fn it_calculates_apca() {
+        let black = Color::rgba(0, 0, 0, 255);
+        let white = Color::rgba(255, 255, 255, 255);
+        assert_close(106.04, apca_contrast(black, white));
+        assert_close(-107.88, apca_contrast(white, black));
+        assert_close(63.06, apca_contrast(Color::rgba(136, 136, 136, 255), white));
+        assert_close(0.0, apca_contrast(white, white));
+    }
+
+    #[test]
+    fn it_calculates_contrast_outside_of_gamut() {
+        let white = Color::rgba(255, 255, 255, 255);
+        let green = Color::unclamped(palette::Srgba::new(-0.2, 1.1, -0.1, 1.0));
+        let black = Color::unclamped(palette::Srgba::new(-0.1, -0.1, -0.1, 1.0));
+        for color in [green, black] {
+            let contrast = Contrast::new(color, white);
+            assert!(contrast.ratio.is_finite() && contrast.ratio >= 1.0);
+            assert!(contrast.lightness_contrast.is_finite());
+        }
+        assert!(Contrast::new(black, white).lightness_contrast > 100.0);
+    }
+}
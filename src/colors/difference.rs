@@ -0,0 +1,266 @@
+use palette::{
+    Lab,
+    color_difference::{Ciede2000, DeltaE},
+    white_point::Any,
+};
+
+use super::{color::Color, illuminant::ReferenceWhite};
+
+/// Formulas for the perceived difference between two colors.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Metric {
+    /// Euclidean distance in CIELAB, defined by the CIE in 1976.
+    Cie76,
+    /// CIE 1994 formula with the weighting factors for graphic arts.
+    Cie94,
+    /// CIEDE2000, the current CIE recommendation.
+    #[default]
+    Ciede2000,
+    /// Formula of the Colour Measurement Committee with l:c of 2:1 (acceptability).
+    Cmc,
+    /// Euclidean distance in Oklab, as used by CSS.
+    Oklab,
+}
+
+//Convert from U32. Needed for converting from the settings AdwComboRow, which use indexes for values.
+impl From<u32> for Metric {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => Self::Cie76,
+            1 => Self::Cie94,
+            3 => Self::Cmc,
+            4 => Self::Oklab,
+            _ => Self::Ciede2000,
+        }
+    }
+}
+
+/// Differences (ΔE) between a reference and a sample color.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Difference {
+    pub cie76: f32,
+    pub cie94: f32,
+    pub ciede2000: f32,
+    pub cmc: f32,
+    /// Oklab ΔE, which is roughly 100 times smaller than the CIELAB based values.
+    pub oklab: f32,
+}
+
+impl Difference {
+    /// Calculates the difference of the sample to the reference color.
+    ///
+    /// CIELAB values are calculated with the given reference white. The alpha values are ignored.
+    pub fn new(reference: Color, sample: Color, white: ReferenceWhite) -> Self {
+        let reference_lab = reference.to_lab(white);
+        let sample_lab = sample.to_lab(white);
+        Self {
+            cie76: cie76(reference_lab, sample_lab),
+            cie94: cie94(reference_lab, sample_lab),
+            ciede2000: ciede2000(reference_lab, sample_lab),
+            cmc: cmc(reference_lab, sample_lab, 2.0, 1.0),
+            oklab: oklab(reference, sample),
+        }
+    }
+
+    /// Returns the difference calculated with the metric.
+    pub fn get(&self, metric: Metric) -> f32 {
+        match metric {
+            Metric::Cie76 => self.cie76,
+            Metric::Cie94 => self.cie94,
+            Metric::Ciede2000 => self.ciede2000,
+            Metric::Cmc => self.cmc,
+            Metric::Oklab => self.oklab,
+        }
+    }
+}
+
+/// CIE76 color difference, the euclidean distance between the CIELAB values.
+pub fn cie76(reference: Lab<Any, f32>, sample: Lab<Any, f32>) -> f32 {
+    reference.delta_e(sample)
+}
+
+/// CIE94 color difference, using the weighting factors for graphic arts.
+///
+/// The formula is not symmetric, the chroma and hue weights are based on the reference color.
+pub fn cie94(reference: Lab<Any, f32>, sample: Lab<Any, f32>) -> f32 {
+    let (k_lightness, k_1, k_2) = (1.0, 0.045, 0.015);
+
+    let chroma_reference = reference.a.hypot(reference.b);
+    let chroma_sample = sample.a.hypot(sample.b);
+
+    let delta_lightness = reference.l - sample.l;
+    let delta_chroma = chroma_reference - chroma_sample;
+    let delta_hue_squared = ((reference.a - sample.a).powi(2) + (reference.b - sample.b).powi(2)
+        - delta_chroma.powi(2))
+    .max(0.0);
+
+    let chroma_weight = 1.0 + k_1 * chroma_reference;
+    let hue_weight = 1.0 + k_2 * chroma_reference;
+
+    ((delta_lightness / k_lightness).powi(2)
+        + (delta_chroma / chroma_weight).powi(2)
+        + delta_hue_squared / hue_weight.powi(2))
+    .sqrt()
+}
+
+/// CIEDE2000 color difference.
+///
+/// Based on Sharma, Wu and Dalal (2005), The CIEDE2000 Color-Difference Formula.
+pub fn ciede2000(reference: Lab<Any, f32>, sample: Lab<Any, f32>) -> f32 {
+    reference.difference(sample)
+}
+
+/// CMC l:c color difference, with the lightness and chroma weights.
+///
+/// Commonly used weights are 2:1 for acceptability and 1:1 for perceptibility.
+/// The formula is not symmetric, the weights are based on the reference color.
+pub fn cmc(
+    reference: Lab<Any, f32>,
+    sample: Lab<Any, f32>,
+    lightness_weight: f32,
+    chroma_weight: f32,
+) -> f32 {
+    let chroma_reference = reference.a.hypot(reference.b);
+    let chroma_sample = sample.a.hypot(sample.b);
+    let hue = reference
+        .b
+        .atan2(reference.a)
+        .to_degrees()
+        .rem_euclid(360.0);
+
+    let delta_lightness = reference.l - sample.l;
+    let delta_chroma = chroma_reference - chroma_sample;
+    let delta_hue_squared = ((reference.a - sample.a).powi(2) + (reference.b - sample.b).powi(2)
+        - delta_chroma.powi(2))
+    .max(0.0);
+
+    let s_lightness = if reference.l < 16.0 {
+        0.511
+    } else {
+        0.040975 * reference.l / (1.0 + 0.01765 * reference.l)
+    };
+    let s_chroma = 0.0638 * chroma_reference / (1.0 + 0.0131 * chroma_reference) + 0.638;
+    let f = (chroma_reference.powi(4) / (chroma_reference.powi(4) + 1900.0)).sqrt();
+    let t = if (164.0..=345.0).contains(&hue) {
+        0.56 + (0.2 * (hue + 168.0).to_radians().cos()).abs()
+    } else {
+        0.36 + (0.4 * (hue + 35.0).to_radians().cos()).abs()
+    };
+    let s_hue = s_chroma * (f * t + 1.0 - f);
+
+    ((delta_lightness / (lightness_weight * s_lightness)).powi(2)
+        + (delta_chroma / (chroma_weight * s_chroma)).powi(2)
+        + delta_hue_squared / s_hue.powi(2))
+    .sqrt()
+}
+
+/// Oklab color difference, the euclidean distance between the Oklab values.
+///
+/// Based on <https://www.w3.org/TR/css-color-4/#color-difference-OK>
+pub fn oklab(reference: Color, sample: Color) -> f32 {
+    let reference = reference.to_oklab();
+    let sample = sample.to_oklab();
+    ((reference.l - sample.l).powi(2)
+        + (reference.a - sample.a).powi(2)
+        + (reference.b - sample.b).powi(2))
+    .sqrt()
+}
+
+#[cfg(test)]
+mod calculate_difference {
+    use super::*;
+
+    fn lab(l: f32, a: f32, b: f32) -> Lab<Any, f32> {
+        Lab::new(l, a, b)
+    }
+
+    fn assert_close(expected: f32, actual: f32) {
+        assert!(
+            (expected - actual).abs() < 0.0005,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn it_calculates_cie76() {
+        assert_close(5.0, cie76(lab(50.0, 3.0, 0.0), lab(50.0, 0.0, 4.0)));
+    }
+
+    #[test]
+    fn it_calculates_cie94() {
+        // pair 1 of the CIEDE2000 test data by Sharma et al.
+        assert_close(
+            1.3950,
+            cie94(lab(50.0, 2.6772, -79.7751), lab(50.0, 0.0, -82.7485)),
+        );
+    }
+
+    #[test]
+    fn it_calculates_ciede2000() {
+        // test data from Sharma, Wu and Dalal (2005)
+        for (reference, sample, expected) in [
+            (
+                lab(50.0, 2.6772, -79.7751),
+                lab(50.0, 0.0, -82.7485),
+                2.0425,
+            ),
+            (lab(50.0, 2.5, 0.0), lab(73.0, 25.0, -18.0), 27.1492),
+            (
+                lab(60.2574, -34.0099, 36.2677),
+                lab(60.4626, -34.1751, 39.4387),
+                1.2644,
+            ),
+            (
+                lab(2.0776, 0.0795, -1.135),
+                lab(0.9033, -0.0636, -0.5514),
+                0.9082,
+            ),
+        ] {
+            assert_close(expected, ciede2000(reference, sample));
+        }
+    }
+
+    #[test]
+    fn it_calculates_cmc() {
+        assert_close(
+            1.7387,
+            cmc(
+                lab(50.0, 2.6772, -79.7751),
+                lab(50.0, 0.0, -82.7485),
+                2.0,
+                1.0,
+            ),
+        );
+    }
+
+    #[test]
+    fn it_weights_cmc_lightness() {
+        // pair 2 of the CIEDE2000 test data by Sharma et al., which differs in lightness
+        let reference = lab(50.0, 2.5, 0.0);
+        let sample = lab(73.0, 25.0, -18.0);
+        assert_close(37.9233, cmc(reference, sample, 2.0, 1.0));
+        assert_close(42.1088, cmc(reference, sample, 1.0, 1.0));
+    }
+
+    #[test]
+    fn it_has_no_difference_for_equal_colors() {
+        let color = Color::rgba(53, 132, 228, 255);
+        let difference = Difference::new(color, color, ReferenceWhite::default());
+        for metric in [
+            Metric::Cie76,
+            Metric::Cie94,
+            Metric::Ciede2000,
+            Metric::Cmc,
+            Metric::Oklab,
+        ] {
+            assert_close(0.0, difference.get(metric));
+        }
+    }
+
+    #[test]
+    fn it_calculates_oklab() {
+        let black = Color::rgba(0, 0, 0, 255);
+        let white = Color::rgba(255, 255, 255, 255);
+        assert!((1.0 - oklab(black, white)).abs() < 0.001);
+    }
+}
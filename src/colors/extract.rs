@@ -0,0 +1,201 @@
+use palette::{IntoColor, LinSrgb, Oklab, WithAlpha};
+
+use super::color::Color;
+
+/// Number of k-means iterations refining the palette found by median cut.
+const ITERATIONS: usize = 8;
+
+/// A color of an extracted palette.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Swatch {
+    pub color: Color,
+    /// Share of the colors, which are closest to this color, between 0.0 and 1.0.
+    pub coverage: f32,
+}
+
+/// A group of colors in Oklab, which is split along its widest channel by median cut.
+#[derive(Clone)]
+struct ColorBox {
+    colors: Vec<[f32; 3]>,
+}
+
+impl ColorBox {
+    /// The widest channel and its range.
+    fn widest_channel(&self) -> (usize, f32) {
+        (0..3)
+            .map(|channel| {
+                let (min, max) = self
+                    .colors
+                    .iter()
+                    .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), color| {
+                        (min.min(color[channel]), max.max(color[channel]))
+                    });
+                (channel, max - min)
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap_or_default()
+    }
+
+    /// How much splitting the box improves the palette, larger boxes with more colors are
+    /// split first.
+    fn priority(&self) -> f32 {
+        self.widest_channel().1 * self.colors.len() as f32
+    }
+
+    /// Splits the box at the median of its widest channel.
+    fn split(mut self) -> (ColorBox, ColorBox) {
+        let (channel, _) = self.widest_channel();
+        let median = self.colors.len() / 2;
+        self.colors
+            .select_nth_unstable_by(median, |a, b| a[channel].total_cmp(&b[channel]));
+        let upper = self.colors.split_off(median);
+        (self, ColorBox { colors: upper })
+    }
+
+    fn mean(&self) -> [f32; 3] {
+        let sum = self.colors.iter().fold([0.0; 3], |sum, color| {
+            [sum[0] + color[0], sum[1] + color[1], sum[2] + color[2]]
+        });
+        sum.map(|value| value / self.colors.len() as f32)
+    }
+}
+
+/// The squared Euclidean distance between two Oklab colors.
+fn distance(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+/// Extracts a palette of up to `count` colors, using median cut and k-means in Oklab.
+///
+/// The colors are repeatedly split at the median of their widest Oklab channel, until
+/// there are `count` groups. Their means are then refined with k-means, so that each color
+/// belongs to the closest palette color. Fully transparent colors are ignored.
+/// The palette is sorted by coverage, starting with the most common color.
+pub fn extract_palette(colors: impl IntoIterator<Item = Color>, count: usize) -> Vec<Swatch> {
+    let colors = colors
+        .into_iter()
+        .filter(|color| color.alpha > 0.0)
+        .map(|color| {
+            let oklab = color.to_oklab();
+            [oklab.l, oklab.a, oklab.b]
+        })
+        .collect::<Vec<[f32; 3]>>();
+    let total = colors.len();
+    if total == 0 || count == 0 {
+        return Vec::new();
+    }
+
+    let mut boxes = vec![ColorBox { colors }];
+    while boxes.len() < count {
+        let Some((index, _)) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, color_box)| color_box.colors.len() > 1)
+            .map(|(index, color_box)| (index, color_box.priority()))
+            .filter(|(_, priority)| *priority > 0.0)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        else {
+            // all remaining boxes contain a single color
+            break;
+        };
+        let (lower, upper) = boxes.swap_remove(index).split();
+        boxes.push(lower);
+        boxes.push(upper);
+    }
+
+    let mut centroids = boxes.iter().map(ColorBox::mean).collect::<Vec<[f32; 3]>>();
+    let colors = boxes
+        .into_iter()
+        .flat_map(|color_box| color_box.colors)
+        .collect::<Vec<[f32; 3]>>();
+    let mut clusters = Vec::new();
+    for _ in 0..ITERATIONS {
+        clusters = vec![ColorBox { colors: Vec::new() }; centroids.len()];
+        for color in &colors {
+            let nearest = centroids
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| distance(a, color).total_cmp(&distance(b, color)))
+                .map(|(index, _)| index)
+                .unwrap_or_default();
+            clusters[nearest].colors.push(*color);
+        }
+        clusters.retain(|cluster| !cluster.colors.is_empty());
+
+        let previous = std::mem::replace(
+            &mut centroids,
+            clusters.iter().map(ColorBox::mean).collect(),
+        );
+        if previous == centroids {
+            break;
+        }
+    }
+
+    let mut palette = clusters
+        .iter()
+        .zip(centroids)
+        .map(|(cluster, [l, a, b])| {
+            let linear: LinSrgb = Oklab::new(l, a, b).into_color();
+            Swatch {
+                color: Color::from_linear(linear.with_alpha(1.0)),
+                coverage: cluster.colors.len() as f32 / total as f32,
+            }
+        })
+        .collect::<Vec<Swatch>>();
+    palette.sort_by(|a, b| b.coverage.total_cmp(&a.coverage));
+    palette
+}
+
+#[cfg(test)]
+mod extract_colors {
+    use super::*;
+
+    fn repeat(color: Color, count: usize) -> impl Iterator<Item = Color> {
+        std::iter::repeat_n(color, count)
+    }
+
+    #[test]
+    fn it_extracts_distinct_colors() {
+        let red = Color::rgba(255, 0, 0, 255);
+        let blue = Color::rgba(0, 0, 255, 255);
+        let colors = repeat(red, 75).chain(repeat(blue, 25));
+        let palette = extract_palette(colors, 2);
+
+        assert_eq!(2, palette.len());
+        assert_eq!(red.into_format::<u8, u8>(), palette[0].color.into_format());
+        assert_eq!(0.75, palette[0].coverage);
+        assert_eq!(blue.into_format::<u8, u8>(), palette[1].color.into_format());
+        assert_eq!(0.25, palette[1].coverage);
+    }
+
+    #[test]
+    fn it_stops_at_the_number_of_colors() {
+        let colors = repeat(Color::rgba(46, 52, 64, 255), 10);
+        let palette = extract_palette(colors, 5);
+        assert_eq!(1, palette.len());
+        assert_eq!(1.0, palette[0].coverage);
+    }
+
+    #[test]
+    fn it_ignores_transparent_colors() {
+        let colors =
+            repeat(Color::rgba(0, 255, 0, 0), 10).chain(repeat(Color::rgba(255, 255, 255, 255), 2));
+        let palette = extract_palette(colors, 3);
+        assert_eq!(1, palette.len());
+        assert!(extract_palette(repeat(Color::rgba(0, 0, 0, 0), 3), 3).is_empty());
+    }
+
+    #[test]
+    fn it_covers_all_colors() {
+        let colors = (0..=255u8).map(|value| Color::rgba(value, 255 - value, value / 2, 255));
+        let palette = extract_palette(colors, 6);
+        assert_eq!(6, palette.len());
+        let coverage = palette.iter().map(|swatch| swatch.coverage).sum::<f32>();
+        assert!((coverage - 1.0).abs() < 1e-5);
+        assert!(
+            palette
+                .windows(2)
+                .all(|pair| pair[0].coverage >= pair[1].coverage)
+        );
+    }
+}
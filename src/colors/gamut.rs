@@ -0,0 +1,160 @@
+use palette::{LinSrgb, Oklch, WithAlpha, convert::IntoColorUnclamped};
+
+use super::{color::Color, difference};
+
+/// Just noticeable difference in Oklab, below which a clipped color is accepted.
+const JND: f32 = 0.02;
+/// Precision of the chroma search.
+const CHROMA_EPSILON: f32 = 0.0001;
+
+/// Methods of mapping colors outside of the sRGB gamut into it.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum GamutMapping {
+    /// Reduces the chroma in Oklch, keeping the lightness and hue of the color.
+    ///
+    /// Based on the CSS gamut mapping algorithm <https://www.w3.org/TR/css-color-4/#binsearch>
+    #[default]
+    Chroma,
+    /// Clamps each sRGB value between 0.0 and 1.0.
+    Clip,
+}
+
+//Convert from U32. Needed for converting from the settings AdwComboRow, which use indexes for values.
+impl From<u32> for GamutMapping {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::Clip,
+            _ => Self::Chroma,
+        }
+    }
+}
+
+impl GamutMapping {
+    /// Maps the color into the sRGB gamut.
+    ///
+    /// Colors that are already in the gamut are returned unchanged.
+    pub fn apply(self, color: Color) -> Color {
+        if color.is_in_gamut() {
+            return color;
+        }
+
+        match self {
+            GamutMapping::Chroma => reduce_chroma(color),
+            GamutMapping::Clip => color.clip(),
+        }
+    }
+}
+
+/// Searches for the highest chroma with the same lightness and hue, at which the clipped
+/// color is not noticeably different from the unclipped one.
+fn reduce_chroma(color: Color) -> Color {
+    let origin: Oklch = color.to_oklab().into_color_unclamped();
+    let alpha = color.alpha;
+
+    if origin.l >= 1.0 {
+        return Color::rgba(255, 255, 255, (alpha * 255.0).round() as u8);
+    }
+    if origin.l <= 0.0 {
+        return Color::rgba(0, 0, 0, (alpha * 255.0).round() as u8);
+    }
+
+    let with_chroma = |chroma: f32| {
+        let linear: LinSrgb = Oklch::new(origin.l, chroma, origin.hue).into_color_unclamped();
+        Color::from_linear(linear.with_alpha(alpha))
+    };
+
+    let mut current = color;
+    if difference::oklab(current.clip(), current) < JND {
+        return current.clip();
+    }
+
+    let (mut min, mut max) = (0.0, origin.chroma);
+    let mut min_in_gamut = true;
+    while max - min > CHROMA_EPSILON {
+        let chroma = (min + max) / 2.0;
+        current = with_chroma(chroma);
+
+        if min_in_gamut && current.is_in_gamut() {
+            min = chroma;
+            continue;
+        }
+
+        let clipped = current.clip();
+        let error = difference::oklab(clipped, current);
+        if error < JND {
+            if JND - error < CHROMA_EPSILON {
+                return clipped;
+            }
+            min_in_gamut = false;
+            min = chroma;
+        } else {
+            max = chroma;
+        }
+    }
+    current.clip()
+}
+
+#[cfg(test)]
+mod map_gamut {
+    use palette::Oklab;
+
+    use super::*;
+    use crate::colors::rgb_space::RgbSpace;
+
+    const METHODS: [GamutMapping; 2] = [GamutMapping::Chroma, GamutMapping::Clip];
+
+    #[test]
+    fn it_keeps_colors_in_gamut() {
+        let color = Color::rgba(46, 52, 64, 255);
+        for method in METHODS {
+            assert_eq!(color, method.apply(color));
+        }
+    }
+
+    #[test]
+    fn it_maps_into_gamut() {
+        let red = RgbSpace::DisplayP3.to_color([1.0, 0.0, 0.0], 0.5);
+        let green = RgbSpace::Rec2020.to_color([0.0, 1.0, 0.0], 1.0);
+        for color in [red, green] {
+            for method in METHODS {
+                let mapped = method.apply(color);
+                assert!(mapped.is_in_gamut());
+                assert_eq!(color.alpha, mapped.alpha);
+            }
+        }
+    }
+
+    #[test]
+    fn it_clips_values() {
+        let color = Color::unclamped(palette::Srgba::new(1.2, 0.5, -0.1, 1.0));
+        let clipped = GamutMapping::Clip.apply(color);
+        assert_eq!((1.0, 0.5, 0.0), (clipped.red, clipped.green, clipped.blue));
+    }
+
+    #[test]
+    fn it_keeps_lightness_and_hue() {
+        let color = RgbSpace::Rec2020.to_color([0.0, 1.0, 0.0], 1.0);
+        let origin: Oklch = color.to_oklab().into_color_unclamped();
+        let mapped: Oklch = GamutMapping::Chroma
+            .apply(color)
+            .to_oklab()
+            .into_color_unclamped();
+
+        // the final clipping may change the color by up to the JND
+        assert!((origin.l - mapped.l).abs() < JND);
+        assert!(
+            (origin.hue.into_positive_degrees() - mapped.hue.into_positive_degrees()).abs() < 5.0
+        );
+        assert!(mapped.chroma < origin.chroma);
+    }
+
+    #[test]
+    fn it_maps_lightness_extremes() {
+        let linear: LinSrgb = Oklab::new(1.2, 0.1, 0.0).into_color_unclamped();
+        let color = Color::from_linear(linear.with_alpha(1.0));
+        assert_eq!(
+            Color::rgba(255, 255, 255, 255),
+            GamutMapping::Chroma.apply(color)
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use std::fmt::Write;
+
+use super::{
+    color::Color,
+    gamut::GamutMapping,
+    mix::{self, HueInterpolation, MixSpace},
+};
+
+/// Number of colors sampled between two stops, when the gradient is exported to a format,
+/// which cannot interpolate in the color space of the gradient.
+const SAMPLES_PER_STOP: usize = 8;
+
+/// Formats, in which a gradient can be exported.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum GradientFormat {
+    /// CSS `linear-gradient()` from left to right.
+    #[default]
+    LinearCss,
+    /// CSS `radial-gradient()` from the center outwards.
+    RadialCss,
+    /// SVG `<linearGradient>` from left to right.
+    Svg,
+}
+
+//Convert from U32. Needed for converting from the settings AdwComboRow, which use indexes for values.
+impl From<u32> for GradientFormat {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::RadialCss,
+            2 => Self::Svg,
+            _ => Self::LinearCss,
+        }
+    }
+}
+
+/// A gradient between multiple colors, which are evenly distributed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    stops: Vec<Color>,
+    space: MixSpace,
+    hue_interpolation: HueInterpolation,
+}
+
+impl Gradient {
+    /// Creates a gradient through the stops, which is interpolated in the space.
+    pub fn new(stops: Vec<Color>, space: MixSpace, hue_interpolation: HueInterpolation) -> Self {
+        Self {
+            stops,
+            space,
+            hue_interpolation,
+        }
+    }
+
+    pub fn stops(&self) -> &[Color] {
+        &self.stops
+    }
+
+    /// Returns the color at the position, between 0.0 at the first and 1.0 at the last stop.
+    ///
+    /// The result is not mapped into the sRGB gamut.
+    pub fn sample(&self, position: f32) -> Color {
+        match self.stops.len() {
+            0 => return Color::default(),
+            1 => return self.stops[0],
+            _ => {}
+        }
+
+        let scaled = position.clamp(0.0, 1.0) * (self.stops.len() - 1) as f32;
+        let index = (scaled.floor() as usize).min(self.stops.len() - 2);
+        mix::mix(
+            self.stops[index],
+            self.stops[index + 1],
+            scaled - index as f32,
+            self.space,
+            self.hue_interpolation,
+        )
+    }
+
+    /// Samples the given number of evenly spaced colors, including the first and last stop.
+    pub fn palette(&self, count: usize) -> Vec<Color> {
+        match count {
+            0 => Vec::new(),
+            1 => vec![self.sample(0.0)],
+            _ => (0..count)
+                .map(|index| self.sample(index as f32 / (count - 1) as f32))
+                .collect(),
+        }
+    }
+
+    /// Colors and their positions, which approximate the gradient when interpolated in sRGB.
+    ///
+    /// Only the stops are needed if the gradient is interpolated in sRGB, otherwise colors
+    /// between the stops are sampled.
+    fn srgb_stops(&self) -> Vec<(f32, Color)> {
+        let count = if self.space == MixSpace::Srgb || self.stops.len() < 2 {
+            self.stops.len()
+        } else {
+            (self.stops.len() - 1) * SAMPLES_PER_STOP + 1
+        };
+        self.palette(count)
+            .into_iter()
+            .enumerate()
+            .map(|(index, color)| (index as f32 / (count.max(2) - 1) as f32, color))
+            .collect()
+    }
+
+    /// The CSS `<color-interpolation-method>` of the gradient, or [`None`] for sRGB,
+    /// which is used by default.
+    fn interpolation_method(&self) -> Option<String> {
+        match self.space {
+            MixSpace::Srgb => None,
+            space if space.is_polar() && self.hue_interpolation != HueInterpolation::Shorter => {
+                Some(format!(
+                    "in {} {} hue",
+                    space.css_name(),
+                    self.hue_interpolation.css_name()
+                ))
+            }
+            space => Some(format!("in {}", space.css_name())),
+        }
+    }
+
+    /// Exports the gradient in the format.
+    ///
+    /// CSS gradients use the interpolation method of the gradient, so only the stops are listed.
+    /// SVG can only interpolate in sRGB, so colors between the stops are sampled.
+    pub fn export(&self, format: GradientFormat, gamut_mapping: GamutMapping) -> String {
+        let css_color = |color: Color| {
+            let color = gamut_mapping.apply(color);
+            if color.alpha < 1.0 {
+                color.hex()
+            } else {
+                color.to_string()
+            }
+        };
+
+        match format {
+            GradientFormat::LinearCss | GradientFormat::RadialCss => {
+                let (function, shape) = match format {
+                    GradientFormat::RadialCss => ("radial-gradient", "circle"),
+                    _ => ("linear-gradient", "to right"),
+                };
+                let method = self
+                    .interpolation_method()
+                    .map(|method| format!(" {}", method))
+                    .unwrap_or_default();
+                let stops = self
+                    .stops
+                    .iter()
+                    .map(|color| css_color(*color))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{}({}{}, {})", function, shape, method, stops)
+            }
+            GradientFormat::Svg => {
+                let mut svg = String::from("<linearGradient id=\"gradient\">\n");
+                for (offset, color) in self.srgb_stops() {
+                    let color = gamut_mapping.apply(color);
+                    let _ = write!(
+                        svg,
+                        "  <stop offset=\"{}%\" stop-color=\"{}\"",
+                        (offset * 1000.0).round() / 10.0,
+                        color
+                    );
+                    if color.alpha < 1.0 {
+                        let _ = write!(svg, " stop-opacity=\"{:.2}\"", color.alpha);
+                    }
+                    svg.push_str("/>\n");
+                }
+                svg.push_str("</linearGradient>");
+                svg
+            }
+        }
+    }
+
+    /// A linear gradient from left to right, which GTK can render.
+    ///
+    /// GTK does not support interpolation methods, so colors between the stops are sampled.
+    pub fn preview_css(&self, gamut_mapping: GamutMapping) -> String {
+        let stops = self
+            .srgb_stops()
+            .into_iter()
+            .map(|(offset, color)| {
+                let rgba = gamut_mapping.apply(color).into_format::<u8, f32>();
+                format!(
+                    "rgba({}, {}, {}, {}) {}%",
+                    rgba.red,
+                    rgba.green,
+                    rgba.blue,
+                    rgba.alpha,
+                    offset * 100.0
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("linear-gradient(to right, {})", stops)
+    }
+}
+
+#[cfg(test)]
+mod build_gradient {
+    use super::*;
+
+    fn gradient(space: MixSpace) -> Gradient {
+        Gradient::new(
+            vec![
+                Color::rgba(255, 0, 0, 255),
+                Color::rgba(0, 255, 0, 255),
+                Color::rgba(0, 0, 255, 255),
+            ],
+            space,
+            HueInterpolation::Shorter,
+        )
+    }
+
+    #[test]
+    fn it_samples_stops() {
+        let gradient = gradient(MixSpace::Oklab);
+        let bytes = |color: Color| color.into_format::<u8, u8>();
+        assert_eq!(bytes(gradient.stops()[0]), bytes(gradient.sample(0.0)));
+        assert_eq!(bytes(gradient.stops()[2]), bytes(gradient.sample(1.0)));
+
+        let middle = gradient.sample(0.5);
+        assert!((middle.green - 1.0).abs() < 0.001);
+        assert!(middle.red.abs() < 0.001 && middle.blue.abs() < 0.001);
+    }
+
+    #[test]
+    fn it_samples_palette() {
+        let palette = gradient(MixSpace::Srgb).palette(5);
+        assert_eq!(5, palette.len());
+        assert_eq!(
+            Color::unclamped(palette::Srgba::new(0.5, 0.5, 0.0, 1.0)),
+            palette[1]
+        );
+        assert!(gradient(MixSpace::Srgb).palette(0).is_empty());
+    }
+
+    #[test]
+    fn it_exports_css() {
+        assert_eq!(
+            "linear-gradient(to right, #ff0000, #00ff00, #0000ff)",
+            gradient(MixSpace::Srgb).export(GradientFormat::LinearCss, GamutMapping::Clip)
+        );
+        assert_eq!(
+            "radial-gradient(circle in oklab, #ff0000, #00ff00, #0000ff)",
+            gradient(MixSpace::Oklab).export(GradientFormat::RadialCss, GamutMapping::Clip)
+        );
+
+        let mut gradient = gradient(MixSpace::Oklch);
+        gradient.hue_interpolation = HueInterpolation::Longer;
+        assert_eq!(
+            "linear-gradient(to right in oklch longer hue, #ff0000, #00ff00, #0000ff)",
+            gradient.export(GradientFormat::LinearCss, GamutMapping::Clip)
+        );
+    }
+
+    #[test]
+    fn it_exports_svg() {
+        let svg = gradient(MixSpace::Srgb).export(GradientFormat::Svg, GamutMapping::Clip);
+        assert_eq!(
+            "<linearGradient id=\"gradient\">\n  <stop offset=\"0%\" stop-color=\"#ff0000\"/>\n  <stop offset=\"50%\" stop-color=\"#00ff00\"/>\n  <stop offset=\"100%\" stop-color=\"#0000ff\"/>\n</linearGradient>",
+            svg
+        );
+
+        // other spaces are approximated by sampling between the stops
+        let svg = gradient(MixSpace::Oklab).export(GradientFormat::Svg, GamutMapping::Clip);
+        assert_eq!(2 * SAMPLES_PER_STOP + 1, svg.matches("<stop").count());
+    }
+}
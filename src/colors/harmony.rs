@@ -0,0 +1,137 @@
+use palette::{Hsla, IntoColor, Oklcha, ShiftHue};
+
+use super::color::Color;
+
+/// Color harmonies, which combine colors at fixed angles on the color wheel.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Harmony {
+    /// The color opposite on the color wheel.
+    #[default]
+    Complementary,
+    /// Three colors evenly spaced around the color wheel.
+    Triadic,
+    /// The neighboring colors on the color wheel.
+    Analogous,
+    /// The two neighbors of the complementary color.
+    SplitComplementary,
+    /// Two pairs of complementary colors, forming a rectangle on the color wheel.
+    Tetradic,
+}
+
+impl Harmony {
+    /// The hue rotations in degrees, starting with the base color.
+    pub fn hue_offsets(&self) -> &'static [f32] {
+        match self {
+            Harmony::Complementary => &[0.0, 180.0],
+            Harmony::Triadic => &[0.0, 120.0, 240.0],
+            Harmony::Analogous => &[0.0, -30.0, 30.0],
+            Harmony::SplitComplementary => &[0.0, 150.0, 210.0],
+            Harmony::Tetradic => &[0.0, 60.0, 180.0, 240.0],
+        }
+    }
+
+    /// Generates the colors of the harmony, starting with the given color.
+    ///
+    /// The hue is rotated in the given color space, while the remaining components,
+    /// including the alpha value, stay the same.
+    pub fn colors(&self, color: Color, space: HueSpace) -> Vec<Color> {
+        self.hue_offsets()
+            .iter()
+            .map(|&degrees| space.rotate_hue(color, degrees))
+            .collect()
+    }
+}
+
+//Convert from U32. Needed for converting from the settings AdwComboRow, which use indexes for values.
+impl From<u32> for Harmony {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::Triadic,
+            2 => Self::Analogous,
+            3 => Self::SplitComplementary,
+            4 => Self::Tetradic,
+            _ => Self::Complementary,
+        }
+    }
+}
+
+/// Color spaces, in which the hue of a color can be rotated.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum HueSpace {
+    /// HSL, as used by the color editor.
+    #[default]
+    Hsl,
+    /// Oklch, which keeps the perceived lightness and chroma of the rotated colors.
+    Oklch,
+}
+
+impl HueSpace {
+    /// Rotates the hue of the color by the given degrees.
+    ///
+    /// Colors outside of the sRGB gamut are clamped.
+    pub fn rotate_hue(&self, color: Color, degrees: f32) -> Color {
+        if degrees == 0.0 {
+            return color;
+        }
+
+        match self {
+            HueSpace::Hsl => {
+                let hsl: Hsla = (*color).into_color();
+                Color::from_palette(hsl.shift_hue(degrees))
+            }
+            HueSpace::Oklch => {
+                let oklch: Oklcha = (*color).into_color();
+                Color::from_palette(oklch.shift_hue(degrees))
+            }
+        }
+    }
+}
+
+//Convert from U32. Needed for converting from the settings AdwComboRow, which use indexes for values.
+impl From<u32> for HueSpace {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::Oklch,
+            _ => Self::Hsl,
+        }
+    }
+}
+
+#[cfg(test)]
+mod generate_harmony {
+    use super::*;
+
+    fn hex(colors: Vec<Color>) -> Vec<String> {
+        colors.iter().map(Color::hex).collect()
+    }
+
+    #[test]
+    fn it_rotates_hsl() {
+        let red = Color::rgba(255, 0, 0, 255);
+        assert_eq!(
+            vec!["#ff0000ff", "#00ffffff"],
+            hex(Harmony::Complementary.colors(red, HueSpace::Hsl))
+        );
+        assert_eq!(
+            vec!["#ff0000ff", "#00ff00ff", "#0000ffff"],
+            hex(Harmony::Triadic.colors(red, HueSpace::Hsl))
+        );
+        assert_eq!(4, Harmony::Tetradic.colors(red, HueSpace::Hsl).len());
+    }
+
+    #[test]
+    fn it_rotates_oklch() {
+        let color = Color::rgba(120, 130, 140, 128);
+        let complementary = HueSpace::Oklch.rotate_hue(color, 180.0);
+
+        let oklch: Oklcha = (*color).into_color();
+        let rotated: Oklcha = (*complementary).into_color();
+        assert!((oklch.l - rotated.l).abs() < 0.01);
+        assert!((oklch.chroma - rotated.chroma).abs() < 0.01);
+        let difference = (oklch.hue.into_positive_degrees() + 180.0
+            - rotated.hue.into_positive_degrees())
+        .rem_euclid(360.0);
+        assert!(!(1.0..=359.0).contains(&difference), "{difference}");
+        assert_eq!(color.alpha, complementary.alpha);
+    }
+}
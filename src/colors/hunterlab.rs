@@ -1,93 +1,57 @@
-use std::marker::PhantomData;
+use palette::{Xyz, white_point::Any};
 
-use palette::{
-    Clamp, WithAlpha, Xyz,
-    convert::FromColorUnclamped,
-    white_point::{D65, WhitePoint},
-};
-
-/// HunterLab with an alpha component.
+/// Hunter Lab color space.
 ///
 /// Based on <https://www.easyrgb.com/en/math.php>
-#[derive(Debug, FromColorUnclamped, WithAlpha)]
-#[palette(skip_derives(Xyz))]
-pub struct HunterLab<Wp = D65> {
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HunterLab {
+    /// The lightness of the color, where 0.0 is black and 100.0 is white.
     pub l: f32,
-    /// The amount of magenta ink, where 0.0 is no magenta ink and 1.0 is the maximum amount.
+    /// The red/green opponent, where negative values are green and positive values are red.
     pub a: f32,
-    /// The amount of yellow ink, where 0.0 is no yellow ink and 1.0 is the maximum amount.
+    /// The yellow/blue opponent, where negative values are blue and positive values are yellow.
     pub b: f32,
-    /// The white point associated with the color's illuminant and observer.
-    /// D65 for 2 degree observer is used by default.
-    #[palette(unsafe_zero_sized)]
-    pub white_point: PhantomData<Wp>,
 }
 
-impl<Wp> HunterLab<Wp> {
+impl HunterLab {
     /// Create a HunterLab color
     pub const fn new(l: f32, a: f32, b: f32) -> Self {
-        Self {
-            l,
-            a,
-            b,
-            white_point: PhantomData,
-        }
+        Self { l, a, b }
     }
-}
 
-impl FromColorUnclamped<HunterLab> for HunterLab {
-    fn from_color_unclamped(color: HunterLab) -> HunterLab {
-        color
+    /// Returns the chromaticity coefficients for the given white point.
+    ///
+    /// The coefficients are defined for tristimulus values with `Y = 100`.
+    fn coefficients(white: Xyz<Any, f32>) -> (f32, f32) {
+        let ka = (175.0 / 198.04) * (white.x + white.y) * 100.0;
+        let kb = (70.0 / 218.11) * (white.y + white.z) * 100.0;
+        (ka, kb)
     }
-}
 
-impl<Wp> FromColorUnclamped<Xyz<Wp, f32>> for HunterLab<Wp>
-where
-    Wp: WhitePoint<f32>,
-{
-    fn from_color_unclamped(color: Xyz<Wp, f32>) -> Self {
-        let wp = Wp::get_xyz();
+    /// Convert XYZ values to Hunter Lab, relative to the given white point.
+    pub fn from_xyz(color: Xyz<Any, f32>, white: Xyz<Any, f32>) -> Self {
+        let (ka, kb) = Self::coefficients(white);
+        let y = color.y / white.y;
 
-        let ka = (175.0 / 198.04) * (wp.x + wp.y);
-        let kb = (70.0 / 218.11) * (wp.y + wp.z);
-
-        let l = 100.0 * f32::sqrt(color.y / wp.y);
-        let a = ka * (((color.x / wp.x) - (color.y / wp.y)) / f32::sqrt(color.y / wp.y));
-        let b = kb * (((color.y / wp.y) - (color.z / wp.z)) / f32::sqrt(color.y / wp.y));
+        let l = 100.0 * f32::sqrt(y);
+        let a = ka * (((color.x / white.x) - y) / f32::sqrt(y));
+        let b = kb * ((y - (color.z / white.z)) / f32::sqrt(y));
 
         Self {
             l,
             a: if a.is_nan() { 0.0 } else { a },
             b: if b.is_nan() { 0.0 } else { b },
-            white_point: PhantomData::<Wp>,
         }
     }
-}
-
-impl<Wp> FromColorUnclamped<HunterLab<Wp>> for Xyz<Wp, f32>
-where
-    Wp: WhitePoint<f32>,
-{
-    fn from_color_unclamped(color: HunterLab<Wp>) -> Self {
-        let wp = Wp::get_xyz();
-        let ka = (175.0 / 198.04) * (wp.y + wp.x);
-        let kb = (70.0 / 218.11) * (wp.y + wp.z);
 
-        let y = ((color.l / wp.y).powi(2)) * 100.0;
-        let x = (color.a / ka * (y / wp.y).sqrt() + (y / wp.y)) * wp.x;
-        let z = -(color.b / kb * (y / wp.y).sqrt() - (y / wp.y)) * wp.z;
+    /// Convert Hunter Lab values to XYZ, relative to the given white point.
+    pub fn to_xyz(self, white: Xyz<Any, f32>) -> Xyz<Any, f32> {
+        let (ka, kb) = Self::coefficients(white);
 
-        palette::Xyz::new(x, y, z)
-    }
-}
+        let y = (self.l / 100.0).powi(2);
+        let x = (self.a / ka * y.sqrt() + y) * white.x;
+        let z = -(self.b / kb * y.sqrt() - y) * white.z;
 
-impl Clamp for HunterLab {
-    fn clamp(self) -> Self {
-        HunterLab {
-            l: self.l.clamp(0.0, 1.0),
-            a: self.a.clamp(0.0, 1.0),
-            b: self.b.clamp(0.0, 1.0),
-            white_point: self.white_point,
-        }
+        Xyz::new(x, y * white.y, z)
     }
 }
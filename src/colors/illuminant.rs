@@ -0,0 +1,196 @@
+use gtk::{gio, prelude::SettingsExt};
+use palette::{Xyz, white_point::Any};
+
+/// Chromatic adaptation matrix of the Bradford transform.
+///
+/// Based on <http://brucelindbloom.com/index.html?Eqn_ChromAdapt.html>
+const BRADFORD: [[f32; 3]; 3] = [
+    [0.8951, 0.2664, -0.1614],
+    [-0.7502, 1.7135, 0.0367],
+    [0.0389, -0.0685, 1.0296],
+];
+
+/// Inverse of the [`BRADFORD`] matrix.
+const BRADFORD_INVERSE: [[f32; 3]; 3] = [
+    [0.9869929, -0.1470543, 0.1599627],
+    [0.4323053, 0.5183603, 0.0492912],
+    [-0.0085287, 0.0400428, 0.9684867],
+];
+
+/// CIE standard illuminants, describing the light source a color is viewed under.
+///
+/// Defaults to [`Illuminant::D65`], which is the white point of sRGB.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Illuminant {
+    /// Incandescent/tungsten light.
+    A,
+    /// Direct noon sunlight, deprecated in favor of the D series.
+    B,
+    /// Average/north sky daylight, deprecated in favor of the D series.
+    C,
+    /// Horizon light, commonly used in printing.
+    D50,
+    /// Mid-morning/mid-afternoon daylight.
+    D55,
+    /// Noon daylight, used by sRGB.
+    #[default]
+    D65,
+    /// North sky daylight.
+    D75,
+    /// Equal energy radiator.
+    E,
+    /// Cool white fluorescent lamp.
+    F2,
+    /// Broadband daylight fluorescent lamp.
+    F7,
+    /// Narrow tri-band fluorescent lamp.
+    F11,
+}
+
+impl Illuminant {
+    /// Returns the tristimulus values of the illuminant for the given observer,
+    /// normalized to `Y = 1.0`.
+    ///
+    /// Values from <https://en.wikipedia.org/wiki/Standard_illuminant#White_points_of_standard_illuminants>
+    fn tristimulus(self, observer: StandardObserver) -> (f32, f32, f32) {
+        let (x, z) = match (self, observer) {
+            (Illuminant::A, StandardObserver::Two) => (1.09850, 0.35585),
+            (Illuminant::A, StandardObserver::Ten) => (1.11144, 0.35200),
+            (Illuminant::B, StandardObserver::Two) => (0.99093, 0.85313),
+            (Illuminant::B, StandardObserver::Ten) => (0.99178, 0.84349),
+            (Illuminant::C, StandardObserver::Two) => (0.98074, 1.18232),
+            (Illuminant::C, StandardObserver::Ten) => (0.97285, 1.16145),
+            (Illuminant::D50, StandardObserver::Two) => (0.96422, 0.82521),
+            (Illuminant::D50, StandardObserver::Ten) => (0.96720, 0.81427),
+            (Illuminant::D55, StandardObserver::Two) => (0.95682, 0.92149),
+            (Illuminant::D55, StandardObserver::Ten) => (0.95799, 0.90926),
+            (Illuminant::D65, StandardObserver::Two) => (0.95047, 1.08883),
+            (Illuminant::D65, StandardObserver::Ten) => (0.94811, 1.07304),
+            (Illuminant::D75, StandardObserver::Two) => (0.94972, 1.22638),
+            (Illuminant::D75, StandardObserver::Ten) => (0.94416, 1.20641),
+            (Illuminant::E, _) => (1.0, 1.0),
+            (Illuminant::F2, StandardObserver::Two) => (0.99187, 0.67395),
+            (Illuminant::F2, StandardObserver::Ten) => (1.03280, 0.69026),
+            (Illuminant::F7, StandardObserver::Two) => (0.95044, 1.08755),
+            (Illuminant::F7, StandardObserver::Ten) => (0.95792, 1.07687),
+            (Illuminant::F11, StandardObserver::Two) => (1.00966, 0.64370),
+            (Illuminant::F11, StandardObserver::Ten) => (1.03866, 0.65627),
+        };
+        (x, 1.0, z)
+    }
+}
+
+//Convert from U32. Needed for converting from the settings AdwComboRow, which use indexes for values.
+impl From<u32> for Illuminant {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => Self::A,
+            1 => Self::B,
+            2 => Self::C,
+            3 => Self::D50,
+            4 => Self::D55,
+            5 => Self::D65,
+            6 => Self::D75,
+            7 => Self::E,
+            8 => Self::F2,
+            9 => Self::F7,
+            10 => Self::F11,
+            _ => Self::default(),
+        }
+    }
+}
+
+/// CIE standard observer, describing the field of view the color is seen with.
+///
+/// Defaults to the 2° observer.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum StandardObserver {
+    /// CIE 1931 2° standard observer.
+    #[default]
+    Two,
+    /// CIE 1964 10° standard observer.
+    Ten,
+}
+
+//Convert from U32. Needed for converting from the settings AdwComboRow, which use indexes for values.
+impl From<u32> for StandardObserver {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::Ten,
+            _ => Self::Two,
+        }
+    }
+}
+
+/// Reference white used for converting colors to and from XYZ based color spaces.
+///
+/// As colors are internally stored as sRGB, which uses D65 with a 2° observer,
+/// they are adapted to the reference white using the Bradford transform.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ReferenceWhite {
+    pub illuminant: Illuminant,
+    pub observer: StandardObserver,
+}
+
+impl ReferenceWhite {
+    /// Create a new reference white from the illuminant and observer.
+    pub fn new(illuminant: Illuminant, observer: StandardObserver) -> Self {
+        Self {
+            illuminant,
+            observer,
+        }
+    }
+
+    /// Returns the reference white selected in the `cie-illuminants` and
+    /// `cie-standard-observer` settings.
+    pub fn from_settings(settings: &gio::Settings) -> Self {
+        Self::new(
+            Illuminant::from(settings.int("cie-illuminants") as u32),
+            StandardObserver::from(settings.int("cie-standard-observer") as u32),
+        )
+    }
+
+    /// Returns the XYZ values of the white point, normalized to `Y = 1.0`.
+    pub fn xyz(&self) -> Xyz<Any, f32> {
+        let (x, y, z) = self.illuminant.tristimulus(self.observer);
+        Xyz::new(x, y, z)
+    }
+
+    /// Adapts XYZ values under the sRGB white point (D65, 2°) to this reference white.
+    pub fn adapt_from_d65(&self, xyz: Xyz<Any, f32>) -> Xyz<Any, f32> {
+        bradford(xyz, ReferenceWhite::default().xyz(), self.xyz())
+    }
+
+    /// Adapts XYZ values under this reference white to the sRGB white point (D65, 2°).
+    pub fn adapt_to_d65(&self, xyz: Xyz<Any, f32>) -> Xyz<Any, f32> {
+        bradford(xyz, self.xyz(), ReferenceWhite::default().xyz())
+    }
+}
+
+/// Multiplies a 3x3 matrix with the XYZ values.
+fn multiply(matrix: &[[f32; 3]; 3], xyz: Xyz<Any, f32>) -> Xyz<Any, f32> {
+    let row = |[a, b, c]: [f32; 3]| a * xyz.x + b * xyz.y + c * xyz.z;
+    Xyz::new(row(matrix[0]), row(matrix[1]), row(matrix[2]))
+}
+
+/// Adapts the color from the source white to the destination white using the Bradford transform.
+fn bradford(
+    xyz: Xyz<Any, f32>,
+    source: Xyz<Any, f32>,
+    destination: Xyz<Any, f32>,
+) -> Xyz<Any, f32> {
+    if source == destination {
+        return xyz;
+    }
+
+    let source = multiply(&BRADFORD, source);
+    let destination = multiply(&BRADFORD, destination);
+    let cone = multiply(&BRADFORD, xyz);
+
+    let scaled = Xyz::new(
+        cone.x * destination.x / source.x,
+        cone.y * destination.y / source.y,
+        cone.z * destination.z / source.z,
+    );
+    multiply(&BRADFORD_INVERSE, scaled)
+}
@@ -0,0 +1,294 @@
+use super::{color::Color, parser};
+
+/// Color spaces, in which two colors can be mixed.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum MixSpace {
+    Srgb,
+    SrgbLinear,
+    Lab,
+    #[default]
+    Oklab,
+    Oklch,
+}
+
+//Convert from U32. Needed for converting from the settings AdwComboRow, which use indexes for values.
+impl From<u32> for MixSpace {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => Self::Srgb,
+            1 => Self::SrgbLinear,
+            2 => Self::Lab,
+            4 => Self::Oklch,
+            _ => Self::Oklab,
+        }
+    }
+}
+
+impl MixSpace {
+    /// The identifier of the space in the interpolation method of CSS.
+    pub fn css_name(self) -> &'static str {
+        match self {
+            MixSpace::Srgb => "srgb",
+            MixSpace::SrgbLinear => "srgb-linear",
+            MixSpace::Lab => "lab",
+            MixSpace::Oklab => "oklab",
+            MixSpace::Oklch => "oklch",
+        }
+    }
+
+    /// Whether the space has a hue, which is interpolated with a [`HueInterpolation`].
+    pub fn is_polar(self) -> bool {
+        self == MixSpace::Oklch
+    }
+}
+
+/// Methods of interpolating the hue of polar color spaces.
+///
+/// Based on <https://www.w3.org/TR/css-color-4/#hue-interpolation>
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum HueInterpolation {
+    #[default]
+    Shorter,
+    Longer,
+    Increasing,
+    Decreasing,
+}
+
+//Convert from U32. Needed for converting from the settings AdwComboRow, which use indexes for values.
+impl From<u32> for HueInterpolation {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::Longer,
+            2 => Self::Increasing,
+            3 => Self::Decreasing,
+            _ => Self::Shorter,
+        }
+    }
+}
+
+impl HueInterpolation {
+    /// The keyword of the method in CSS.
+    pub fn css_name(self) -> &'static str {
+        match self {
+            HueInterpolation::Shorter => "shorter",
+            HueInterpolation::Longer => "longer",
+            HueInterpolation::Increasing => "increasing",
+            HueInterpolation::Decreasing => "decreasing",
+        }
+    }
+
+    /// Adjusts both hues, so that interpolating between them follows the method.
+    pub(super) fn adjust(self, first: f32, second: f32) -> (f32, f32) {
+        let (mut first, mut second) = (first.rem_euclid(360.0), second.rem_euclid(360.0));
+        let delta = second - first;
+        match self {
+            HueInterpolation::Shorter if delta > 180.0 => first += 360.0,
+            HueInterpolation::Shorter if delta < -180.0 => second += 360.0,
+            HueInterpolation::Longer if 0.0 < delta && delta < 180.0 => first += 360.0,
+            HueInterpolation::Longer if -180.0 < delta && delta <= 0.0 => second += 360.0,
+            HueInterpolation::Increasing if delta < 0.0 => second += 360.0,
+            HueInterpolation::Decreasing if delta > 0.0 => first += 360.0,
+            _ => {}
+        }
+        (first, second)
+    }
+}
+
+/// Mixes the second color into the first one, as done by `color-mix()` in CSS.
+///
+/// The ratio is the amount of the second color, between 0.0 and 1.0. Channels are interpolated
+/// with premultiplied alpha, and the hue of achromatic colors is taken from the other color.
+/// The result is not mapped into the sRGB gamut.
+pub fn mix(
+    first: Color,
+    second: Color,
+    ratio: f32,
+    space: MixSpace,
+    hue_interpolation: HueInterpolation,
+) -> Color {
+    parser::mix_colors(
+        first,
+        second,
+        ratio.clamp(0.0, 1.0),
+        space,
+        hue_interpolation,
+    )
+}
+
+/// Returns the `color-mix()` function of CSS, which mixes the colors like [`mix`].
+///
+/// The colors are written as `color(srgb …)`, so colors outside of the sRGB gamut are kept.
+pub fn css_function(
+    first: Color,
+    second: Color,
+    ratio: f32,
+    space: MixSpace,
+    hue_interpolation: HueInterpolation,
+) -> String {
+    let method = if space.is_polar() && hue_interpolation != HueInterpolation::Shorter {
+        format!("{} {} hue", space.css_name(), hue_interpolation.css_name())
+    } else {
+        space.css_name().to_owned()
+    };
+    // the percentage of the second color, trailing zeros of the fraction are removed
+    let percentage = format!("{:.4}", ratio.clamp(0.0, 1.0) * 100.0);
+    let percentage = percentage.trim_end_matches('0').trim_end_matches('.');
+    format!(
+        "color-mix(in {}, {}, {} {}%)",
+        method,
+        first.css(),
+        second.css(),
+        percentage
+    )
+}
+
+#[cfg(test)]
+mod mix_colors {
+    use super::*;
+
+    fn assert_close(expected: Color, actual: Color) {
+        assert!(
+            (expected.red - actual.red).abs() < 0.001
+                && (expected.green - actual.green).abs() < 0.001
+                && (expected.blue - actual.blue).abs() < 0.001
+                && (expected.alpha - actual.alpha).abs() < 0.001,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    fn red() -> Color {
+        Color::rgba(255, 0, 0, 255)
+    }
+
+    fn blue() -> Color {
+        Color::rgba(0, 0, 255, 255)
+    }
+
+    #[test]
+    fn it_mixes_in_srgb() {
+        let mixed = mix(
+            red(),
+            blue(),
+            0.5,
+            MixSpace::Srgb,
+            HueInterpolation::Shorter,
+        );
+        assert_close(
+            Color::unclamped(palette::Srgba::new(0.5, 0.0, 0.5, 1.0)),
+            mixed,
+        );
+
+        let mixed = mix(
+            red(),
+            blue(),
+            0.25,
+            MixSpace::Srgb,
+            HueInterpolation::Shorter,
+        );
+        assert_close(
+            Color::unclamped(palette::Srgba::new(0.75, 0.0, 0.25, 1.0)),
+            mixed,
+        );
+    }
+
+    #[test]
+    fn it_keeps_colors_at_the_ends() {
+        for space in [
+            MixSpace::Srgb,
+            MixSpace::SrgbLinear,
+            MixSpace::Lab,
+            MixSpace::Oklab,
+            MixSpace::Oklch,
+        ] {
+            assert_close(
+                red(),
+                mix(red(), blue(), 0.0, space, HueInterpolation::Longer),
+            );
+            assert_close(
+                blue(),
+                mix(red(), blue(), 1.0, space, HueInterpolation::Longer),
+            );
+        }
+    }
+
+    #[test]
+    fn it_premultiplies_alpha() {
+        let transparent = Color::rgba(0, 0, 255, 0);
+        let mixed = mix(
+            red(),
+            transparent,
+            0.5,
+            MixSpace::Srgb,
+            HueInterpolation::Shorter,
+        );
+        assert_close(
+            Color::unclamped(palette::Srgba::new(1.0, 0.0, 0.0, 0.5)),
+            mixed,
+        );
+    }
+
+    #[test]
+    fn it_matches_css() {
+        let cases = [
+            (MixSpace::SrgbLinear, HueInterpolation::Shorter),
+            (MixSpace::Lab, HueInterpolation::Shorter),
+            (MixSpace::Oklab, HueInterpolation::Shorter),
+            (MixSpace::Oklch, HueInterpolation::Shorter),
+            (MixSpace::Oklch, HueInterpolation::Longer),
+            (MixSpace::Oklch, HueInterpolation::Increasing),
+            (MixSpace::Oklch, HueInterpolation::Decreasing),
+        ];
+        for (space, hue_interpolation) in cases {
+            let function = css_function(red(), blue(), 0.3, space, hue_interpolation);
+            let (_, (_, expected)) = parser::css_color(&function).unwrap();
+            assert_close(expected, mix(red(), blue(), 0.3, space, hue_interpolation));
+        }
+    }
+
+    #[test]
+    fn it_keeps_exact_colors_in_css() {
+        let wide = Color::unclamped(palette::Srgba::new(1.2, -0.1, 0.3, 0.8));
+        let function = css_function(
+            wide,
+            blue(),
+            0.255,
+            MixSpace::Oklab,
+            HueInterpolation::Shorter,
+        );
+        assert!(function.ends_with(" 25.5%)"), "{}", function);
+        let (_, (_, expected)) = parser::css_color(&function).unwrap();
+        assert_close(
+            expected,
+            mix(
+                wide,
+                blue(),
+                0.255,
+                MixSpace::Oklab,
+                HueInterpolation::Shorter,
+            ),
+        );
+    }
+
+    #[test]
+    fn it_interpolates_hue() {
+        let shorter = mix(
+            red(),
+            blue(),
+            0.5,
+            MixSpace::Oklch,
+            HueInterpolation::Shorter,
+        );
+        let longer = mix(
+            red(),
+            blue(),
+            0.5,
+            MixSpace::Oklch,
+            HueInterpolation::Longer,
+        );
+        // red and blue are mixed into purple on the shorter and green on the longer arc
+        assert!(shorter.blue > shorter.green);
+        assert!(longer.green > longer.blue);
+    }
+}
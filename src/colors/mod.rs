@@ -1,9 +1,23 @@
 pub mod cmyk;
 pub mod color;
 pub mod color_names;
+pub mod contrast;
+pub mod difference;
+pub mod extract;
+pub mod gamut;
+pub mod gradient;
+pub mod harmony;
 pub mod hunterlab;
+pub mod illuminant;
+pub mod mix;
 mod notation;
+pub mod palette_file;
 pub mod parser;
 pub mod position;
+pub mod ramp;
+pub mod rgb_space;
+pub mod sample;
+pub mod template;
+pub mod vision;
 
 pub use notation::Notation;
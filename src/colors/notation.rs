@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use gettextrs::gettext;
 use gtk::{gio, glib, prelude::SettingsExt};
-use palette::IntoColor;
+use palette::{IntoColor, convert::IntoColorUnclamped, white_point::Any};
 
 use crate::{
     colors::{cmyk::Cmyka, hunterlab::HunterLab},
@@ -13,8 +13,11 @@ use crate::{
 use super::{
     color::{Color, ColorError},
     color_names::{self, ColorNameSources},
-    parser,
+    gamut::GamutMapping,
+    illuminant::ReferenceWhite,
+    palette_file, parser,
     position::AlphaPosition,
+    rgb_space::RgbSpace,
 };
 
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, glib::Enum)]
@@ -35,36 +38,123 @@ pub enum Notation {
     HunterLab,
     Oklab,
     Oklch,
+    SrgbLinear,
+    DisplayP3,
+    Rec2020,
+    A98Rgb,
+    ProphotoRgb,
+    Android,
+    SwiftUi,
+    Flutter,
+    Qt,
+    Glsl,
+    DotNet,
+    RustPalette,
+    Latex,
 }
 
 impl Notation {
+    /// Parses the input in this notation, using the alpha position and reference white from the settings.
     pub fn parse(&self, input: &str, name_sources: ColorNameSources) -> Result<Color, ColorError> {
+        let (alpha_position, reference_white) = Self::parse_settings();
+        self.parse_with(input, alpha_position, name_sources, reference_white)
+    }
+
+    /// Parses the input in any supported notation, using the alpha position and reference white from the settings.
+    ///
+    /// Returns the detected notation alongside the parsed color.
+    pub fn detect(
+        input: &str,
+        name_sources: ColorNameSources,
+    ) -> Result<(Notation, Color), ColorError> {
+        let (alpha_position, reference_white) = Self::parse_settings();
+        let (_, result) = parser::any_color(input, alpha_position, name_sources, reference_white)?;
+        Ok(result)
+    }
+
+    /// Returns the alpha position and reference white used for parsing from the settings.
+    pub fn parse_settings() -> (AlphaPosition, ReferenceWhite) {
         let settings = gio::Settings::new(config::APP_ID);
+        (
+            AlphaPosition::from(settings.int("alpha-position") as u32),
+            ReferenceWhite::from_settings(&settings),
+        )
+    }
+
+    /// Parses the input in this notation.
+    ///
+    /// In contrast to [`Notation::parse`], this does not read any settings.
+    pub fn parse_with(
+        &self,
+        input: &str,
+        alpha_position: AlphaPosition,
+        name_sources: ColorNameSources,
+        reference_white: ReferenceWhite,
+    ) -> Result<Color, ColorError> {
         let (_, color) = match self {
-            Notation::Hex => parser::hex_color(
-                input,
-                AlphaPosition::from(settings.int("alpha-position") as u32),
-            ),
+            Notation::Hex => parser::hex_color(input, alpha_position),
             Notation::Rgb => parser::rgb(input),
             Notation::Hsl => parser::hsl(input),
             Notation::Hsv => parser::hsv(input),
             Notation::Cmyk => parser::cmyk(input),
-            Notation::Xyz => parser::xyz(input),
-            Notation::Lab => parser::cielab(input),
+            Notation::Xyz => parser::xyz(input, reference_white),
+            Notation::Lab => parser::cielab(input, reference_white),
             Notation::Hwb => parser::hwb(input),
-            Notation::Hcl => parser::lch(input),
-            Notation::Lms => parser::lms(input),
-            Notation::HunterLab => parser::hunter_lab(input),
+            Notation::Hcl => parser::lch(input, reference_white),
+            Notation::Lms => parser::lms(input, reference_white),
+            Notation::HunterLab => parser::hunter_lab(input, reference_white),
             Notation::Oklab => parser::oklab(input),
             Notation::Oklch => parser::oklch(input),
+            Notation::SrgbLinear => parser::color_function(input, RgbSpace::SrgbLinear),
+            Notation::DisplayP3 => parser::color_function(input, RgbSpace::DisplayP3),
+            Notation::Rec2020 => parser::color_function(input, RgbSpace::Rec2020),
+            Notation::A98Rgb => parser::color_function(input, RgbSpace::A98Rgb),
+            Notation::ProphotoRgb => parser::color_function(input, RgbSpace::ProphotoRgb),
+            Notation::Android => parser::android(input),
+            Notation::SwiftUi => parser::swift_ui(input),
+            Notation::Flutter => parser::flutter(input),
+            Notation::Qt => parser::qt(input),
+            Notation::Glsl => parser::glsl(input),
+            Notation::DotNet => parser::dot_net(input),
+            Notation::RustPalette => parser::rust_palette(input),
+            Notation::Latex => parser::latex(input),
             Notation::Name => {
-                return color_names::color(input, name_sources)
+                // approximated names are prefixed, but should still be parsable
+                let name = input.trim().trim_start_matches('≈').trim();
+                return color_names::color(name, name_sources)
                     .ok_or(ColorError::ParsingError("No name found".to_owned()));
             }
         }?;
         Ok(color)
     }
 
+    /// Whether the notation can only represent colors in the sRGB gamut.
+    pub fn is_srgb(&self) -> bool {
+        matches!(
+            self,
+            Notation::Hex
+                | Notation::Rgb
+                | Notation::Hsl
+                | Notation::Hsv
+                | Notation::Cmyk
+                | Notation::Hwb
+                | Notation::Name
+                | Notation::Android
+                | Notation::SwiftUi
+                | Notation::Flutter
+                | Notation::Qt
+                | Notation::Glsl
+                | Notation::DotNet
+                | Notation::RustPalette
+                | Notation::Latex
+        )
+    }
+
+    /// Formats the color in this notation.
+    ///
+    /// Colors out of the sRGB gamut are mapped into it with the gamut mapping for sRGB notations,
+    /// see [`Notation::is_srgb`]. Other notations show the unmapped values.
+    #[allow(clippy::too_many_arguments)]
     pub fn as_str(
         &self,
         color: Color,
@@ -72,13 +162,69 @@ impl Notation {
         rgb_decimal_notation: bool,
         precision: usize,
         name_sources: ColorNameSources,
+        reference_white: ReferenceWhite,
+        gamut_mapping: GamutMapping,
     ) -> String {
+        let color = if self.is_srgb() {
+            gamut_mapping.apply(color)
+        } else {
+            color
+        };
         let percent = |value: f32| (value * 100.0).round();
         let pretty_percent = |value: f32| match value {
             1.0 => "1".to_string(),
             0.0 => "0".to_string(),
             _ => format!("{:.2}", value),
         };
+        let color_function = |space: RgbSpace| {
+            let [r, g, b] = space.to_rgb(color);
+            match alpha_position {
+                AlphaPosition::End => format!(
+                    "color({} {:.precision$} {:.precision$} {:.precision$} / {})",
+                    space.css_name(),
+                    r,
+                    g,
+                    b,
+                    pretty_percent(percent(color.alpha) / 100.0),
+                ),
+                _ => format!(
+                    "color({} {:.precision$} {:.precision$} {:.precision$})",
+                    space.css_name(),
+                    r,
+                    g,
+                    b,
+                ),
+            }
+        };
+
+        // channels as integers between 0 and 255
+        let byte = |value: f32| (value * 255.0).round() as u8;
+        // programming languages need a decimal point to recognize floating point literals
+        let float = |value: f32| format!("{:.*}", precision.max(1), value);
+        let argb = format!(
+            "{:02X}{:02X}{:02X}{:02X}",
+            byte(color.alpha),
+            byte(color.red),
+            byte(color.green),
+            byte(color.blue)
+        );
+
+        // name of an Android color resource, which may only contain letters, digits and underscores
+        let resource_name = || {
+            let name = color_names::name(color, name_sources)
+                .or_else(|| color_names::nearest_name(color, name_sources).map(|(name, _)| name))
+                .map(|name| palette_file::slug(&name).replace('-', "_"))
+                .unwrap_or_default();
+            if name.starts_with(|c: char| c.is_ascii_lowercase())
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            {
+                name
+            } else {
+                "color".to_owned()
+            }
+        };
 
         match self {
             Notation::Hex => {
@@ -154,7 +300,7 @@ impl Notation {
                 )
             }
             Notation::Xyz => {
-                let xyz: palette::Xyz = color.color.into_color();
+                let xyz = color.to_xyz(reference_white);
                 format!(
                     "XYZ({:.precision$}, {:.precision$}, {:.precision$})",
                     xyz.x * 100.0,
@@ -163,7 +309,7 @@ impl Notation {
                 )
             }
             Notation::Lab => {
-                let lab: palette::Lab = color.color.into_color();
+                let lab = color.to_lab(reference_white);
                 format!(
                     "lab({:.precision$}, {:.precision$}, {:.precision$})",
                     lab.l, lab.a, lab.b,
@@ -179,7 +325,7 @@ impl Notation {
                 )
             }
             Notation::Hcl => {
-                let lch: palette::Lch = color.color.into_color();
+                let lch: palette::Lch<Any> = color.to_lab(reference_white).into_color();
                 format!(
                     "lch({:.precision$}, {:.precision$}, {:.precision$})",
                     lch.l,
@@ -188,21 +334,21 @@ impl Notation {
                 )
             }
             Notation::Lms => {
-                let (l, m, s) = color.to_lms();
+                let (l, m, s) = color.to_lms(reference_white);
                 format!(
                     "L: {:.precision$}, M: {:.precision$}, S: {:.precision$}",
                     l, m, s,
                 )
             }
             Notation::HunterLab => {
-                let lab: HunterLab = color.color.into_color();
+                let lab = HunterLab::from_xyz(color.to_xyz(reference_white), reference_white.xyz());
                 format!(
                     "L: {:.precision$}, a: {:.precision$}, b: {:.precision$}",
                     lab.l, lab.a, lab.b,
                 )
             }
             Notation::Oklab => {
-                let oklab: palette::Oklab = color.color.into_color();
+                let oklab = color.to_oklab();
                 match alpha_position {
                     AlphaPosition::End => format!(
                         "oklab({}% {:.precision$} {:.precision$} / {})",
@@ -220,7 +366,7 @@ impl Notation {
                 }
             }
             Notation::Oklch => {
-                let oklch: palette::Oklch = color.color.into_color();
+                let oklch: palette::Oklch = color.to_oklab().into_color_unclamped();
                 match alpha_position {
                     AlphaPosition::End => format!(
                         "oklch({}% {:.precision$} {:.precision$} / {})",
@@ -237,9 +383,112 @@ impl Notation {
                     ),
                 }
             }
-            Notation::Name => {
-                color_names::name(color, name_sources).unwrap_or_else(|| gettext("Not named"))
-            }
+            Notation::SrgbLinear => color_function(RgbSpace::SrgbLinear),
+            Notation::DisplayP3 => color_function(RgbSpace::DisplayP3),
+            Notation::Rec2020 => color_function(RgbSpace::Rec2020),
+            Notation::A98Rgb => color_function(RgbSpace::A98Rgb),
+            Notation::ProphotoRgb => color_function(RgbSpace::ProphotoRgb),
+            // Android integer literals always start with the alpha value, color resources
+            // may omit it, but also need it in front
+            Notation::Android => match alpha_position {
+                AlphaPosition::Start => format!("0x{}", argb),
+                AlphaPosition::End => {
+                    format!("<color name=\"{}\">#{}</color>", resource_name(), argb)
+                }
+                AlphaPosition::None => format!(
+                    "<color name=\"{}\">#{}</color>",
+                    resource_name(),
+                    &argb[2..]
+                ),
+            },
+            Notation::SwiftUi => match alpha_position {
+                AlphaPosition::Start | AlphaPosition::End => format!(
+                    "Color(red: {:.precision$}, green: {:.precision$}, blue: {:.precision$}, opacity: {:.precision$})",
+                    color.red, color.green, color.blue, color.alpha,
+                ),
+                AlphaPosition::None => format!(
+                    "Color(red: {:.precision$}, green: {:.precision$}, blue: {:.precision$})",
+                    color.red, color.green, color.blue,
+                ),
+            },
+            // Flutter colors always contain the alpha value, opaque colors are used without it
+            Notation::Flutter => match alpha_position {
+                AlphaPosition::Start | AlphaPosition::End => format!("Color(0x{})", argb),
+                AlphaPosition::None => format!("Color(0xFF{})", &argb[2..]),
+            },
+            Notation::Qt => match alpha_position {
+                AlphaPosition::Start | AlphaPosition::End => format!(
+                    "QColor({}, {}, {}, {})",
+                    byte(color.red),
+                    byte(color.green),
+                    byte(color.blue),
+                    byte(color.alpha)
+                ),
+                AlphaPosition::None => format!(
+                    "QColor({}, {}, {})",
+                    byte(color.red),
+                    byte(color.green),
+                    byte(color.blue)
+                ),
+            },
+            Notation::Glsl => match alpha_position {
+                AlphaPosition::Start | AlphaPosition::End => format!(
+                    "vec4({}, {}, {}, {})",
+                    float(color.red),
+                    float(color.green),
+                    float(color.blue),
+                    float(color.alpha)
+                ),
+                AlphaPosition::None => format!(
+                    "vec3({}, {}, {})",
+                    float(color.red),
+                    float(color.green),
+                    float(color.blue)
+                ),
+            },
+            Notation::DotNet => match alpha_position {
+                AlphaPosition::Start | AlphaPosition::End => format!(
+                    "Color.FromArgb({}, {}, {}, {})",
+                    byte(color.alpha),
+                    byte(color.red),
+                    byte(color.green),
+                    byte(color.blue)
+                ),
+                AlphaPosition::None => format!(
+                    "Color.FromArgb({}, {}, {})",
+                    byte(color.red),
+                    byte(color.green),
+                    byte(color.blue)
+                ),
+            },
+            Notation::RustPalette => match alpha_position {
+                AlphaPosition::Start | AlphaPosition::End => format!(
+                    "palette::Srgba::new({}, {}, {}, {})",
+                    float(color.red),
+                    float(color.green),
+                    float(color.blue),
+                    float(color.alpha)
+                ),
+                AlphaPosition::None => format!(
+                    "palette::Srgb::new({}, {}, {})",
+                    float(color.red),
+                    float(color.green),
+                    float(color.blue)
+                ),
+            },
+            Notation::Latex => format!(
+                "\\definecolor{{color}}{{RGB}}{{{},{},{}}}",
+                byte(color.red),
+                byte(color.green),
+                byte(color.blue)
+            ),
+            Notation::Name => color_names::name(color, name_sources)
+                .or_else(|| {
+                    color_names::nearest_name(color, name_sources)
+                        // Translators: Shown in front of the name of the closest named color, when the color has no exact name. Do not translate the `{}`.
+                        .map(|(name, _distance)| gettext("≈ {}").replace("{}", &name))
+                })
+                .unwrap_or_else(|| gettext("Not named")),
         }
     }
 
@@ -258,6 +507,19 @@ impl Notation {
             Notation::HunterLab => "Copy Hunter Lab",
             Notation::Oklab => "Copy Oklab",
             Notation::Oklch => "Copy Oklch",
+            Notation::SrgbLinear => "Copy Linear sRGB",
+            Notation::DisplayP3 => "Copy Display P3",
+            Notation::Rec2020 => "Copy Rec. 2020",
+            Notation::A98Rgb => "Copy Adobe RGB",
+            Notation::ProphotoRgb => "Copy ProPhoto RGB",
+            Notation::Android => "Copy Android Color",
+            Notation::SwiftUi => "Copy SwiftUI Color",
+            Notation::Flutter => "Copy Flutter Color",
+            Notation::Qt => "Copy QColor",
+            Notation::Glsl => "Copy GLSL Vector",
+            Notation::DotNet => "Copy .NET Color",
+            Notation::RustPalette => "Copy Rust palette Color",
+            Notation::Latex => "Copy LaTeX Color",
             Notation::Name => "Copy Name",
         })
     }
@@ -279,6 +541,19 @@ impl Notation {
                 Notation::HunterLab => "Hunter Lab".to_string(),
                 Notation::Oklab => "Oklab".to_string(),
                 Notation::Oklch => "Oklch".to_string(),
+                Notation::SrgbLinear => gettext("Linear sRGB"),
+                Notation::DisplayP3 => "Display P3".to_string(),
+                Notation::Rec2020 => "Rec. 2020".to_string(),
+                Notation::A98Rgb => "Adobe RGB (1998)".to_string(),
+                Notation::ProphotoRgb => "ProPhoto RGB".to_string(),
+                Notation::Android => "Android".to_string(),
+                Notation::SwiftUi => "SwiftUI".to_string(),
+                Notation::Flutter => "Flutter".to_string(),
+                Notation::Qt => "Qt".to_string(),
+                Notation::Glsl => "GLSL".to_string(),
+                Notation::DotNet => ".NET".to_string(),
+                Notation::RustPalette => "Rust (palette)".to_string(),
+                Notation::Latex => "LaTeX".to_string(),
                 Notation::Name => gettext("Name"),
             },
             self.as_str(
@@ -287,6 +562,8 @@ impl Notation {
                 false,
                 2,
                 ColorNameSources::empty(),
+                ReferenceWhite::default(),
+                GamutMapping::default(),
             ),
         )
     }
@@ -311,6 +588,19 @@ impl FromStr for Notation {
             "hunterlab" => Self::HunterLab,
             "oklab" => Self::Oklab,
             "oklch" => Self::Oklch,
+            "srgb-linear" => Self::SrgbLinear,
+            "display-p3" => Self::DisplayP3,
+            "rec2020" => Self::Rec2020,
+            "a98-rgb" => Self::A98Rgb,
+            "prophoto-rgb" => Self::ProphotoRgb,
+            "android" => Self::Android,
+            "swiftui" => Self::SwiftUi,
+            "flutter" => Self::Flutter,
+            "qt" => Self::Qt,
+            "glsl" => Self::Glsl,
+            "dotnet" => Self::DotNet,
+            "rust-palette" => Self::RustPalette,
+            "latex" => Self::Latex,
             _ => {
                 tracing::error!("Failed to parse notation: {}", s);
                 return Err(ColorError::ParsingError(
@@ -320,3 +610,40 @@ impl FromStr for Notation {
         })
     }
 }
+
+#[cfg(test)]
+mod format_name {
+    use super::*;
+
+    fn name(color: Color) -> String {
+        Notation::Name.as_str(
+            color,
+            AlphaPosition::None,
+            false,
+            2,
+            ColorNameSources::Html,
+            ReferenceWhite::default(),
+            GamutMapping::default(),
+        )
+    }
+
+    #[test]
+    fn it_formats_exact_name() {
+        assert_eq!("red", name(Color::rgba(255, 0, 0, 255)));
+    }
+
+    #[test]
+    fn it_round_trips_nearest_name() {
+        let formatted = name(Color::rgba(250, 5, 5, 255));
+        assert_eq!("≈ red", formatted);
+        assert_eq!(
+            Ok(Color::rgba(255, 0, 0, 255)),
+            Notation::Name.parse_with(
+                &formatted,
+                AlphaPosition::None,
+                ColorNameSources::Html,
+                ReferenceWhite::default(),
+            )
+        );
+    }
+}
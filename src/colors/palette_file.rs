@@ -0,0 +1,779 @@
+use std::path::Path;
+
+use gettextrs::gettext;
+use palette::{Hsva, Lab};
+
+use super::{
+    Notation,
+    cmyk::Cmyka,
+    color::{Color, ColorError},
+    color_names::{self, ColorNameSources},
+    gamut::GamutMapping,
+    illuminant::{Illuminant, ReferenceWhite, StandardObserver},
+    parser,
+    position::AlphaPosition,
+};
+
+/// File formats, which a palette of colors can be exported to or imported from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PaletteFormat {
+    /// GIMP palette.
+    Gpl,
+    /// Adobe Swatch Exchange.
+    Ase,
+    /// Adobe Photoshop color swatches.
+    Aco,
+    /// CSS custom properties.
+    Css,
+    /// SCSS variables.
+    Scss,
+    /// Colors section of a Tailwind CSS config.
+    Tailwind,
+    /// JSON array of named colors.
+    Json,
+    /// Plain text with one color per line.
+    Text,
+}
+
+impl PaletteFormat {
+    /// All supported palette formats.
+    pub const ALL: [PaletteFormat; 8] = [
+        PaletteFormat::Gpl,
+        PaletteFormat::Ase,
+        PaletteFormat::Aco,
+        PaletteFormat::Css,
+        PaletteFormat::Scss,
+        PaletteFormat::Tailwind,
+        PaletteFormat::Json,
+        PaletteFormat::Text,
+    ];
+
+    /// Palette formats, which can be exported.
+    pub const EXPORTABLE: [PaletteFormat; 6] = [
+        PaletteFormat::Gpl,
+        PaletteFormat::Ase,
+        PaletteFormat::Css,
+        PaletteFormat::Scss,
+        PaletteFormat::Tailwind,
+        PaletteFormat::Json,
+    ];
+
+    /// Palette formats, which can be imported.
+    pub const IMPORTABLE: [PaletteFormat; 6] = [
+        PaletteFormat::Gpl,
+        PaletteFormat::Ase,
+        PaletteFormat::Aco,
+        PaletteFormat::Css,
+        PaletteFormat::Scss,
+        PaletteFormat::Text,
+    ];
+
+    /// Returns the format matching the extension of the given path.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|format| format.extension() == extension)
+    }
+
+    /// The file extension used by the format, without the leading dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PaletteFormat::Gpl => "gpl",
+            PaletteFormat::Ase => "ase",
+            PaletteFormat::Aco => "aco",
+            PaletteFormat::Css => "css",
+            PaletteFormat::Scss => "scss",
+            PaletteFormat::Tailwind => "js",
+            PaletteFormat::Json => "json",
+            PaletteFormat::Text => "txt",
+        }
+    }
+
+    /// A human-readable name of the format.
+    pub fn display_name(&self) -> String {
+        gettext(match self {
+            PaletteFormat::Gpl => "GIMP Palette",
+            PaletteFormat::Ase => "Adobe Swatch Exchange",
+            PaletteFormat::Aco => "Adobe Photoshop Swatches",
+            PaletteFormat::Css => "CSS",
+            PaletteFormat::Scss => "SCSS",
+            PaletteFormat::Tailwind => "Tailwind CSS",
+            PaletteFormat::Json => "JSON",
+            PaletteFormat::Text => "Plain Text",
+        })
+    }
+
+    /// Exports the colors into the format.
+    ///
+    /// The names of the colors are looked up in the given name sources, falling back to the
+    /// nearest named color. Textual formats use hex codes as values, with the alpha value only
+    /// being included for transparent colors.
+    /// All formats store sRGB values, so colors are mapped into the sRGB gamut first.
+    /// Fails if the format cannot be exported, see [`PaletteFormat::EXPORTABLE`].
+    pub fn export(
+        &self,
+        colors: &[Color],
+        name_sources: ColorNameSources,
+        gamut_mapping: GamutMapping,
+    ) -> Result<Vec<u8>, ColorError> {
+        let names = names(colors, name_sources);
+        let entries = colors
+            .iter()
+            .map(|&color| gamut_mapping.apply(color))
+            .zip(names.iter().map(String::as_str));
+
+        let text = match self {
+            PaletteFormat::Ase => return Ok(ase(entries)),
+            PaletteFormat::Aco | PaletteFormat::Text => {
+                return Err(ColorError::ParsingError(format!(
+                    "{} palettes cannot be exported",
+                    self.display_name()
+                )));
+            }
+            PaletteFormat::Gpl => {
+                let mut text = String::from("GIMP Palette\nName: Eyedropper\nColumns: 0\n#\n");
+                for (color, name) in entries {
+                    let (red, green, blue) = color.into_format::<u8, u8>().color.into_components();
+                    text.push_str(&format!("{red:3} {green:3} {blue:3}\t{name}\n"));
+                }
+                text
+            }
+            PaletteFormat::Css => {
+                let mut text = String::from(":root {\n");
+                for (color, name) in entries {
+                    text.push_str(&format!(
+                        "  --{}: {};\n",
+                        slug(name),
+                        css_value(color, gamut_mapping)
+                    ));
+                }
+                text.push_str("}\n");
+                text
+            }
+            PaletteFormat::Scss => entries
+                .map(|(color, name)| {
+                    format!("${}: {};\n", slug(name), css_value(color, gamut_mapping))
+                })
+                .collect(),
+            PaletteFormat::Tailwind => {
+                let mut text = String::from(
+                    "module.exports = {\n  theme: {\n    extend: {\n      colors: {\n",
+                );
+                for (color, name) in entries {
+                    text.push_str(&format!(
+                        "        '{}': '{}',\n",
+                        slug(name),
+                        css_value(color, gamut_mapping)
+                    ));
+                }
+                text.push_str("      },\n    },\n  },\n};\n");
+                text
+            }
+            PaletteFormat::Json => {
+                let items = entries
+                    .map(|(color, name)| {
+                        format!(
+                            "  {{ \"name\": \"{}\", \"value\": \"{}\" }}",
+                            escape_json(name),
+                            css_value(color, gamut_mapping)
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                format!("[\n{}\n]\n", items.join(",\n"))
+            }
+        };
+        Ok(text.into_bytes())
+    }
+
+    /// Imports the colors from the contents of a palette file.
+    ///
+    /// Textual colors are parsed in any supported notation, using the given settings.
+    /// Entries that could not be parsed are collected in [`ImportedPalette::invalid`].
+    /// Fails if the contents are not in the format at all, or if the format cannot be imported.
+    pub fn import(
+        &self,
+        contents: &[u8],
+        alpha_position: AlphaPosition,
+        name_sources: ColorNameSources,
+        reference_white: ReferenceWhite,
+    ) -> Result<ImportedPalette, ColorError> {
+        let parse = |input: &str| {
+            parser::any_color(input, alpha_position, name_sources, reference_white)
+                .ok()
+                .map(|(_, (_notation, color))| color)
+        };
+
+        match self {
+            PaletteFormat::Ase => return import_ase(contents),
+            PaletteFormat::Aco => return import_aco(contents),
+            PaletteFormat::Tailwind | PaletteFormat::Json => {
+                return Err(ColorError::ParsingError(format!(
+                    "{} palettes cannot be imported",
+                    self.display_name()
+                )));
+            }
+            _ => {}
+        }
+
+        let text = std::str::from_utf8(contents)
+            .map_err(|err| ColorError::ParsingError(err.to_string()))?;
+        let mut palette = ImportedPalette::default();
+
+        match self {
+            PaletteFormat::Gpl => {
+                let mut lines = text.lines();
+                if lines.next().map(str::trim) != Some("GIMP Palette") {
+                    return Err(ColorError::ParsingError(
+                        "Missing GIMP palette header".to_owned(),
+                    ));
+                }
+
+                for line in lines.map(str::trim) {
+                    if line.is_empty()
+                        || line.starts_with('#')
+                        || line.starts_with("Name:")
+                        || line.starts_with("Columns:")
+                    {
+                        continue;
+                    }
+
+                    let channels = line
+                        .split_whitespace()
+                        .take(3)
+                        .map(str::parse::<u8>)
+                        .collect::<Result<Vec<u8>, _>>();
+                    match channels.as_deref() {
+                        Ok(&[red, green, blue]) => {
+                            palette.colors.push(Color::rgba(red, green, blue, 255))
+                        }
+                        _ => palette.invalid.push(line.to_owned()),
+                    }
+                }
+            }
+            PaletteFormat::Css | PaletteFormat::Scss => {
+                for declaration in css_declarations(text) {
+                    let Some((property, value)) = declaration.split_once(':') else {
+                        continue;
+                    };
+                    let property = property.trim().to_ascii_lowercase();
+                    let value = value.trim().trim_end_matches("!important").trim();
+
+                    match parse(value) {
+                        Some(color) => palette.colors.push(color),
+                        // only report declarations, which should contain a color
+                        None if property.starts_with("--")
+                            || property.starts_with('$')
+                            || property.ends_with("color") =>
+                        {
+                            palette.invalid.push(declaration.to_owned())
+                        }
+                        None => {}
+                    }
+                }
+            }
+            _ => {
+                for line in text.lines().map(str::trim) {
+                    // lines starting with a semicolon are comments in Paint.NET palettes
+                    if line.is_empty() || line.starts_with(';') {
+                        continue;
+                    }
+
+                    match parse(line) {
+                        Some(color) => palette.colors.push(color),
+                        None => palette.invalid.push(line.to_owned()),
+                    }
+                }
+            }
+        }
+
+        Ok(palette)
+    }
+}
+
+/// Colors imported from a palette file.
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportedPalette {
+    /// The successfully imported colors, in the order of the file.
+    pub colors: Vec<Color>,
+    /// Entries of the file, which could not be imported.
+    pub invalid: Vec<String>,
+}
+
+/// Returns a unique name for each color.
+///
+/// Colors without a name are numbered, duplicated names get a numbered suffix.
+fn names(colors: &[Color], name_sources: ColorNameSources) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(colors.len());
+    for (index, color) in colors.iter().enumerate() {
+        let name = color_names::name(*color, name_sources)
+            .or_else(|| color_names::nearest_name(*color, name_sources).map(|(name, _)| name))
+            .unwrap_or_else(|| format!("color {}", index + 1));
+
+        let mut unique_name = name.clone();
+        let mut suffix = 2;
+        while names.contains(&unique_name) {
+            unique_name = format!("{} {}", name, suffix);
+            suffix += 1;
+        }
+        names.push(unique_name);
+    }
+    names
+}
+
+/// Formats the color as hex code, including the alpha value only if the color is transparent.
+///
+/// Colors outside of the sRGB gamut are mapped into it.
+pub fn css_value(color: Color, gamut_mapping: GamutMapping) -> String {
+    let alpha_position = if color.alpha < 1.0 {
+        AlphaPosition::End
+    } else {
+        AlphaPosition::None
+    };
+    Notation::Hex.as_str(
+        color,
+        alpha_position,
+        false,
+        2,
+        ColorNameSources::empty(),
+        ReferenceWhite::default(),
+        gamut_mapping,
+    )
+}
+
+/// Converts the name into a lowercase identifier, usable as a variable name.
+pub fn slug(name: &str) -> String {
+    name.to_lowercase()
+        .split(|char: char| !char.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Escapes quotes, backslashes and control characters, so the text can be used as a JSON string.
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\u{0}'..='\u{1f}' => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Splits the stylesheet into its declarations, ignoring comments, selectors and braces.
+fn css_declarations(text: &str) -> Vec<&str> {
+    let mut declarations = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let (content, remaining) = match rest.split_once("/*") {
+            Some((content, comment)) => (
+                content,
+                comment
+                    .split_once("*/")
+                    .map_or("", |(_, remaining)| remaining),
+            ),
+            None => (rest, ""),
+        };
+        declarations.extend(
+            content
+                .split([';', '{', '}'])
+                .map(str::trim)
+                .filter(|declaration| !declaration.is_empty()),
+        );
+        rest = remaining;
+    }
+    declarations
+}
+
+/// Reads big-endian values from binary palette files.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Option<&'a [u8]> {
+        let (bytes, rest) = self.0.split_at_checked(count)?;
+        self.0 = rest;
+        Some(bytes)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn f32(&mut self) -> Option<f32> {
+        self.u32().map(f32::from_bits)
+    }
+}
+
+/// Reference white of the CIELAB values in Adobe palettes.
+fn adobe_reference_white() -> ReferenceWhite {
+    ReferenceWhite::new(Illuminant::D50, StandardObserver::Two)
+}
+
+/// Reads the colors from an Adobe Swatch Exchange file.
+///
+/// Groups are flattened, colors in unknown color models are reported as invalid.
+fn import_ase(contents: &[u8]) -> Result<ImportedPalette, ColorError> {
+    let mut reader = Reader(contents);
+    if reader.take(4) != Some(b"ASEF") {
+        return Err(ColorError::ParsingError(
+            "Not an Adobe Swatch Exchange file".to_owned(),
+        ));
+    }
+    // skip version
+    reader.take(4);
+    let block_count = reader.u32().unwrap_or_default();
+
+    let mut palette = ImportedPalette::default();
+    for index in 0..block_count {
+        let (Some(block_type), Some(length)) = (reader.u16(), reader.u32()) else {
+            palette.invalid.push(format!("Block {}", index + 1));
+            break;
+        };
+        let Some(block) = reader.take(length as usize) else {
+            palette.invalid.push(format!("Block {}", index + 1));
+            break;
+        };
+        // only color entries are of interest, group start and end blocks are skipped
+        if block_type != 0x0001 {
+            continue;
+        }
+
+        let mut block = Reader(block);
+        let name_length = block.u16().unwrap_or_default() as usize;
+        let name = block
+            .take(name_length * 2)
+            .map(|name| {
+                let name = name
+                    .chunks_exact(2)
+                    .map(|char| u16::from_be_bytes([char[0], char[1]]))
+                    .take_while(|char| *char != 0)
+                    .collect::<Vec<u16>>();
+                String::from_utf16_lossy(&name)
+            })
+            .unwrap_or_else(|| format!("Block {}", index + 1));
+
+        let color = match block.take(4) {
+            Some(b"RGB ") => (|| {
+                let (red, green, blue) = (block.f32()?, block.f32()?, block.f32()?);
+                Some(Color::from_palette(palette::Srgba::new(
+                    red, green, blue, 1.0,
+                )))
+            })(),
+            Some(b"CMYK") => (|| {
+                let cmyk = Cmyka::new(block.f32()?, block.f32()?, block.f32()?, block.f32()?, 1.0);
+                Some(Color::from_palette(cmyk))
+            })(),
+            Some(b"LAB ") => (|| {
+                let lab = Lab::new(block.f32()? * 100.0, block.f32()?, block.f32()?);
+                Some(Color::from_lab(lab, adobe_reference_white(), 1.0))
+            })(),
+            Some(b"Gray") => block
+                .f32()
+                .map(|gray| Color::from_palette(palette::Srgba::new(gray, gray, gray, 1.0))),
+            _ => None,
+        };
+
+        match color {
+            Some(color) => palette.colors.push(color),
+            None => palette.invalid.push(name),
+        }
+    }
+
+    Ok(palette)
+}
+
+/// Reads the colors from an Adobe Photoshop color swatch file.
+///
+/// Based on <https://www.adobe.com/devnet-apps/photoshop/fileformatashtml/#50577411_pgfId-1055819>
+fn import_aco(contents: &[u8]) -> Result<ImportedPalette, ColorError> {
+    let mut reader = Reader(contents);
+    let (Some(version @ (1 | 2)), Some(count)) = (reader.u16(), reader.u16()) else {
+        return Err(ColorError::ParsingError(
+            "Not an Adobe Photoshop swatch file".to_owned(),
+        ));
+    };
+
+    let mut palette = ImportedPalette::default();
+    for index in 0..count {
+        let Some(values) = reader.take(10) else {
+            palette.invalid.push(format!("Color {}", index + 1));
+            break;
+        };
+        let value = |i: usize| u16::from_be_bytes([values[i], values[i + 1]]);
+        let (space, w, x, y, z) = (value(0), value(2), value(4), value(6), value(8));
+        // version 2 colors are followed by their name
+        if version == 2 {
+            reader.u16();
+            let length = reader.u16().unwrap_or_default();
+            reader.take(length as usize * 2);
+        }
+
+        let unit = |value: u16| value as f32 / u16::MAX as f32;
+        let color = match space {
+            0 => Some(Color::from_palette(palette::Srgba::new(
+                unit(w),
+                unit(x),
+                unit(y),
+                1.0,
+            ))),
+            1 => Some(Color::from_palette(Hsva::new(
+                unit(w) * 360.0,
+                unit(x),
+                unit(y),
+                1.0,
+            ))),
+            // CMYK values are inverted, 0 is the maximum amount of ink
+            2 => Some(Color::from_palette(Cmyka::new(
+                1.0 - unit(w),
+                1.0 - unit(x),
+                1.0 - unit(y),
+                1.0 - unit(z),
+                1.0,
+            ))),
+            7 => {
+                let lab = Lab::new(
+                    w as f32 / 100.0,
+                    x as i16 as f32 / 100.0,
+                    y as i16 as f32 / 100.0,
+                );
+                Some(Color::from_lab(lab, adobe_reference_white(), 1.0))
+            }
+            8 => {
+                let gray = 1.0 - (w as f32 / 10000.0).clamp(0.0, 1.0);
+                Some(Color::from_palette(palette::Srgba::new(
+                    gray, gray, gray, 1.0,
+                )))
+            }
+            _ => None,
+        };
+
+        match color {
+            Some(color) => palette.colors.push(color),
+            None => palette.invalid.push(format!("Color {}", index + 1)),
+        }
+    }
+
+    Ok(palette)
+}
+
+/// Writes the colors as Adobe Swatch Exchange file.
+///
+/// Based on <http://www.selapa.net/swatches/colors/fileformats.php#adobe_ase>
+fn ase<'a>(entries: impl Iterator<Item = (Color, &'a str)>) -> Vec<u8> {
+    let entries = entries.collect::<Vec<_>>();
+
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"ASEF");
+    // version 1.0
+    bytes.extend_from_slice(&1u16.to_be_bytes());
+    bytes.extend_from_slice(&0u16.to_be_bytes());
+    bytes.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+
+    for (color, name) in entries {
+        // names are null-terminated UTF-16 strings
+        let name = name.encode_utf16().chain([0]).collect::<Vec<u16>>();
+
+        let mut block = Vec::new();
+        block.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        name.iter()
+            .for_each(|char| block.extend_from_slice(&char.to_be_bytes()));
+        block.extend_from_slice(b"RGB ");
+        [color.red, color.green, color.blue]
+            .iter()
+            .for_each(|value| block.extend_from_slice(&value.to_be_bytes()));
+        // normal color type
+        block.extend_from_slice(&2u16.to_be_bytes());
+
+        // color entry block
+        bytes.extend_from_slice(&1u16.to_be_bytes());
+        bytes.extend_from_slice(&(block.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&block);
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod import_export {
+    use super::*;
+
+    #[test]
+    fn it_exports_text_formats() {
+        let colors = [Color::rgba(46, 52, 64, 255), Color::rgba(255, 136, 0, 127)];
+
+        assert_eq!(
+            "GIMP Palette\nName: Eyedropper\nColumns: 0\n#\n 46  52  64\tcolor 1\n255 136   0\tcolor 2\n",
+            String::from_utf8(
+                PaletteFormat::Gpl
+                    .export(&colors, ColorNameSources::empty(), GamutMapping::Clip)
+                    .unwrap()
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            ":root {\n  --color-1: #2E3440;\n  --color-2: #FF88007F;\n}\n",
+            String::from_utf8(
+                PaletteFormat::Css
+                    .export(&colors, ColorNameSources::empty(), GamutMapping::Clip)
+                    .unwrap()
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            "$color-1: #2E3440;\n$color-2: #FF88007F;\n",
+            String::from_utf8(
+                PaletteFormat::Scss
+                    .export(&colors, ColorNameSources::empty(), GamutMapping::Clip)
+                    .unwrap()
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn it_exports_ase() {
+        let bytes = PaletteFormat::Ase
+            .export(
+                &[Color::rgba(255, 0, 0, 255)],
+                ColorNameSources::empty(),
+                GamutMapping::Clip,
+            )
+            .unwrap();
+
+        assert_eq!(b"ASEF", &bytes[0..4]);
+        // one block with the name `color 1`
+        assert_eq!(&1u32.to_be_bytes(), &bytes[8..12]);
+        assert_eq!(&(2 + 8 * 2 + 4 + 12 + 2u32).to_be_bytes(), &bytes[14..18]);
+        assert_eq!(bytes.len(), 18 + 2 + 8 * 2 + 4 + 12 + 2);
+
+        // values outside of the sRGB gamut are mapped into it
+        let color = Color::unclamped(palette::Srgba::new(1.2, 0.5, -0.1, 1.0));
+        let bytes = PaletteFormat::Ase
+            .export(&[color], ColorNameSources::empty(), GamutMapping::Clip)
+            .unwrap();
+        let values = bytes[40..52]
+            .chunks(4)
+            .map(|value| f32::from_be_bytes(value.try_into().unwrap()))
+            .collect::<Vec<f32>>();
+        assert_eq!(vec![1.0, 0.5, 0.0], values);
+    }
+
+    fn import(format: PaletteFormat, contents: &[u8]) -> ImportedPalette {
+        format
+            .import(
+                contents,
+                AlphaPosition::None,
+                ColorNameSources::all(),
+                ReferenceWhite::default(),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn it_imports_exported_palettes() {
+        let colors = vec![Color::rgba(46, 52, 64, 255), Color::rgba(255, 136, 0, 255)];
+
+        for format in [
+            PaletteFormat::Gpl,
+            PaletteFormat::Ase,
+            PaletteFormat::Css,
+            PaletteFormat::Scss,
+        ] {
+            let contents = format
+                .export(&colors, ColorNameSources::empty(), GamutMapping::Clip)
+                .unwrap();
+            let palette = import(format, &contents);
+            assert!(palette.invalid.is_empty(), "{:?}", format);
+            assert_eq!(
+                colors
+                    .iter()
+                    .map(|color| color.into_format::<u8, u8>())
+                    .collect::<Vec<_>>(),
+                palette
+                    .colors
+                    .iter()
+                    .map(|color| color.into_format::<u8, u8>())
+                    .collect::<Vec<_>>(),
+                "{:?}",
+                format
+            );
+        }
+    }
+
+    #[test]
+    fn it_imports_aco() {
+        let mut contents = Vec::new();
+        // version 1 with a RGB and a grayscale color
+        for value in [1u16, 2, 0, 0xffff, 0x8888, 0, 0, 8, 5000, 0, 0, 0] {
+            contents.extend_from_slice(&value.to_be_bytes());
+        }
+        let palette = import(PaletteFormat::Aco, &contents);
+        assert!(palette.invalid.is_empty());
+        assert_eq!(
+            vec![
+                palette::Srgba::new(255, 136, 0, 255),
+                palette::Srgba::new(128, 128, 128, 255)
+            ],
+            palette
+                .colors
+                .iter()
+                .map(|color| color.into_format::<u8, u8>())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn it_reports_invalid_entries() {
+        let palette = import(
+            PaletteFormat::Css,
+            b":root {\n  /* accent: #fff */\n  --accent: #3584e4;\n  --broken: #12;\n  margin: 0 auto;\n}\n",
+        );
+        assert_eq!(vec![Color::rgba(53, 132, 228, 255)], palette.colors);
+        assert_eq!(vec!["--broken: #12"], palette.invalid);
+
+        let palette = import(
+            PaletteFormat::Text,
+            b"; comment\nrgb(46, 52, 64)\nnot a color\n",
+        );
+        assert_eq!(vec![Color::rgba(46, 52, 64, 255)], palette.colors);
+        assert_eq!(vec!["not a color"], palette.invalid);
+
+        assert!(
+            PaletteFormat::Gpl
+                .import(
+                    b"255 0 0",
+                    AlphaPosition::None,
+                    ColorNameSources::all(),
+                    ReferenceWhite::default()
+                )
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn it_creates_unique_names() {
+        assert_eq!(
+            vec!["color 1", "color 2"],
+            names(
+                &[Color::rgba(0, 0, 0, 255), Color::rgba(0, 0, 0, 255)],
+                ColorNameSources::empty()
+            )
+        );
+        assert_eq!("dusty-rose", slug("Dusty Rose"));
+    }
+
+    #[test]
+    fn it_escapes_json_strings() {
+        assert_eq!(r#"a \"b\" \\ c"#, escape_json(r#"a "b" \ c"#));
+        assert_eq!(r"line\u000abreak\u0009tab", escape_json("line\nbreak\ttab"));
+    }
+}
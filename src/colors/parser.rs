@@ -1,15 +1,30 @@
 use nom::{
     IResult, Parser,
     branch::alt,
-    bytes::complete::{tag, tag_no_case, take_while_m_n},
-    character::complete::{digit0, digit1, multispace0},
-    combinator::{map, map_res, opt, recognize, value},
-    error::ParseError,
-    multi::many_m_n,
-    sequence::{delimited, pair, separated_pair, terminated},
+    bytes::complete::{tag, tag_no_case, take_until, take_while_m_n, take_while1},
+    character::complete::{char, digit0, digit1, multispace0, multispace1, one_of, satisfy},
+    combinator::{eof, map, map_res, not, opt, peek, recognize, value, verify},
+    error::{Error, ErrorKind, ParseError},
+    multi::{many_m_n, separated_list1},
+    sequence::{delimited, preceded, separated_pair, terminated},
+};
+use palette::{
+    Hsl, Hwb, IntoColor, Lab, Lch, LinSrgb, Oklab, Oklch, Srgb, WithAlpha, Xyz,
+    convert::{FromColorUnclamped, IntoColorUnclamped},
+    white_point::Any,
 };
 
-use super::{cmyk::Cmyka, color::Color, hunterlab::HunterLab, position::AlphaPosition};
+use super::{
+    Notation,
+    cmyk::Cmyka,
+    color::Color,
+    color_names::{self, ColorNameSources},
+    hunterlab::HunterLab,
+    illuminant::{Illuminant, ReferenceWhite, StandardObserver},
+    mix::{HueInterpolation, MixSpace},
+    position::AlphaPosition,
+    rgb_space::RgbSpace,
+};
 
 /// Parses a hexadecimal value from a string input and returns the parsed value.
 ///
@@ -51,12 +66,10 @@ fn relative_percentage(input: &str) -> IResult<&str, f32> {
 /// assert_eq!(result, Ok(("", 0.5)));
 /// ```
 fn percentage(input: &str) -> IResult<&str, f32> {
-    let (input, digits) = terminated(digit1, tag("%")).parse(input)?;
-    let (_input, value) = nom::number::complete::float(digits)?;
-    Ok((input, (value / 100f32).clamp(0.0, 1.0)))
+    map(parse_percentage, |value| value.clamp(0.0, 1.0)).parse(input)
 }
 
-/// Parses a percentage value, such as `-51.6%`.
+/// Parses a percentage value, such as `-51.6%` or `.5%`.
 ///
 /// The input string should represent a number followed by a `%` symbol.
 /// The function handles both positive and negative percentages, as well as fractional parts.
@@ -69,18 +82,7 @@ fn percentage(input: &str) -> IResult<&str, f32> {
 /// assert_eq!(result, Ok(("", -0.516)));
 ///```
 fn parse_percentage(input: &str) -> IResult<&str, f32> {
-    let (input, digits) = map_res(
-        terminated(
-            separated_pair(pair(opt(tag("-")), digit1), opt(tag(".")), digit0),
-            tag("%"),
-        ),
-        |((sign, int), frac)| {
-            let float_str = format!("{}{}.{}", sign.unwrap_or(""), int, frac);
-            float_str.parse::<f32>()
-        },
-    )
-    .parse(input)?;
-    Ok((input, (digits / 100f32)))
+    map(terminated(number, tag("%")), |value| value / 100f32).parse(input)
 }
 /// Parses different separators used to separate values.
 ///
@@ -153,9 +155,46 @@ where
     delimited(opt(multispace0), inner, opt(multispace0))
 }
 
+/// Parses the shorthand of a hex color, where each digit is repeated, such as `#fb0` for `#ffbb00`.
+///
+/// A fourth digit is used as the alpha value, unless the alpha position is [`AlphaPosition::None`].
+fn hex_shorthand(input: &str, alpha_position: AlphaPosition) -> IResult<&str, Color> {
+    let length = if alpha_position == AlphaPosition::None {
+        3..=3
+    } else {
+        3..=4
+    };
+    let (input, digits) = verify(
+        take_while_m_n(3, 4, |char: char| char.is_ascii_hexdigit()),
+        |digits: &str| length.contains(&digits.len()),
+    )
+    .parse(input)?;
+    let (input, _) = not(satisfy(|char: char| char.is_ascii_hexdigit())).parse(input)?;
+
+    let mut values: Vec<u8> = digits
+        .chars()
+        .filter_map(|digit| digit.to_digit(16))
+        .map(|digit| (digit * 17) as u8)
+        .collect();
+    if values.len() == 3 {
+        values.push(255);
+    } else if alpha_position == AlphaPosition::Start {
+        values.rotate_left(1);
+    }
+
+    Ok((
+        input,
+        Color::rgba(values[0], values[1], values[2], values[3]),
+    ))
+}
+
 pub fn hex_color(input: &str, alpha_position: AlphaPosition) -> IResult<&str, Color> {
     let (input, _) = opt(whitespace(tag("#"))).parse(input)?;
 
+    if let Ok(result) = hex_shorthand(input, alpha_position) {
+        return Ok(result);
+    }
+
     let (input, first_alpha) = if alpha_position == AlphaPosition::Start && input.len() >= 8 {
         hex(input)?
     } else {
@@ -165,10 +204,10 @@ pub fn hex_color(input: &str, alpha_position: AlphaPosition) -> IResult<&str, Co
     let (input, (red, green, blue)) =
         (whitespace(hex), whitespace(hex), whitespace(hex)).parse(input)?;
 
-    let alpha = match alpha_position {
-        AlphaPosition::None => 255,
-        AlphaPosition::Start => first_alpha,
-        AlphaPosition::End => opt(hex).parse(input)?.1.unwrap_or(255),
+    let (input, alpha) = match alpha_position {
+        AlphaPosition::None => (input, 255),
+        AlphaPosition::Start => (input, first_alpha),
+        AlphaPosition::End => map(opt(hex), |alpha| alpha.unwrap_or(255)).parse(input)?,
     };
 
     let color = Color::rgba(red, green, blue, alpha);
@@ -467,7 +506,10 @@ mod parse_cmyk {
 }
 
 /// Parses a xyz representation of a color.
-pub fn xyz(input: &str) -> IResult<&str, Color> {
+///
+/// The values are expected to be scaled to `Y = 100` for the given reference white, the same
+/// scale used when formatting [`Notation::Xyz`], so formatted values can be parsed again.
+pub fn xyz(input: &str, white: ReferenceWhite) -> IResult<&str, Color> {
     let (input, color_values) = delimited(
         whitespace(tag_no_case("XYZ(")),
         many_m_n(
@@ -482,9 +524,13 @@ pub fn xyz(input: &str) -> IResult<&str, Color> {
     )
     .parse(input)?;
 
-    let color = palette::Xyz::new(color_values[0], color_values[1], color_values[2]);
+    let color = palette::Xyz::new(
+        color_values[0] / 100.0,
+        color_values[1] / 100.0,
+        color_values[2] / 100.0,
+    );
 
-    Ok((input, Color::from_palette(color)))
+    Ok((input, Color::from_xyz(color, white, 1.0)))
 }
 
 #[cfg(test)]
@@ -495,13 +541,13 @@ mod parse_xyz {
     fn it_parses() {
         assert_eq!(
             Ok(("", Color::rgb(46, 52, 64))),
-            xyz("XYZ(3.280, 3.407, 5.335)")
+            xyz("XYZ(3.280, 3.407, 5.335)", ReferenceWhite::default())
         );
     }
 }
 
-/// Parses a cielab representation of a color.
-pub fn cielab(input: &str) -> IResult<&str, Color> {
+/// Parses a cielab representation of a color, relative to the given reference white.
+pub fn cielab(input: &str, white: ReferenceWhite) -> IResult<&str, Color> {
     let (input, _) = whitespace(alt((tag_no_case("lab("), tag_no_case("cielab(")))).parse(input)?;
 
     //can either be an percentage or a number between 0 and 100
@@ -540,12 +586,15 @@ pub fn cielab(input: &str) -> IResult<&str, Color> {
 
     let (input, _) = opt(whitespace(tag(")"))).parse(input)?;
 
-    let color = Color::from_palette(palette::Laba::new(
-        cie_l.clamp(0.0, 100.0),
-        cie_a_b[0].clamp(-125.0, 125.0),
-        cie_a_b[1].clamp(-125.0, 125.0),
+    let color = Color::from_lab(
+        Lab::new(
+            cie_l.clamp(0.0, 100.0),
+            cie_a_b[0].clamp(-125.0, 125.0),
+            cie_a_b[1].clamp(-125.0, 125.0),
+        ),
+        white,
         alpha.unwrap_or(1.0),
-    ));
+    );
 
     Ok((input, color))
 }
@@ -558,11 +607,11 @@ mod parse_cie_lab {
     fn it_parses() {
         assert_eq!(
             Ok(("", Color::rgb(46, 52, 64))),
-            cielab(" lab(21.61%, 0.56%,  -6.68%)")
+            cielab(" lab(21.61%, 0.56%,  -6.68%)", ReferenceWhite::default())
         );
         assert_eq!(
             Ok(("", Color::rgb(46, 52, 64))),
-            cielab("lab(21.61, 0.70, -8.35)")
+            cielab("lab(21.61, 0.70, -8.35)", ReferenceWhite::default())
         );
     }
 }
@@ -608,40 +657,36 @@ mod parse_hwb {
     }
 }
 
-/// Parses a lch representation of a color.
-pub fn lch(input: &str) -> IResult<&str, Color> {
+/// Parses a lch representation of a color, relative to the given reference white.
+pub fn lch(input: &str, white: ReferenceWhite) -> IResult<&str, Color> {
     let (input, _) = tag("lch(").parse(input)?;
 
     let (input, lightness) = terminated(
-        alt((
+        whitespace(alt((
             map(percentage, |percent| percent * 100.0),
             nom::number::complete::float,
-        )),
+        ))),
         opt(whitespace(separator)),
     )
     .parse(input)?;
 
     let (input, chroma) = terminated(
-        alt((
+        whitespace(alt((
             map(percentage, |percent| percent * 150.0),
             nom::number::complete::float,
-        )),
+        ))),
         opt(whitespace(separator)),
     )
     .parse(input)?;
 
-    let (input, hue) = terminated(hue, opt(whitespace(separator))).parse(input)?;
+    let (input, hue) = terminated(whitespace(hue), opt(whitespace(separator))).parse(input)?;
 
     let (input, alpha) = opt(whitespace(alt((percentage, relative_percentage)))).parse(input)?;
 
     let (input, _) = opt(whitespace(tag(")"))).parse(input)?;
 
-    let color = Color::from_palette(palette::Lcha::new(
-        lightness,
-        chroma,
-        hue,
-        alpha.unwrap_or(1.0),
-    ));
+    let lab: Lab<Any> = Lch::new(lightness, chroma, hue).into_color();
+    let color = Color::from_lab(lab, white, alpha.unwrap_or(1.0));
 
     Ok((input, color))
 }
@@ -654,17 +699,23 @@ mod parse_lch {
     fn it_parses_lch() {
         assert_eq!(
             Ok(("", Color::rgb(46, 52, 64))),
-            lch("lch(21.605232, 8.378235, 274.76328)")
+            lch(
+                "lch(21.605232, 8.378235, 274.76328)",
+                ReferenceWhite::default()
+            )
         );
         assert_eq!(
             Ok(("", Color::rgba(46, 52, 64, 127))),
-            lch("lch(21.605232, 8.378235, 274.76328, 0.5)")
+            lch(
+                "lch(21.605232, 8.378235, 274.76328, 0.5)",
+                ReferenceWhite::default()
+            )
         );
     }
 }
 
-/// Parses a LMS representation of a color.
-pub fn lms(input: &str) -> IResult<&str, Color> {
+/// Parses a LMS representation of a color, relative to the given reference white.
+pub fn lms(input: &str, white: ReferenceWhite) -> IResult<&str, Color> {
     let (input, long) = delimited(
         whitespace(tag("L:")),
         whitespace(nom::number::complete::float),
@@ -684,7 +735,7 @@ pub fn lms(input: &str) -> IResult<&str, Color> {
     )
     .parse(input)?;
 
-    let color = Color::from_lms(long, medium, short, 255);
+    let color = Color::from_lms(long, medium, short, 255, white);
 
     Ok((input, color))
 }
@@ -697,13 +748,16 @@ mod parse_lms {
     fn it_parses() {
         assert_eq!(
             Ok(("", Color::rgb(46, 52, 64))),
-            lms("L: 3.20580, M: 3.52562, S: 5.33522")
+            lms(
+                "L: 3.20580, M: 3.52562, S: 5.33522",
+                ReferenceWhite::default()
+            )
         );
     }
 }
 
-/// Parses a hunter lab representation of a color.
-pub fn hunter_lab(input: &str) -> IResult<&str, Color> {
+/// Parses a hunter lab representation of a color, relative to the given reference white.
+pub fn hunter_lab(input: &str, white: ReferenceWhite) -> IResult<&str, Color> {
     let (input, l) = delimited(
         whitespace(tag("L:")),
         whitespace(nom::number::complete::float),
@@ -723,9 +777,9 @@ pub fn hunter_lab(input: &str) -> IResult<&str, Color> {
     )
     .parse(input)?;
 
-    let color = HunterLab::new(l, a, b);
+    let xyz = HunterLab::new(l, a, b).to_xyz(white.xyz());
 
-    Ok((input, Color::from_palette(color)))
+    Ok((input, Color::from_xyz(xyz, white, 1.0)))
 }
 
 #[cfg(test)]
@@ -736,7 +790,10 @@ mod tests {
     fn parse_hunter_lab() {
         assert_eq!(
             Ok(("", Color::rgb(46, 52, 64))),
-            hunter_lab("L: 18.45804, a: 0.41141, b: -5.42239",)
+            hunter_lab(
+                "L: 18.45804, a: 0.41141, b: -5.42239",
+                ReferenceWhite::default()
+            )
         );
     }
 }
@@ -774,12 +831,10 @@ pub fn oklab(input: &str) -> IResult<&str, Color> {
 
     let (input, _) = opt(whitespace(tag(")"))).parse(input)?;
 
-    let color = Color::from_palette(palette::Oklaba::new(
-        lightness,
-        ok_a_b[0],
-        ok_a_b[1],
-        alpha.unwrap_or(1.0),
-    ));
+    let color = Color::from_linear(
+        palette::Oklaba::new(lightness, ok_a_b[0], ok_a_b[1], alpha.unwrap_or(1.0))
+            .into_color_unclamped(),
+    );
 
     Ok((input, color))
 }
@@ -836,12 +891,9 @@ pub fn oklch(input: &str) -> IResult<&str, Color> {
 
     let (input, _) = opt(whitespace(tag(")"))).parse(input)?;
 
-    let color = Color::from_palette(palette::Oklcha::new(
-        lightness,
-        chroma,
-        hue,
-        alpha.unwrap_or(1.0),
-    ));
+    let color = Color::from_linear(
+        palette::Oklcha::new(lightness, chroma, hue, alpha.unwrap_or(1.0)).into_color_unclamped(),
+    );
 
     Ok((input, color))
 }
@@ -857,4 +909,2254 @@ mod parse_oklch {
             oklch("32% 0.023499 261.187836")
         );
     }
+
+    #[test]
+    fn it_keeps_out_of_gamut_colors() {
+        let (_, color) = oklch("oklch(70% 0.4 30)").unwrap();
+        assert!(!color.is_in_gamut());
+        assert!(color.red > 1.0);
+        assert!(color.blue < 0.0);
+    }
+}
+
+/// Parses a CSS `color()` function in the given predefined RGB color space,
+/// such as `color(display-p3 1 0.5 0 / 50%)`.
+///
+/// The values can either be numbers between 0 and 1 or percentages. They are not clamped,
+/// as the color space might have a wider gamut, so the color may be out of the sRGB gamut.
+pub fn color_function(input: &str, space: RgbSpace) -> IResult<&str, Color> {
+    let (input, _) = (
+        whitespace(tag_no_case("color(")),
+        whitespace(tag_no_case(space.css_name())),
+    )
+        .parse(input)?;
+
+    let (input, color_values) = many_m_n(
+        3,
+        3,
+        terminated(
+            whitespace(alt((parse_percentage, nom::number::complete::float))),
+            opt(whitespace(separator)),
+        ),
+    )
+    .parse(input)?;
+
+    let (input, alpha) = opt(whitespace(map(
+        alt((parse_percentage, nom::number::complete::float)),
+        |alpha| alpha.clamp(0.0, 1.0),
+    )))
+    .parse(input)?;
+
+    let (input, _) = opt(whitespace(tag(")"))).parse(input)?;
+
+    let color = space.to_color(
+        [color_values[0], color_values[1], color_values[2]],
+        alpha.unwrap_or(1.0),
+    );
+
+    Ok((input, color))
+}
+
+#[cfg(test)]
+mod parse_color_function {
+    use super::*;
+
+    #[test]
+    fn it_parses() {
+        let (_, color) = color_function(
+            "color(srgb-linear 0.0273 0.0343 0.0513)",
+            RgbSpace::SrgbLinear,
+        )
+        .unwrap();
+        assert_eq!(
+            Color::rgb(46, 52, 64).into_format::<u8, u8>(),
+            color.into_format::<u8, u8>()
+        );
+
+        let (_, color) =
+            color_function("color(display-p3 100% 1 1 / 0.5)", RgbSpace::DisplayP3).unwrap();
+        assert_eq!(
+            Color::rgba(255, 255, 255, 128).into_format::<u8, u8>(),
+            color.into_format::<u8, u8>()
+        );
+    }
+
+    #[test]
+    fn it_keeps_out_of_gamut_colors() {
+        let (_, color) = color_function("color(rec2020 0 1 0)", RgbSpace::Rec2020).unwrap();
+        assert!(!color.is_in_gamut());
+        assert!(color.green > 1.0);
+        assert!(color.red < 0.0);
+    }
+
+    #[test]
+    fn it_rejects_other_spaces() {
+        assert!(color_function("color(a98-rgb 0 1 0)", RgbSpace::ProphotoRgb).is_err());
+    }
+}
+
+#[cfg(test)]
+mod parse_reference_white {
+    use super::*;
+    use crate::colors::illuminant::{Illuminant, StandardObserver};
+
+    fn assert_close(expected: Color, actual: Color) {
+        assert!(
+            (expected.red - actual.red).abs() < 0.001
+                && (expected.green - actual.green).abs() < 0.001
+                && (expected.blue - actual.blue).abs() < 0.001,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn it_parses_reference_white() {
+        let white = ReferenceWhite::new(Illuminant::D50, StandardObserver::Two);
+        assert_close(
+            Color::rgba(255, 255, 255, 255),
+            xyz("XYZ(96.422, 100, 82.521)", white).unwrap().1,
+        );
+        assert_close(
+            Color::rgba(255, 255, 255, 255),
+            cielab("lab(100, 0, 0)", white).unwrap().1,
+        );
+    }
+
+    #[test]
+    fn it_round_trips() {
+        let white = ReferenceWhite::new(Illuminant::F11, StandardObserver::Ten);
+        let color = Color::rgba(46, 52, 64, 255);
+
+        let xyz_values = color.to_xyz(white);
+        let input = format!(
+            "XYZ({}, {}, {})",
+            xyz_values.x * 100.0,
+            xyz_values.y * 100.0,
+            xyz_values.z * 100.0
+        );
+        assert_close(color, xyz(&input, white).unwrap().1);
+
+        let lab = color.to_lab(white);
+        let input = format!("lab({}, {}, {})", lab.l, lab.a, lab.b);
+        assert_close(color, cielab(&input, white).unwrap().1);
+
+        let lch_values: Lch<Any> = lab.into_color();
+        let input = format!(
+            "lch({}, {}, {})",
+            lch_values.l,
+            lch_values.chroma,
+            lch_values.hue.into_positive_degrees()
+        );
+        assert_close(color, lch(&input, white).unwrap().1);
+
+        let (l, m, s) = color.to_lms(white);
+        let input = format!("L: {}, M: {}, S: {}", l, m, s);
+        assert_close(color, lms(&input, white).unwrap().1);
+
+        let hunter = HunterLab::from_xyz(xyz_values, white.xyz());
+        let input = format!("L: {}, a: {}, b: {}", hunter.l, hunter.a, hunter.b);
+        assert_close(color, hunter_lab(&input, white).unwrap().1);
+    }
+}
+
+/// Parses a hex code of a programming language, with the alpha value in front, such as `FF2E3440`.
+///
+/// Codes without alpha value and shorthands, such as `#ARGB`, are supported as well.
+fn argb_hex(input: &str) -> IResult<&str, Color> {
+    let (input, digits) = verify(
+        take_while_m_n(3, 8, |char: char| char.is_ascii_hexdigit()),
+        |digits: &str| [3, 4, 6, 8].contains(&digits.len()),
+    )
+    .parse(input)?;
+
+    let (_, color) = match digits.len() {
+        3 | 4 => hex_shorthand(digits, AlphaPosition::Start),
+        6 => hex_color(digits, AlphaPosition::None),
+        _ => hex_color(digits, AlphaPosition::Start),
+    }
+    .map_err(|err| err.map_input(|_| input))?;
+    Ok((input, color))
+}
+
+/// Parses an integer literal with alpha, red, green and blue bytes, such as `0xFF2E3440`.
+fn argb_literal(input: &str) -> IResult<&str, Color> {
+    preceded(
+        (
+            tag_no_case("0x"),
+            peek(take_while_m_n(8, 8, |char: char| char.is_ascii_hexdigit())),
+        ),
+        argb_hex,
+    )
+    .parse(input)
+}
+
+/// Parses a floating point literal, with an optional type suffix, such as `0.5`, `.5f` or `1.0_f32`.
+fn float_literal(input: &str) -> IResult<&str, f32> {
+    terminated(
+        verify(nom::number::complete::float, |value: &f32| {
+            value.is_finite()
+        }),
+        opt(alt((
+            tag("_f32"),
+            tag("_f64"),
+            tag("f32"),
+            tag("f64"),
+            tag_no_case("f"),
+            tag_no_case("d"),
+        ))),
+    )
+    .parse(input)
+}
+
+/// Parses a floating point channel and clamps it between 0 and 1.
+fn float_channel(input: &str) -> IResult<&str, f32> {
+    map(float_literal, |value| value.clamp(0.0, 1.0)).parse(input)
+}
+
+/// Parses the comma separated arguments of the function call with the given name,
+/// such as `vec3(0.5, 1.0, 0.0)`, with an optional semicolon at the end.
+///
+/// Only succeeds if the number of arguments is between `min` and `max`.
+fn arguments<'a, O, F>(
+    name: &'static str,
+    min: usize,
+    max: usize,
+    argument: F,
+) -> impl Parser<&'a str, Output = Vec<O>, Error = nom::error::Error<&'a str>>
+where
+    F: Parser<&'a str, Output = O, Error = nom::error::Error<&'a str>>,
+{
+    delimited(
+        (whitespace(tag(name)), tag("(")),
+        verify(
+            separated_list1(tag(","), whitespace(argument)),
+            move |arguments: &Vec<O>| (min..=max).contains(&arguments.len()),
+        ),
+        (tag(")"), opt(whitespace(tag(";")))),
+    )
+}
+
+/// Creates a color from 3 or 4 integer arguments, with the alpha value at the end.
+fn rgba_arguments(arguments: Vec<u8>) -> Color {
+    Color::rgba(
+        arguments[0],
+        arguments[1],
+        arguments[2],
+        arguments.get(3).copied().unwrap_or(255),
+    )
+}
+
+/// Creates a color from 3 or 4 floating point arguments, with the alpha value at the end.
+fn float_arguments(arguments: Vec<f32>) -> Color {
+    Color::from_palette(palette::Srgba::new(
+        arguments[0],
+        arguments[1],
+        arguments[2],
+        arguments.get(3).copied().unwrap_or(1.0),
+    ))
+}
+
+/// Parses an Android color, either as integer literal, such as `0xFF2E3440`,
+/// or as color resource, such as `<color name="background">#FF2E3440</color>`.
+pub fn android(input: &str) -> IResult<&str, Color> {
+    whitespace(alt((
+        argb_literal,
+        delimited(
+            (tag("<color"), take_until(">"), tag(">")),
+            whitespace(preceded(tag("#"), argb_hex)),
+            tag("</color>"),
+        ),
+    )))
+    .parse(input)
+}
+
+/// Parses a SwiftUI color, such as `Color(red: 0.18, green: 0.2, blue: 0.25, opacity: 1)`.
+///
+/// The `.sRGB` color space and UIKit colors, such as `UIColor(red: 0.18, green: 0.2, blue: 0.25, alpha: 1)`,
+/// are accepted as well.
+pub fn swift_ui(input: &str) -> IResult<&str, Color> {
+    let labeled = |label: &'static str| {
+        preceded(
+            (whitespace(tag(label)), tag(":")),
+            whitespace(float_channel),
+        )
+    };
+
+    let (input, _) = (
+        whitespace(alt((tag("Color"), tag("UIColor"), tag("NSColor")))),
+        tag("("),
+        opt((whitespace(tag(".sRGB")), tag(","))),
+    )
+        .parse(input)?;
+    let (input, (red, _, green, _, blue)) = (
+        labeled("red"),
+        tag(","),
+        labeled("green"),
+        tag(","),
+        labeled("blue"),
+    )
+        .parse(input)?;
+    let (input, alpha) = opt(preceded(
+        tag(","),
+        alt((labeled("opacity"), labeled("alpha"))),
+    ))
+    .parse(input)?;
+    let (input, _) = (tag(")"), opt(whitespace(tag(";")))).parse(input)?;
+
+    Ok((
+        input,
+        float_arguments(vec![red, green, blue, alpha.unwrap_or(1.0)]),
+    ))
+}
+
+/// Parses a Flutter color, such as `Color(0xFF2E3440)`, `Color.fromARGB(255, 46, 52, 64)`
+/// or `Color.fromRGBO(46, 52, 64, 1.0)`.
+pub fn flutter(input: &str) -> IResult<&str, Color> {
+    let (input, _) = opt(whitespace(tag("const"))).parse(input)?;
+    alt((
+        delimited(
+            (whitespace(tag("Color")), tag("(")),
+            whitespace(argb_literal),
+            (whitespace(tag(")")), opt(whitespace(tag(";")))),
+        ),
+        map(
+            arguments("Color.fromARGB", 4, 4, nom::character::complete::u8),
+            |values| Color::rgba(values[1], values[2], values[3], values[0]),
+        ),
+        map(arguments("Color.fromRGBO", 4, 4, float_literal), |values| {
+            Color::from_palette(palette::Srgba::new(
+                values[0] / 255.0,
+                values[1] / 255.0,
+                values[2] / 255.0,
+                values[3],
+            ))
+        }),
+    ))
+    .parse(input)
+}
+
+/// Parses a Qt color, such as `QColor(46, 52, 64)`, `QColor::fromRgb(46, 52, 64, 255)`
+/// or `QColor::fromRgbF(0.18, 0.2, 0.25)`.
+pub fn qt(input: &str) -> IResult<&str, Color> {
+    alt((
+        map(
+            arguments("QColor", 3, 4, nom::character::complete::u8),
+            rgba_arguments,
+        ),
+        map(
+            arguments("QColor::fromRgbF", 3, 4, float_channel),
+            float_arguments,
+        ),
+        map(
+            arguments("QColor::fromRgb", 3, 4, nom::character::complete::u8),
+            rgba_arguments,
+        ),
+    ))
+    .parse(input)
+}
+
+/// Parses a GLSL vector, such as `vec3(0.18, 0.2, 0.25)` or `vec4(0.18, 0.2, 0.25, 1.0)`.
+pub fn glsl(input: &str) -> IResult<&str, Color> {
+    map(
+        alt((
+            arguments("vec3", 3, 3, float_channel),
+            arguments("vec4", 4, 4, float_channel),
+        )),
+        float_arguments,
+    )
+    .parse(input)
+}
+
+/// Parses a .NET color, such as `Color.FromArgb(255, 46, 52, 64)` or `Color.FromArgb(46, 52, 64)`.
+pub fn dot_net(input: &str) -> IResult<&str, Color> {
+    let (input, _) = opt(tag("System.Drawing.")).parse(multispace0(input)?.0)?;
+    map(
+        arguments("Color.FromArgb", 3, 4, nom::character::complete::u8),
+        |mut values| {
+            if values.len() == 4 {
+                values.rotate_left(1);
+            }
+            rgba_arguments(values)
+        },
+    )
+    .parse(input)
+}
+
+/// Parses a color of the Rust `palette` crate, such as `palette::Srgb::new(0.18, 0.2, 0.25)`,
+/// `Srgba::new(0.18, 0.2, 0.25, 1.0)` or `Srgb::new(46u8, 52u8, 64u8)`.
+pub fn rust_palette(input: &str) -> IResult<&str, Color> {
+    let (input, _) = opt(tag("palette::")).parse(multispace0(input)?.0)?;
+    let channel = || {
+        alt((
+            map(
+                terminated(nom::character::complete::u8, alt((tag("u8"), tag("_u8")))),
+                |value| value as f32 / 255.0,
+            ),
+            float_channel,
+        ))
+    };
+    map(
+        alt((
+            arguments("Srgb::new", 3, 3, channel()),
+            arguments("Srgba::new", 4, 4, channel()),
+        )),
+        float_arguments,
+    )
+    .parse(input)
+}
+
+/// Parses a LaTeX color definition of the `xcolor` package, such as `\definecolor{name}{RGB}{46,52,64}`.
+///
+/// The `rgb`, `RGB`, `HTML`, `cmyk` and `gray` models are supported.
+pub fn latex(input: &str) -> IResult<&str, Color> {
+    let (input, (_, _, model)) = (
+        whitespace(tag("\\definecolor")),
+        delimited(tag("{"), take_until("}"), tag("}")),
+        delimited(whitespace(tag("{")), take_until("}"), tag("}")),
+    )
+        .parse(input)?;
+    let (input, _) = whitespace(tag("{")).parse(input)?;
+
+    let values = |count| {
+        verify(
+            separated_list1(tag(","), whitespace(float_literal)),
+            move |values: &Vec<f32>| values.len() == count,
+        )
+    };
+    let (input, color) = match model.trim() {
+        "RGB" => map(values(3), |values| {
+            Color::from_palette(palette::Srgb::new(
+                values[0] / 255.0,
+                values[1] / 255.0,
+                values[2] / 255.0,
+            ))
+        })
+        .parse(input)?,
+        "rgb" => map(values(3), float_arguments).parse(input)?,
+        "HTML" => whitespace(argb_hex).parse(input)?,
+        "cmyk" => map(values(4), |values| {
+            Color::from_palette(Cmyka::new(values[0], values[1], values[2], values[3], 1.0))
+        })
+        .parse(input)?,
+        "gray" => map(values(1), |values| {
+            float_arguments(vec![values[0], values[0], values[0]])
+        })
+        .parse(input)?,
+        _ => {
+            return Err(nom::Err::Error(nom::error::Error::new(
+                input,
+                nom::error::ErrorKind::Tag,
+            )));
+        }
+    };
+    let (input, _) = tag("}").parse(input)?;
+
+    Ok((input, color))
+}
+
+#[cfg(test)]
+mod parse_code {
+    use super::*;
+
+    fn assert_parses(expected: Color, parser: fn(&str) -> IResult<&str, Color>, input: &str) {
+        let (rest, color) = parser(input).unwrap_or_else(|_| panic!("Failed to parse {input}"));
+        assert_eq!("", rest.trim(), "{input}");
+        assert_eq!(
+            expected.into_format::<u8, u8>(),
+            color.into_format::<u8, u8>(),
+            "{input}"
+        );
+    }
+
+    #[test]
+    fn it_parses_android() {
+        let color = Color::rgba(46, 52, 64, 128);
+        assert_parses(color, android, "0x802E3440");
+        assert_parses(
+            color,
+            android,
+            r#"<color name="background">#802e3440</color>"#,
+        );
+        assert_parses(
+            Color::rgba(255, 187, 0, 255),
+            android,
+            "<color name=\"accent\">#fb0</color>",
+        );
+        assert_parses(
+            Color::rgba(46, 52, 64, 255),
+            android,
+            r#"<color name="polar_night">#2E3440</color>"#,
+        );
+        assert!(android("0x2E3440").is_err());
+    }
+
+    #[test]
+    fn it_parses_swift_ui() {
+        let color = Color::rgba(255, 128, 0, 255);
+        assert_parses(color, swift_ui, "Color(red: 1, green: 0.5, blue: 0)");
+        assert_parses(
+            Color::rgba(255, 128, 0, 128),
+            swift_ui,
+            "Color(.sRGB, red: 1.0, green: 0.5, blue: 0.0, opacity: 0.5)",
+        );
+        assert_parses(
+            color,
+            swift_ui,
+            "UIColor(red: 1, green: 0.5, blue: 0, alpha: 1)",
+        );
+    }
+
+    #[test]
+    fn it_parses_flutter() {
+        let color = Color::rgba(46, 52, 64, 255);
+        assert_parses(color, flutter, "const Color(0xFF2E3440);");
+        assert_parses(color, flutter, "Color.fromARGB(255, 46, 52, 64)");
+        assert_parses(color, flutter, "Color.fromRGBO(46, 52, 64, 1.0)");
+    }
+
+    #[test]
+    fn it_parses_qt() {
+        assert_parses(Color::rgba(46, 52, 64, 255), qt, "QColor(46, 52, 64)");
+        assert_parses(
+            Color::rgba(46, 52, 64, 128),
+            qt,
+            "QColor::fromRgb(46, 52, 64, 128)",
+        );
+        assert_parses(
+            Color::rgba(255, 128, 0, 255),
+            qt,
+            "QColor::fromRgbF(1.0, 0.5, 0.0)",
+        );
+    }
+
+    #[test]
+    fn it_parses_glsl() {
+        assert_parses(Color::rgba(255, 128, 0, 255), glsl, "vec3(1.0, 0.5, 0.0)");
+        assert_parses(
+            Color::rgba(255, 128, 0, 128),
+            glsl,
+            "vec4(1., .5, 0.0, 0.5);",
+        );
+        assert!(glsl("vec4(1.0, 0.5, 0.0)").is_err());
+    }
+
+    #[test]
+    fn it_parses_dot_net() {
+        let color = Color::rgba(46, 52, 64, 128);
+        assert_parses(color, dot_net, "Color.FromArgb(128, 46, 52, 64)");
+        assert_parses(
+            Color::rgba(46, 52, 64, 255),
+            dot_net,
+            "System.Drawing.Color.FromArgb(46, 52, 64);",
+        );
+    }
+
+    #[test]
+    fn it_parses_rust_palette() {
+        let color = Color::rgba(255, 128, 0, 255);
+        assert_parses(color, rust_palette, "palette::Srgb::new(1.0, 0.5, 0.0)");
+        assert_parses(color, rust_palette, "Srgb::new(255u8, 128u8, 0u8)");
+        assert_parses(
+            Color::rgba(255, 128, 0, 128),
+            rust_palette,
+            "Srgba::new(1.0_f32, 0.5, 0.0, 0.5);",
+        );
+    }
+
+    #[test]
+    fn it_parses_latex() {
+        let color = Color::rgba(46, 52, 64, 255);
+        assert_parses(color, latex, r"\definecolor{background}{RGB}{46,52,64}");
+        assert_parses(color, latex, r"\definecolor{background}{HTML}{2E3440}");
+        assert_parses(
+            Color::rgba(255, 128, 0, 255),
+            latex,
+            r"\definecolor{accent}{rgb}{1, 0.5, 0}",
+        );
+        assert_parses(
+            Color::rgba(128, 128, 128, 255),
+            latex,
+            r"\definecolor{gray}{gray}{0.5}",
+        );
+        assert!(latex(r"\definecolor{accent}{wave}{500}").is_err());
+    }
+}
+
+/// Chroma below which the hue of a converted color is powerless, and therefore missing.
+const LCH_ACHROMATIC: f32 = 0.02;
+const OKLCH_ACHROMATIC: f32 = 0.0001;
+
+/// Color spaces, in which CSS colors can be specified and mixed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Space {
+    Srgb,
+    Rgb(RgbSpace),
+    XyzD50,
+    XyzD65,
+    Hsl,
+    Hwb,
+    Lab,
+    Lch,
+    Oklab,
+    Oklch,
+}
+
+impl Space {
+    /// Returns the space with the given CSS identifier.
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "srgb" => Space::Srgb,
+            "srgb-linear" => Space::Rgb(RgbSpace::SrgbLinear),
+            "display-p3" => Space::Rgb(RgbSpace::DisplayP3),
+            "rec2020" => Space::Rgb(RgbSpace::Rec2020),
+            "a98-rgb" => Space::Rgb(RgbSpace::A98Rgb),
+            "prophoto-rgb" => Space::Rgb(RgbSpace::ProphotoRgb),
+            "xyz" | "xyz-d65" => Space::XyzD65,
+            "xyz-d50" => Space::XyzD50,
+            "hsl" => Space::Hsl,
+            "hwb" => Space::Hwb,
+            "lab" => Space::Lab,
+            "lch" => Space::Lch,
+            "oklab" => Space::Oklab,
+            "oklch" => Space::Oklch,
+            _ => return None,
+        })
+    }
+
+    /// Whether the space can be used in the `color()` function.
+    fn is_predefined(self) -> bool {
+        matches!(
+            self,
+            Space::Srgb | Space::Rgb(_) | Space::XyzD50 | Space::XyzD65
+        )
+    }
+
+    /// Index of the hue channel in polar color spaces.
+    fn hue(self) -> Option<usize> {
+        match self {
+            Space::Hsl | Space::Hwb => Some(0),
+            Space::Lch | Space::Oklch => Some(2),
+            _ => None,
+        }
+    }
+
+    /// Keywords of the channels in relative colors.
+    fn keywords(self) -> [&'static str; 3] {
+        match self {
+            Space::Srgb | Space::Rgb(_) => ["r", "g", "b"],
+            Space::XyzD50 | Space::XyzD65 => ["x", "y", "z"],
+            Space::Hsl => ["h", "s", "l"],
+            Space::Hwb => ["h", "w", "b"],
+            Space::Lab | Space::Oklab => ["l", "a", "b"],
+            Space::Lch | Space::Oklch => ["l", "c", "h"],
+        }
+    }
+
+    /// The notation, in which colors of this space are displayed.
+    fn notation(self) -> Notation {
+        match self {
+            Space::Srgb => Notation::Rgb,
+            Space::Rgb(RgbSpace::SrgbLinear) => Notation::SrgbLinear,
+            Space::Rgb(RgbSpace::DisplayP3) => Notation::DisplayP3,
+            Space::Rgb(RgbSpace::Rec2020) => Notation::Rec2020,
+            Space::Rgb(RgbSpace::A98Rgb) => Notation::A98Rgb,
+            Space::Rgb(RgbSpace::ProphotoRgb) => Notation::ProphotoRgb,
+            Space::XyzD50 | Space::XyzD65 => Notation::Xyz,
+            Space::Hsl => Notation::Hsl,
+            Space::Hwb => Notation::Hwb,
+            Space::Lab => Notation::Lab,
+            Space::Lch => Notation::Hcl,
+            Space::Oklab => Notation::Oklab,
+            Space::Oklch => Notation::Oklch,
+        }
+    }
+
+    /// Creates a color from the channels of this space.
+    ///
+    /// Saturation, lightness, whiteness and blackness range from 0 to 100, the other
+    /// channels use the ranges of their CSS functions. CIELAB is relative to D50, as in CSS.
+    fn to_color(self, [first, second, third]: [f32; 3], alpha: f32) -> Color {
+        let from_srgb = |rgb: Srgb| Color::unclamped(rgb.with_alpha(alpha));
+        let from_lab = |lab: Lab<Any, f32>| Color::from_lab(lab, d50(), alpha);
+        let from_oklab = |oklab: Oklab| {
+            let linear: LinSrgb = oklab.into_color_unclamped();
+            Color::from_linear(linear.with_alpha(alpha))
+        };
+
+        match self {
+            Space::Srgb => from_srgb(Srgb::new(first, second, third)),
+            Space::Rgb(space) => space.to_color([first, second, third], alpha),
+            Space::XyzD50 => Color::from_xyz(Xyz::new(first, second, third), d50(), alpha),
+            Space::XyzD65 => Color::from_xyz(
+                Xyz::new(first, second, third),
+                ReferenceWhite::default(),
+                alpha,
+            ),
+            Space::Hsl => from_srgb(
+                Hsl::new_srgb(first, second / 100.0, third / 100.0).into_color_unclamped(),
+            ),
+            Space::Hwb => {
+                let (whiteness, blackness) = (second / 100.0, third / 100.0);
+                if whiteness + blackness >= 1.0 {
+                    let gray = whiteness / (whiteness + blackness);
+                    from_srgb(Srgb::new(gray, gray, gray))
+                } else {
+                    from_srgb(Hwb::new_srgb(first, whiteness, blackness).into_color_unclamped())
+                }
+            }
+            Space::Lab => from_lab(Lab::new(first, second, third)),
+            Space::Lch => from_lab(Lch::new(first, second, third).into_color_unclamped()),
+            Space::Oklab => from_oklab(Oklab::new(first, second, third)),
+            Space::Oklch => from_oklab(Oklch::new(first, second, third).into_color_unclamped()),
+        }
+    }
+
+    /// Returns the channels of the color in this space, see [`Space::to_color`].
+    fn channels_of(self, color: Color) -> [f32; 3] {
+        match self {
+            Space::Srgb => [color.red, color.green, color.blue],
+            Space::Rgb(space) => space.to_rgb(color),
+            Space::XyzD50 | Space::XyzD65 => {
+                let white = if self == Space::XyzD50 {
+                    d50()
+                } else {
+                    ReferenceWhite::default()
+                };
+                let xyz = color.to_xyz(white);
+                [xyz.x, xyz.y, xyz.z]
+            }
+            Space::Hsl => {
+                let hsl = Hsl::from_color_unclamped(color.color);
+                [
+                    hsl.hue.into_positive_degrees(),
+                    hsl.saturation * 100.0,
+                    hsl.lightness * 100.0,
+                ]
+            }
+            Space::Hwb => {
+                let hwb = Hwb::from_color_unclamped(color.color);
+                [
+                    hwb.hue.into_positive_degrees(),
+                    hwb.whiteness * 100.0,
+                    hwb.blackness * 100.0,
+                ]
+            }
+            Space::Lab => {
+                let lab = color.to_lab(d50());
+                [lab.l, lab.a, lab.b]
+            }
+            Space::Lch => {
+                let lch = Lch::from_color_unclamped(color.to_lab(d50()));
+                [lch.l, lch.chroma, lch.hue.into_positive_degrees()]
+            }
+            Space::Oklab => {
+                let oklab = color.to_oklab();
+                [oklab.l, oklab.a, oklab.b]
+            }
+            Space::Oklch => {
+                let oklch = Oklch::from_color_unclamped(color.to_oklab());
+                [oklch.l, oklch.chroma, oklch.hue.into_positive_degrees()]
+            }
+        }
+    }
+}
+
+/// Reference white of CIELAB and `xyz-d50` in CSS.
+fn d50() -> ReferenceWhite {
+    ReferenceWhite::new(Illuminant::D50, StandardObserver::Two)
+}
+
+/// A color in one of the CSS color spaces.
+///
+/// Missing components, e.g. specified as `none`, are [`None`]. They are treated as zero,
+/// except when mixing colors.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct CssColor {
+    space: Space,
+    channels: [Option<f32>; 3],
+    alpha: Option<f32>,
+}
+
+impl CssColor {
+    fn new(space: Space, channels: [f32; 3], alpha: f32) -> Self {
+        Self {
+            space,
+            channels: channels.map(Some),
+            alpha: Some(alpha),
+        }
+    }
+
+    fn to_color(self) -> Color {
+        self.space.to_color(
+            self.channels.map(|channel| channel.unwrap_or(0.0)),
+            self.alpha.unwrap_or(0.0).clamp(0.0, 1.0),
+        )
+    }
+
+    /// Converts the color into the space.
+    ///
+    /// Missing components are kept if the space does not change. Otherwise the hue of
+    /// achromatic colors is powerless, so it becomes missing.
+    fn convert(self, space: Space) -> Self {
+        if self.space == space {
+            return self;
+        }
+
+        let mut channels = space.channels_of(self.to_color()).map(Some);
+        let powerless = match (space, channels) {
+            (Space::Hsl, [_, Some(saturation), _]) => saturation.abs() < 0.001,
+            (Space::Hwb, [_, Some(whiteness), Some(blackness)]) => whiteness + blackness >= 99.999,
+            (Space::Lch, [_, Some(chroma), _]) => chroma < LCH_ACHROMATIC,
+            (Space::Oklch, [_, Some(chroma), _]) => chroma < OKLCH_ACHROMATIC,
+            _ => false,
+        };
+        if let Some(hue) = space.hue().filter(|_| powerless) {
+            channels[hue] = None;
+        }
+
+        Self {
+            space,
+            channels,
+            alpha: self.alpha,
+        }
+    }
+
+    /// Clamps the channel to the range, keeping it missing.
+    fn clamp(mut self, index: usize, min: f32, max: f32) -> Self {
+        self.channels[index] = self.channels[index].map(|channel| channel.clamp(min, max));
+        self
+    }
+}
+
+/// Types of numeric values.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Kind {
+    Number,
+    /// Percentages range from 0 to 100.
+    Percentage,
+    /// Angles are always converted to degrees.
+    Angle,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Value {
+    kind: Kind,
+    value: f32,
+}
+
+impl Value {
+    fn number(value: f32) -> Self {
+        Self {
+            kind: Kind::Number,
+            value,
+        }
+    }
+
+    fn angle(degrees: f32) -> Self {
+        Self {
+            kind: Kind::Angle,
+            value: degrees,
+        }
+    }
+
+    fn with_value(self, value: f32) -> Self {
+        Self { value, ..self }
+    }
+}
+
+/// Values of the channel keywords of a relative color, e.g. `r` in `rgb(from red r g b)`.
+type Keywords<'k> = &'k [(&'static str, f32)];
+
+/// Accepted values of a channel.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Slot {
+    /// A number or an angle, in degrees.
+    Hue,
+    /// A number or a percentage of the given reference value.
+    Value(f32),
+}
+
+impl Slot {
+    /// Returns the channel value, or [`None`] if the type is not accepted.
+    fn resolve(self, value: Value) -> Option<f32> {
+        match (self, value.kind) {
+            (Slot::Hue, Kind::Number | Kind::Angle) | (Slot::Value(_), Kind::Number) => {
+                Some(value.value)
+            }
+            (Slot::Value(reference), Kind::Percentage) => Some(value.value / 100.0 * reference),
+            _ => None,
+        }
+    }
+}
+
+fn error<T>(input: &str) -> IResult<&str, T> {
+    Err(nom::Err::Error(Error::new(input, ErrorKind::Verify)))
+}
+
+fn is_ident_char(char: char) -> bool {
+    char.is_ascii_alphanumeric() || char == '-' || char == '_' || !char.is_ascii()
+}
+
+/// Parses the keyword, ignoring the case, unless it is only the start of a longer identifier.
+fn keyword<'a>(
+    name: &'static str,
+) -> impl Parser<&'a str, Output = &'a str, Error = Error<&'a str>> {
+    terminated(tag_no_case(name), not(satisfy(is_ident_char)))
+}
+
+fn comma(input: &str) -> IResult<&str, char> {
+    whitespace(char(',')).parse(input)
+}
+
+/// Parses a number, such as `-1.5`, `.5` or `1e3`.
+pub(super) fn number(input: &str) -> IResult<&str, f32> {
+    let (rest, digits) = recognize((
+        opt(one_of("+-")),
+        alt((
+            recognize((digit1, opt((char('.'), digit1)))),
+            recognize((char('.'), digit1)),
+        )),
+        opt((one_of("eE"), opt(one_of("+-")), digit1)),
+    ))
+    .parse(input)?;
+    match digits.parse() {
+        Ok(number) => Ok((rest, number)),
+        Err(_) => error(input),
+    }
+}
+
+/// Parses a number, which may be followed by `%` or an angle unit.
+fn dimension(input: &str) -> IResult<&str, Value> {
+    let (input, number) = number(input)?;
+    alt((
+        value(
+            Value {
+                kind: Kind::Percentage,
+                value: number,
+            },
+            char('%'),
+        ),
+        value(Value::angle(number), keyword("deg")),
+        value(Value::angle(number * 0.9), keyword("grad")),
+        value(Value::angle(number.to_degrees()), keyword("rad")),
+        value(Value::angle(number * 360.0), keyword("turn")),
+        value(Value::number(number), not(satisfy(is_ident_char))),
+    ))
+    .parse(input)
+}
+
+fn constant(input: &str) -> IResult<&str, Value> {
+    map(
+        alt((
+            value(std::f32::consts::E, keyword("e")),
+            value(std::f32::consts::PI, keyword("pi")),
+            value(f32::INFINITY, keyword("infinity")),
+            value(f32::NEG_INFINITY, keyword("-infinity")),
+            value(f32::NAN, keyword("nan")),
+        )),
+        Value::number,
+    )
+    .parse(input)
+}
+
+fn channel_keyword<'a>(input: &'a str, keywords: Keywords) -> IResult<&'a str, Value> {
+    keywords
+        .iter()
+        .find_map(|&(name, value)| {
+            keyword(name)
+                .parse(input)
+                .ok()
+                .map(|(rest, _)| (rest, Value::number(value)))
+        })
+        .map_or_else(|| error(input), Ok)
+}
+
+/// Parses a channel value, which may be `none` ([`None`]), a number, percentage or angle,
+/// a math function like `calc()` or a channel keyword of the relative color.
+fn component<'a>(input: &'a str, keywords: Keywords) -> IResult<&'a str, Option<Value>> {
+    if let Ok((rest, _)) = keyword("none").parse(input) {
+        return Ok((rest, None));
+    }
+    if let Ok((rest, value)) = math_function(input, keywords) {
+        // NaN is censored to zero and infinite values to the largest finite values
+        let censored = if value.value.is_nan() {
+            0.0
+        } else {
+            value.value.clamp(f32::MIN, f32::MAX)
+        };
+        return Ok((rest, Some(value.with_value(censored))));
+    }
+    if let Ok((rest, value)) = channel_keyword(input, keywords) {
+        return Ok((rest, Some(value)));
+    }
+    map(dimension, Some).parse(input)
+}
+
+/// Parses a math function, such as `calc(r * 0.5)` or `clamp(0, h, 180)`.
+///
+/// Based on <https://www.w3.org/TR/css-values-4/#math>
+fn math_function<'a>(input: &'a str, keywords: Keywords) -> IResult<&'a str, Value> {
+    let (rest, name) = terminated(take_while1(is_ident_char), char('(')).parse(input)?;
+    let name = name.to_ascii_lowercase();
+    let (rest, _) = multispace0(rest)?;
+
+    let (rest, result) = if name == "round" {
+        let (rest, strategy) = opt(terminated(
+            alt((
+                keyword("nearest"),
+                keyword("up"),
+                keyword("down"),
+                keyword("to-zero"),
+            )),
+            comma,
+        ))
+        .parse(rest)?;
+        let (rest, arguments) = math_arguments(rest, keywords)?;
+        (rest, round(strategy.unwrap_or("nearest"), &arguments))
+    } else {
+        let (rest, arguments) = math_arguments(rest, keywords)?;
+        (rest, evaluate(&name, &arguments))
+    };
+
+    let (rest, _) = preceded(multispace0, char(')')).parse(rest)?;
+    match result {
+        Some(result) => Ok((rest, result)),
+        None => error(input),
+    }
+}
+
+fn math_arguments<'a>(input: &'a str, keywords: Keywords) -> IResult<&'a str, Vec<Value>> {
+    separated_list1(comma, |input| calc_sum(input, keywords)).parse(input)
+}
+
+/// Evaluates the math function, returns [`None`] if the function or arguments are invalid.
+fn evaluate(name: &str, arguments: &[Value]) -> Option<Value> {
+    let same_kind = arguments
+        .iter()
+        .all(|argument| argument.kind == arguments[0].kind);
+    let radians = |value: Value| match value.kind {
+        Kind::Number => Some(value.value),
+        Kind::Angle => Some(value.value.to_radians()),
+        Kind::Percentage => None,
+    };
+    let number = |value: Value| (value.kind == Kind::Number).then_some(value.value);
+
+    match (name, arguments) {
+        ("calc", [value]) => Some(*value),
+        ("min", _) if same_kind => arguments
+            .iter()
+            .copied()
+            .reduce(|a, b| if b.value < a.value { b } else { a }),
+        ("max", _) if same_kind => arguments
+            .iter()
+            .copied()
+            .reduce(|a, b| if b.value > a.value { b } else { a }),
+        ("clamp", [min, value, max]) if same_kind => {
+            Some(value.with_value(value.value.min(max.value).max(min.value)))
+        }
+        ("abs", [value]) => Some(value.with_value(value.value.abs())),
+        ("sign", [value]) => Some(Value::number(if value.value == 0.0 {
+            0.0
+        } else {
+            value.value.signum()
+        })),
+        ("mod", [a, b]) if same_kind => {
+            Some(a.with_value(a.value - b.value * (a.value / b.value).floor()))
+        }
+        ("rem", [a, b]) if same_kind => Some(a.with_value(a.value % b.value)),
+        ("sin", [value]) => radians(*value).map(|value| Value::number(value.sin())),
+        ("cos", [value]) => radians(*value).map(|value| Value::number(value.cos())),
+        ("tan", [value]) => radians(*value).map(|value| Value::number(value.tan())),
+        ("asin", [value]) => number(*value).map(|value| Value::angle(value.asin().to_degrees())),
+        ("acos", [value]) => number(*value).map(|value| Value::angle(value.acos().to_degrees())),
+        ("atan", [value]) => number(*value).map(|value| Value::angle(value.atan().to_degrees())),
+        ("atan2", [a, b]) if same_kind => Some(Value::angle(a.value.atan2(b.value).to_degrees())),
+        ("pow", [a, b]) => Some(Value::number(number(*a)?.powf(number(*b)?))),
+        ("sqrt", [value]) => number(*value).map(|value| Value::number(value.sqrt())),
+        ("hypot", _) if same_kind => Some(
+            arguments[0].with_value(
+                arguments
+                    .iter()
+                    .map(|argument| argument.value.powi(2))
+                    .sum::<f32>()
+                    .sqrt(),
+            ),
+        ),
+        ("log", [value]) => number(*value).map(|value| Value::number(value.ln())),
+        ("log", [value, base]) => Some(Value::number(number(*value)?.log(number(*base)?))),
+        ("exp", [value]) => number(*value).map(|value| Value::number(value.exp())),
+        _ => None,
+    }
+}
+
+/// Evaluates `round()` with the rounding strategy.
+fn round(strategy: &str, arguments: &[Value]) -> Option<Value> {
+    let (value, step) = match arguments {
+        [value] if value.kind == Kind::Number => (*value, 1.0),
+        [value, step] if value.kind == step.kind => (*value, step.value),
+        _ => return None,
+    };
+    let multiple = value.value / step;
+    let rounded = match strategy.to_ascii_lowercase().as_str() {
+        "up" => multiple.ceil(),
+        "down" => multiple.floor(),
+        "to-zero" => multiple.trunc(),
+        // halfway values are rounded up
+        _ => (multiple + 0.5).floor(),
+    };
+    Some(value.with_value(rounded * step))
+}
+
+/// Parses a sum of a calculation, e.g. `r + 10`.
+///
+/// Whitespace around the `+` and `-` operators is required, so they are not
+/// confused with signs of numbers.
+fn calc_sum<'a>(input: &'a str, keywords: Keywords) -> IResult<&'a str, Value> {
+    let (mut input, mut sum) = calc_product(input, keywords)?;
+    loop {
+        let operator: IResult<&str, char> =
+            preceded(multispace1, terminated(one_of("+-"), multispace1)).parse(input);
+        let Ok((rest, operator)) = operator else {
+            return Ok((input, sum));
+        };
+        let (rest, operand) = calc_product(rest, keywords)?;
+        if operand.kind != sum.kind {
+            return error(input);
+        }
+        sum.value = match operator {
+            '+' => sum.value + operand.value,
+            _ => sum.value - operand.value,
+        };
+        input = rest;
+    }
+}
+
+/// Parses a product of a calculation, e.g. `r * 0.5`.
+///
+/// At least one side of a multiplication and the divisor have to be numbers.
+fn calc_product<'a>(input: &'a str, keywords: Keywords) -> IResult<&'a str, Value> {
+    let (mut input, mut product) = calc_value(input, keywords)?;
+    loop {
+        let operator: IResult<&str, char> =
+            preceded(multispace0, terminated(one_of("*/"), multispace0)).parse(input);
+        let Ok((rest, operator)) = operator else {
+            return Ok((input, product));
+        };
+        let (rest, operand) = calc_value(rest, keywords)?;
+        product = match (operator, product.kind, operand.kind) {
+            ('*', Kind::Number, _) => operand.with_value(product.value * operand.value),
+            ('*', _, Kind::Number) => product.with_value(product.value * operand.value),
+            ('/', _, Kind::Number) => product.with_value(product.value / operand.value),
+            _ => return error(input),
+        };
+        input = rest;
+    }
+}
+
+fn calc_value<'a>(input: &'a str, keywords: Keywords) -> IResult<&'a str, Value> {
+    if let Ok((rest, _)) = char::<&str, Error<&str>>('(').parse(input) {
+        let (rest, _) = multispace0(rest)?;
+        let (rest, value) = calc_sum(rest, keywords)?;
+        let (rest, _) = preceded(multispace0, char(')')).parse(rest)?;
+        return Ok((rest, value));
+    }
+    if let Ok(result) = math_function(input, keywords) {
+        return Ok(result);
+    }
+    if let Ok(result) = constant(input) {
+        return Ok(result);
+    }
+    if let Ok(result) = channel_keyword(input, keywords) {
+        return Ok(result);
+    }
+    dimension(input)
+}
+
+/// Parses the alpha value, a number or percentage, which is clamped between 0.0 and 1.0.
+fn alpha(value: Option<Value>) -> Option<Option<f32>> {
+    match value {
+        None => Some(None),
+        Some(value) => Slot::Value(1.0)
+            .resolve(value)
+            .map(|alpha| Some(alpha.clamp(0.0, 1.0))),
+    }
+}
+
+/// Parses the `from <color>` prefix of the relative color syntax.
+fn relative_origin(input: &str) -> IResult<&str, Option<CssColor>> {
+    let (input, _) = multispace0(input)?;
+    opt(map(
+        preceded(
+            terminated(keyword("from"), multispace0),
+            terminated(css_color_value, multispace0),
+        ),
+        |(_, color)| color,
+    ))
+    .parse(input)
+}
+
+/// Parses the channels of the modern syntax, e.g. `10 20% none / 50%)`, including the
+/// closing parenthesis.
+///
+/// Channel values are divided by the scale, to convert them into the units of the space.
+/// The channel keywords of a relative color are converted into the units of the function.
+fn modern(
+    input: &str,
+    space: Space,
+    slots: [Slot; 3],
+    scale: f32,
+    origin: Option<CssColor>,
+) -> IResult<&str, CssColor> {
+    let origin = origin.map(|origin| origin.convert(space));
+    let keywords = origin
+        .map(|origin| {
+            let [first, second, third] = space.keywords();
+            let values = origin
+                .channels
+                .map(|channel| channel.unwrap_or(0.0) * scale);
+            vec![
+                (first, values[0]),
+                (second, values[1]),
+                (third, values[2]),
+                ("alpha", origin.alpha.unwrap_or(0.0)),
+            ]
+        })
+        .unwrap_or_default();
+
+    let (input, values) = many_m_n(
+        3,
+        3,
+        preceded(multispace0, |input| component(input, &keywords)),
+    )
+    .parse(input)?;
+    let (input, alpha_value) = opt(preceded((multispace0, char('/'), multispace0), |input| {
+        component(input, &keywords)
+    }))
+    .parse(input)?;
+    let (input, _) = preceded(multispace0, char(')')).parse(input)?;
+
+    let mut channels = [None; 3];
+    for (channel, (value, slot)) in channels.iter_mut().zip(values.into_iter().zip(slots)) {
+        if let Some(value) = value {
+            let Some(resolved) = slot.resolve(value) else {
+                return error(input);
+            };
+            *channel = Some(resolved / scale);
+        }
+    }
+
+    // the alpha value of relative colors defaults to the alpha value of the origin color
+    let alpha = match alpha_value {
+        Some(value) => match alpha(value) {
+            Some(alpha) => alpha,
+            None => return error(input),
+        },
+        None => origin.map_or(Some(1.0), |origin| origin.alpha),
+    };
+
+    Ok((
+        input,
+        CssColor {
+            space,
+            channels,
+            alpha,
+        },
+    ))
+}
+
+/// Parses the comma separated channels of the legacy syntax, e.g. `255, 0, 0, 0.5)`,
+/// including the closing parenthesis. Neither `none` nor channel keywords are allowed.
+fn legacy(input: &str) -> IResult<&str, (Vec<Value>, Option<f32>)> {
+    let legacy_component = |input| match component(input, &[])? {
+        (rest, Some(value)) => Ok((rest, value)),
+        (_, None) => error(input),
+    };
+
+    let (input, (_, first, _, second, _, third)) = (
+        multispace0,
+        legacy_component,
+        comma,
+        legacy_component,
+        comma,
+        legacy_component,
+    )
+        .parse(input)?;
+    let (input, alpha_value) = opt(preceded(comma, legacy_component)).parse(input)?;
+    let (input, _) = preceded(multispace0, char(')')).parse(input)?;
+
+    let alpha = match alpha_value {
+        Some(value) => match alpha(Some(value)) {
+            Some(alpha) => alpha,
+            None => return error(input),
+        },
+        None => Some(1.0),
+    };
+    Ok((input, (vec![first, second, third], alpha)))
+}
+
+/// Parses `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa` with [`hex_color`], without the
+/// whitespace it allows between the digits.
+fn css_hex(input: &str) -> IResult<&str, (Notation, CssColor)> {
+    let (rest, digits) = terminated(
+        preceded(
+            char('#'),
+            take_while1(|char: char| char.is_ascii_hexdigit()),
+        ),
+        not(satisfy(is_ident_char)),
+    )
+    .parse(input)?;
+    let (_, color) =
+        terminated(|digits| hex_color(digits, AlphaPosition::End), eof).parse(digits)?;
+
+    Ok((
+        rest,
+        (
+            Notation::Hex,
+            CssColor::new(
+                Space::Srgb,
+                [color.red, color.green, color.blue],
+                color.alpha,
+            ),
+        ),
+    ))
+}
+
+/// Parses a named color, such as `rebeccapurple`, or `transparent`.
+fn named(input: &str) -> IResult<&str, (Notation, CssColor)> {
+    let (rest, name) = take_while1(is_ident_char).parse(input)?;
+    if name.eq_ignore_ascii_case("transparent") {
+        return Ok((
+            rest,
+            (
+                Notation::Name,
+                CssColor::new(Space::Srgb, [0.0, 0.0, 0.0], 0.0),
+            ),
+        ));
+    }
+
+    match color_names::color(name, ColorNameSources::Html | ColorNameSources::Svg) {
+        Some(color) => Ok((
+            rest,
+            (
+                Notation::Name,
+                CssColor::new(Space::Srgb, [color.red, color.green, color.blue], 1.0),
+            ),
+        )),
+        None => error(input),
+    }
+}
+
+/// Parses `rgb()` and `rgba()`, in the legacy, modern and relative syntax.
+fn css_rgb(input: &str) -> IResult<&str, (Notation, CssColor)> {
+    let (input, _) = alt((tag_no_case("rgba("), tag_no_case("rgb("))).parse(input)?;
+    let (input, origin) = relative_origin(input)?;
+
+    let legacy_rgb = |input| {
+        let (rest, (values, alpha)) = legacy(input)?;
+        let kind = values[0].kind;
+        let reference = match kind {
+            Kind::Number => 255.0,
+            Kind::Percentage => 100.0,
+            Kind::Angle => return error(input),
+        };
+        if values.iter().any(|value| value.kind != kind) {
+            return error(input);
+        }
+        let channels = [0, 1, 2].map(|i| Some(values[i].value / reference));
+        Ok((
+            rest,
+            CssColor {
+                space: Space::Srgb,
+                channels,
+                alpha,
+            },
+        ))
+    };
+    let modern_rgb = |input| modern(input, Space::Srgb, [Slot::Value(255.0); 3], 255.0, origin);
+
+    let (input, color) = if origin.is_some() {
+        modern_rgb(input)?
+    } else {
+        alt((legacy_rgb, modern_rgb)).parse(input)?
+    };
+
+    let color = color
+        .clamp(0, 0.0, 1.0)
+        .clamp(1, 0.0, 1.0)
+        .clamp(2, 0.0, 1.0);
+    Ok((input, (Notation::Rgb, color)))
+}
+
+/// Parses `hsl()` and `hsla()`, in the legacy, modern and relative syntax.
+fn css_hsl(input: &str) -> IResult<&str, (Notation, CssColor)> {
+    let (input, _) = alt((tag_no_case("hsla("), tag_no_case("hsl("))).parse(input)?;
+    let (input, origin) = relative_origin(input)?;
+
+    let legacy_hsl = |input| {
+        let (rest, (values, alpha)) = legacy(input)?;
+        let (Some(hue), Kind::Percentage, Kind::Percentage) =
+            (Slot::Hue.resolve(values[0]), values[1].kind, values[2].kind)
+        else {
+            return error(input);
+        };
+        Ok((
+            rest,
+            CssColor {
+                space: Space::Hsl,
+                channels: [Some(hue), Some(values[1].value), Some(values[2].value)],
+                alpha,
+            },
+        ))
+    };
+    let slots = [Slot::Hue, Slot::Value(100.0), Slot::Value(100.0)];
+    let modern_hsl = |input| modern(input, Space::Hsl, slots, 1.0, origin);
+
+    let (input, color) = if origin.is_some() {
+        modern_hsl(input)?
+    } else {
+        alt((legacy_hsl, modern_hsl)).parse(input)?
+    };
+
+    let color = color.clamp(1, 0.0, 100.0).clamp(2, 0.0, 100.0);
+    Ok((input, (Notation::Hsl, color)))
+}
+
+/// Parses the functions, which only support the modern and relative syntax.
+fn modern_function(input: &str) -> IResult<&str, (Notation, CssColor)> {
+    let (input, name) = terminated(take_while1(is_ident_char), char('(')).parse(input)?;
+    let (space, slots) = match name.to_ascii_lowercase().as_str() {
+        "hwb" => (
+            Space::Hwb,
+            [Slot::Hue, Slot::Value(100.0), Slot::Value(100.0)],
+        ),
+        "lab" => (
+            Space::Lab,
+            [Slot::Value(100.0), Slot::Value(125.0), Slot::Value(125.0)],
+        ),
+        "lch" => (
+            Space::Lch,
+            [Slot::Value(100.0), Slot::Value(150.0), Slot::Hue],
+        ),
+        "oklab" => (
+            Space::Oklab,
+            [Slot::Value(1.0), Slot::Value(0.4), Slot::Value(0.4)],
+        ),
+        "oklch" => (
+            Space::Oklch,
+            [Slot::Value(1.0), Slot::Value(0.4), Slot::Hue],
+        ),
+        _ => return error(input),
+    };
+
+    let (input, origin) = relative_origin(input)?;
+    let (input, color) = modern(input, space, slots, 1.0, origin)?;
+
+    let color = match space {
+        Space::Hwb => color.clamp(1, 0.0, 100.0).clamp(2, 0.0, 100.0),
+        Space::Lab => color.clamp(0, 0.0, 100.0),
+        Space::Lch => color.clamp(0, 0.0, 100.0).clamp(1, 0.0, f32::MAX),
+        Space::Oklab => color.clamp(0, 0.0, 1.0),
+        _ => color.clamp(0, 0.0, 1.0).clamp(1, 0.0, f32::MAX),
+    };
+    Ok((input, (space.notation(), color)))
+}
+
+/// Parses the `color()` function with a predefined color space, e.g. `color(display-p3 1 0 0)`.
+fn css_color_function(input: &str) -> IResult<&str, (Notation, CssColor)> {
+    let (input, _) = tag_no_case("color(").parse(input)?;
+    let (input, origin) = relative_origin(input)?;
+    let (rest, name) = take_while1(is_ident_char).parse(input)?;
+    let Some(space) = Space::from_name(name).filter(|space| space.is_predefined()) else {
+        return error(input);
+    };
+
+    let (rest, color) = modern(rest, space, [Slot::Value(1.0); 3], 1.0, origin)?;
+    Ok((rest, (space.notation(), color)))
+}
+
+/// Parses the color interpolation method, e.g. `in oklch longer hue`.
+fn interpolation_method(input: &str) -> IResult<&str, (Space, HueInterpolation)> {
+    let (input, _) = terminated(keyword("in"), multispace1).parse(input)?;
+    let (rest, name) = take_while1(is_ident_char).parse(input)?;
+    let Some(space) = Space::from_name(name) else {
+        return error(input);
+    };
+
+    let (rest, hue_interpolation) = opt(preceded(
+        multispace1,
+        terminated(
+            alt((
+                value(HueInterpolation::Shorter, keyword("shorter")),
+                value(HueInterpolation::Longer, keyword("longer")),
+                value(HueInterpolation::Increasing, keyword("increasing")),
+                value(HueInterpolation::Decreasing, keyword("decreasing")),
+            )),
+            (multispace1, keyword("hue")),
+        ),
+    ))
+    .parse(rest)?;
+
+    if hue_interpolation.is_some() && space.hue().is_none() {
+        return error(input);
+    }
+    Ok((rest, (space, hue_interpolation.unwrap_or_default())))
+}
+
+/// Parses a percentage between 0% and 100% of a color in `color-mix()`, as a fraction.
+fn mix_percentage(input: &str) -> IResult<&str, f32> {
+    match component(input, &[])? {
+        (
+            rest,
+            Some(Value {
+                kind: Kind::Percentage,
+                value,
+            }),
+        ) if (0.0..=100.0).contains(&value) => Ok((rest, value / 100.0)),
+        _ => error(input),
+    }
+}
+
+/// Parses a color of `color-mix()` with its optional percentage, which can be in front of or behind the color.
+fn mix_color(input: &str) -> IResult<&str, (CssColor, Option<f32>)> {
+    alt((
+        map(
+            (mix_percentage, multispace0, css_color_value),
+            |(percentage, _, (_, color))| (color, Some(percentage)),
+        ),
+        map(
+            (css_color_value, opt(preceded(multispace0, mix_percentage))),
+            |((_, color), percentage)| (color, percentage),
+        ),
+    ))
+    .parse(input)
+}
+
+/// Parses `color-mix()`, e.g. `color-mix(in oklch, red 40%, blue)`.
+///
+/// Without an interpolation method, colors are mixed in Oklab.
+fn color_mix(input: &str) -> IResult<&str, (Notation, CssColor)> {
+    let (input, _) = (tag_no_case("color-mix("), multispace0).parse(input)?;
+    let (input, method) = opt(terminated(interpolation_method, comma)).parse(input)?;
+    let (space, hue_interpolation) = method.unwrap_or((Space::Oklab, HueInterpolation::Shorter));
+
+    let (input, first) = mix_color(input)?;
+    let (input, _) = comma(input)?;
+    let (input, second) = mix_color(input)?;
+    let (rest, _) = preceded(multispace0, char(')')).parse(input)?;
+
+    match mix(space, hue_interpolation, first, second) {
+        Some(color) => Ok((rest, (space.notation(), color))),
+        None => error(input),
+    }
+}
+
+/// Mixes two colors with their optional percentages in the color space.
+///
+/// Returns [`None`] if both percentages are zero.
+/// Based on <https://www.w3.org/TR/css-color-5/#color-mix-result>
+fn mix(
+    space: Space,
+    hue_interpolation: HueInterpolation,
+    (first, first_percentage): (CssColor, Option<f32>),
+    (second, second_percentage): (CssColor, Option<f32>),
+) -> Option<CssColor> {
+    let (first_weight, second_weight) = match (first_percentage, second_percentage) {
+        (None, None) => (0.5, 0.5),
+        (Some(first), None) => (first, 1.0 - first),
+        (None, Some(second)) => (1.0 - second, second),
+        (Some(first), Some(second)) => (first, second),
+    };
+    let sum = first_weight + second_weight;
+    if sum <= 0.0 {
+        return None;
+    }
+    // percentages summing to less than 100% make the result transparent
+    let alpha_multiplier = sum.min(1.0);
+    let (first_weight, second_weight) = (first_weight / sum, second_weight / sum);
+
+    let first = first.convert(space);
+    let second = second.convert(space);
+
+    // missing components take the value of the other color
+    let pair = |first: Option<f32>, second: Option<f32>| (first.or(second), second.or(first));
+    let (first_alpha, second_alpha) = pair(first.alpha, second.alpha);
+    let alpha = first_alpha
+        .zip(second_alpha)
+        .map(|(first, second)| first * first_weight + second * second_weight);
+
+    let mut channels = [None; 3];
+    for (index, channel) in channels.iter_mut().enumerate() {
+        let (Some(first_value), Some(second_value)) =
+            pair(first.channels[index], second.channels[index])
+        else {
+            continue;
+        };
+
+        *channel = Some(if space.hue() == Some(index) {
+            let (first_value, second_value) = hue_interpolation.adjust(first_value, second_value);
+            first_value * first_weight + second_value * second_weight
+        } else {
+            // interpolated with premultiplied alpha
+            let premultiplied = first_value * first_alpha.unwrap_or(1.0) * first_weight
+                + second_value * second_alpha.unwrap_or(1.0) * second_weight;
+            match alpha {
+                Some(alpha) if alpha != 0.0 => premultiplied / alpha,
+                _ => premultiplied,
+            }
+        });
+    }
+
+    Some(CssColor {
+        space,
+        channels,
+        alpha: alpha.map(|alpha| alpha * alpha_multiplier),
+    })
+}
+
+/// Mixes two colors in the space, with the ratio as amount of the second color, see [`super::mix::mix`].
+pub(super) fn mix_colors(
+    first: Color,
+    second: Color,
+    ratio: f32,
+    space: MixSpace,
+    hue_interpolation: HueInterpolation,
+) -> Color {
+    let srgb = |color: Color| {
+        CssColor::new(
+            Space::Srgb,
+            [color.red, color.green, color.blue],
+            color.alpha,
+        )
+    };
+    let space = match space {
+        MixSpace::Srgb => Space::Srgb,
+        MixSpace::SrgbLinear => Space::Rgb(RgbSpace::SrgbLinear),
+        MixSpace::Lab => Space::Lab,
+        MixSpace::Oklab => Space::Oklab,
+        MixSpace::Oklch => Space::Oklch,
+    };
+
+    mix(
+        space,
+        hue_interpolation,
+        (srgb(first), Some(1.0 - ratio)),
+        (srgb(second), Some(ratio)),
+    )
+    .map_or(first, CssColor::to_color)
+}
+
+fn css_color_value(input: &str) -> IResult<&str, (Notation, CssColor)> {
+    let (input, _) = multispace0(input)?;
+    alt((
+        css_hex,
+        css_rgb,
+        css_hsl,
+        color_mix,
+        css_color_function,
+        modern_function,
+        named,
+    ))
+    .parse(input)
+}
+
+/// Parses a color in CSS syntax, as specified by CSS Color Module Level 4 and 5, returning the
+/// notation it is displayed in alongside the color.
+///
+/// This includes hex codes, named colors, the legacy and modern syntax of `rgb()`, `hsl()`,
+/// `hwb()`, `lab()`, `lch()`, `oklab()`, `oklch()` and `color()`, missing components (`none`),
+/// math functions like `calc()`, the relative color syntax (`from`) and `color-mix()`.
+/// System colors and `currentcolor` are not supported, as they depend on the context.
+///
+/// CIELAB values are relative to D50, as in CSS, regardless of the reference white in the settings.
+/// [`any_color`] parses `lab()` and `lch()` with the reference white in the settings instead.
+pub fn css_color(input: &str) -> IResult<&str, (Notation, Color)> {
+    map(css_color_value, |(notation, color)| {
+        (notation, color.to_color())
+    })
+    .parse(input)
+}
+
+/// Test cases are derived from the css-color tests of the web-platform-tests project,
+/// <https://github.com/web-platform-tests/wpt/tree/master/css/css-color/parsing>
+#[cfg(test)]
+mod parse_css_color {
+    use super::*;
+
+    fn parse(input: &str) -> Option<Color> {
+        match css_color(input) {
+            Ok(("", (_, color))) => Some(color),
+            _ => None,
+        }
+    }
+
+    /// Asserts that the input is parsed as the expected color, with a tolerance
+    /// for the rounding of the serialized values.
+    fn assert_rgba(input: &str, [red, green, blue, alpha]: [f32; 4]) {
+        let Some(color) = parse(input) else {
+            panic!("failed to parse {input}");
+        };
+        let actual = [
+            color.red * 255.0,
+            color.green * 255.0,
+            color.blue * 255.0,
+            color.alpha,
+        ];
+        assert!(
+            actual
+                .iter()
+                .zip([red, green, blue])
+                .all(|(actual, expected)| (actual - expected).abs() < 1.0)
+                && (actual[3] - alpha).abs() < 0.01,
+            "{input}: expected {:?}, got {actual:?}",
+            [red, green, blue, alpha]
+        );
+    }
+
+    /// Asserts that both inputs are parsed as the same color.
+    fn assert_equivalent(input: &str, expected: &str) {
+        let expected_color = parse(expected).expect("failed to parse expected color");
+        assert_rgba(
+            input,
+            [
+                expected_color.red * 255.0,
+                expected_color.green * 255.0,
+                expected_color.blue * 255.0,
+                expected_color.alpha,
+            ],
+        );
+    }
+
+    #[test]
+    fn it_parses_hex() {
+        assert_rgba("#fb0", [255.0, 187.0, 0.0, 1.0]);
+        assert_rgba("#fb08", [255.0, 187.0, 0.0, 0.533]);
+        assert_rgba("#ffbb00", [255.0, 187.0, 0.0, 1.0]);
+        assert_rgba("#FFBB0080", [255.0, 187.0, 0.0, 0.502]);
+    }
+
+    #[test]
+    fn it_parses_keywords() {
+        assert_rgba("transparent", [0.0, 0.0, 0.0, 0.0]);
+        assert_rgba("RebeccaPurple", [102.0, 51.0, 153.0, 1.0]);
+        assert_rgba("lime", [0.0, 255.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn it_parses_rgb() {
+        assert_rgba("rgb(255, 0, 0)", [255.0, 0.0, 0.0, 1.0]);
+        assert_rgba("rgba(255, 0, 0, 0.2)", [255.0, 0.0, 0.0, 0.2]);
+        assert_rgba("rgb(100%, 0%, 0%, 20%)", [255.0, 0.0, 0.0, 0.2]);
+        assert_rgba("rgb(255 0 0)", [255.0, 0.0, 0.0, 1.0]);
+        assert_rgba("rgb(255 0 0 / 0.2)", [255.0, 0.0, 0.0, 0.2]);
+        assert_rgba("rgb(100% 0 0 / 20%)", [255.0, 0.0, 0.0, 0.2]);
+        assert_rgba("rgba(255 0 0)", [255.0, 0.0, 0.0, 1.0]);
+        assert_rgba("RGB(255 0 0)", [255.0, 0.0, 0.0, 1.0]);
+        assert_rgba("rgb(300 -10 0 / 2)", [255.0, 0.0, 0.0, 1.0]);
+        assert_rgba("rgb(1e2, .5e1, .5e0)", [100.0, 5.0, 0.5, 1.0]);
+        assert_rgba("rgb(  10  ,  20,30  )", [10.0, 20.0, 30.0, 1.0]);
+        assert_rgba("rgb(2.5 5.5 7.5)", [2.5, 5.5, 7.5, 1.0]);
+    }
+
+    #[test]
+    fn it_parses_hsl_and_hwb() {
+        assert_rgba("hsl(120, 30%, 50%)", [89.0, 166.0, 89.0, 1.0]);
+        assert_rgba("hsla(120, 30%, 50%, 0.5)", [89.0, 166.0, 89.0, 0.5]);
+        assert_rgba("hsl(120 30% 50%)", [89.0, 166.0, 89.0, 1.0]);
+        assert_rgba("hsl(120 30 50)", [89.0, 166.0, 89.0, 1.0]);
+        assert_rgba("hsl(120deg 30% 50% / 50%)", [89.0, 166.0, 89.0, 0.5]);
+        assert_rgba("hsl(0.3333turn 30% 50%)", [89.0, 166.0, 89.0, 1.0]);
+        assert_rgba("hsl(133.3333grad 30% 50%)", [89.0, 166.0, 89.0, 1.0]);
+        assert_rgba("hsl(2.0944rad 30% 50%)", [89.0, 166.0, 89.0, 1.0]);
+        assert_rgba("hsl(480 30% 50%)", [89.0, 166.0, 89.0, 1.0]);
+        assert_rgba("hsl(120 -30% 50%)", [128.0, 128.0, 128.0, 1.0]);
+        assert_rgba("hwb(120 30% 50%)", [77.0, 128.0, 77.0, 1.0]);
+        assert_rgba("hwb(120 30% 50% / 0.5)", [77.0, 128.0, 77.0, 0.5]);
+        assert_rgba("hwb(90deg 50% 60%)", [116.0, 116.0, 116.0, 1.0]);
+    }
+
+    #[test]
+    fn it_parses_lab_spaces() {
+        // all colors are sRGB green (#008000)
+        let green = [0.0, 128.0, 0.0, 1.0];
+        assert_rgba("lab(46.2775% -47.5621 48.5837)", green);
+        assert_rgba("lab(46.2775 -38.04968% 38.86696%)", green);
+        assert_rgba("lch(46.2775% 67.9892 134.3912)", green);
+        assert_rgba("oklab(51.975% -0.1403 0.10768)", green);
+        assert_rgba("oklab(0.51975 -35.075% 26.92%)", green);
+        assert_rgba("oklch(51.975% 0.17686 142.495)", green);
+        assert_rgba("oklch(0.51975 44.215% 142.495deg / 100%)", green);
+        assert_rgba("lab(0 0 0 / 0.5)", [0.0, 0.0, 0.0, 0.5]);
+        assert_rgba("lab(110 0 0)", [255.0, 255.0, 255.0, 1.0]);
+    }
+
+    #[test]
+    fn it_parses_predefined_spaces() {
+        let green = [0.0, 128.0, 0.0, 1.0];
+        assert_rgba("color(srgb 0 0.50196 0)", green);
+        assert_rgba("color(srgb 0% 50.196% 0%)", green);
+        assert_rgba("color(srgb-linear 0 0.21586 0)", green);
+        assert_rgba("color(display-p3 0.21604 0.49418 0.13151)", green);
+        assert_rgba("color(xyz 0.07719 0.15438 0.02573)", green);
+        assert_rgba("color(xyz-d65 0.07719 0.15438 0.02573)", green);
+        assert_rgba("color(xyz-d50 0.08314 0.15475 0.02087)", green);
+        assert_rgba("color(srgb 1 0 0 / 0.5)", [255.0, 0.0, 0.0, 0.5]);
+
+        let color = parse("color(srgb 1.5 -0.5 0)").unwrap();
+        assert!(!color.is_in_gamut());
+    }
+
+    #[test]
+    fn it_parses_none() {
+        assert_rgba("rgb(none none none)", [0.0, 0.0, 0.0, 1.0]);
+        assert_rgba("rgb(none none none / none)", [0.0, 0.0, 0.0, 0.0]);
+        assert_rgba("rgb(128 none none)", [128.0, 0.0, 0.0, 1.0]);
+        assert_rgba("hsl(none none none)", [0.0, 0.0, 0.0, 1.0]);
+        assert_rgba("hsl(120 none 50%)", [128.0, 128.0, 128.0, 1.0]);
+        assert_rgba("hwb(none 100% 0%)", [255.0, 255.0, 255.0, 1.0]);
+        assert_equivalent("oklch(51.975% 0 none)", "oklab(51.975% 0 0)");
+        assert_equivalent("color(display-p3 none 1 none)", "color(display-p3 0 1 0)");
+    }
+
+    #[test]
+    fn it_parses_math_functions() {
+        assert_rgba("rgb(calc(255) 0 0)", [255.0, 0.0, 0.0, 1.0]);
+        assert_rgba("rgb(calc(255 / 2) 0 0)", [127.5, 0.0, 0.0, 1.0]);
+        assert_rgba("rgb(calc(50% + 25%) 0 0)", [191.25, 0.0, 0.0, 1.0]);
+        assert_rgba("rgb(calc(infinity) 0 0)", [255.0, 0.0, 0.0, 1.0]);
+        assert_rgba("rgb(calc(-infinity) 0 0)", [0.0, 0.0, 0.0, 1.0]);
+        assert_rgba("rgb(calc(NaN) 0 0)", [0.0, 0.0, 0.0, 1.0]);
+        assert_rgba("rgb(calc(10 * 10%) 0 0)", [255.0, 0.0, 0.0, 1.0]);
+        assert_rgba("rgb(calc(0.5 * (100 + 110)) 0 0)", [105.0, 0.0, 0.0, 1.0]);
+        assert_rgba(
+            "rgba(calc(50 * 2), 0, 0, calc(0.5))",
+            [100.0, 0.0, 0.0, 0.5],
+        );
+        assert_rgba(
+            "rgb(min(100, 20) max(10, 50) clamp(0, 500, 255))",
+            [20.0, 50.0, 255.0, 1.0],
+        );
+        assert_rgba(
+            "rgb(round(up, 10.2) abs(-20) calc(sign(-5) * -30))",
+            [11.0, 20.0, 30.0, 1.0],
+        );
+        assert_rgba("hsl(calc(90deg + 30deg) 100% 50%)", [0.0, 255.0, 0.0, 1.0]);
+        assert_rgba("hsl(calc(1turn / 3) 100% 50%)", [0.0, 255.0, 0.0, 1.0]);
+        assert_rgba("hsl(atan2(1, -1) 100% 50%)", [0.0, 255.0, 63.75, 1.0]);
+        assert_rgba("rgb(calc(255 * sin(pi / 2)) 0 0)", [255.0, 0.0, 0.0, 1.0]);
+        assert_rgba("rgb(mod(-300, 256) 0 0)", [212.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn it_parses_relative_colors() {
+        let purple = [102.0, 51.0, 153.0, 1.0];
+        assert_rgba("rgb(from rebeccapurple r g b)", purple);
+        assert_rgba("rgb(from rebeccapurple b g r)", [153.0, 51.0, 102.0, 1.0]);
+        assert_rgba("rgb(from rebeccapurple 0 0 0)", [0.0, 0.0, 0.0, 1.0]);
+        assert_rgba(
+            "rgb(from rebeccapurple r g b / 0.5)",
+            [102.0, 51.0, 153.0, 0.5],
+        );
+        assert_rgba(
+            "rgb(from rgb(20 40 60 / 80%) r g b)",
+            [20.0, 40.0, 60.0, 0.8],
+        );
+        assert_rgba(
+            "rgb(from rgb(20 40 60 / 80%) r g b / alpha)",
+            [20.0, 40.0, 60.0, 0.8],
+        );
+        assert_rgba(
+            "rgb(from rebeccapurple calc(r + 1) g b)",
+            [103.0, 51.0, 153.0, 1.0],
+        );
+        assert_rgba(
+            "rgb(from rebeccapurple calc(r * 2) calc(g * 2) calc(b * 2))",
+            [204.0, 102.0, 255.0, 1.0],
+        );
+        assert_rgba("rgba(from #663399 r g b)", purple);
+        assert_rgba("hsl(from rebeccapurple h s l)", purple);
+        assert_rgba("hwb(from rebeccapurple h w b)", purple);
+        assert_rgba("lab(from rebeccapurple l a b)", purple);
+        assert_rgba("lch(from rebeccapurple l c h)", purple);
+        assert_rgba("oklab(from rebeccapurple l a b)", purple);
+        assert_rgba("oklch(from rebeccapurple l c h)", purple);
+        assert_rgba("color(from rebeccapurple srgb r g b)", purple);
+        assert_rgba("color(from rebeccapurple display-p3 r g b)", purple);
+        assert_rgba("color(from rebeccapurple xyz-d50 x y z)", purple);
+        assert_equivalent(
+            "color(from color(srgb 0.25 0.5 0.75) srgb b g r)",
+            "color(srgb 0.75 0.5 0.25)",
+        );
+        assert_equivalent(
+            "hsl(from hsl(120 50% 50%) calc(h + 180) s l)",
+            "hsl(300 50% 50%)",
+        );
+        assert_equivalent(
+            "oklch(from oklch(0.7 0.1 200) l c calc(h - 40))",
+            "oklch(0.7 0.1 160)",
+        );
+        assert_equivalent("lab(from lab(25 20 50) l a b)", "lab(25 20 50)");
+        assert_equivalent("hsl(from white h s l)", "hsl(0 0% 100%)");
+        assert_equivalent(
+            "rgb(from rgb(from rebeccapurple b g r) b g r)",
+            "rebeccapurple",
+        );
+    }
+
+    #[test]
+    fn it_mixes_colors() {
+        assert_rgba("color-mix(in srgb, red, blue)", [127.5, 0.0, 127.5, 1.0]);
+        assert_rgba(
+            "color-mix(in srgb, red 25%, blue)",
+            [63.75, 0.0, 191.25, 1.0],
+        );
+        assert_rgba(
+            "color-mix(in srgb, red, 25% blue)",
+            [191.25, 0.0, 63.75, 1.0],
+        );
+        assert_rgba(
+            "color-mix(in srgb, red 30%, blue 30%)",
+            [127.5, 0.0, 127.5, 0.6],
+        );
+        assert_rgba(
+            "color-mix(in srgb, red 100%, blue 100%)",
+            [127.5, 0.0, 127.5, 1.0],
+        );
+        assert_rgba(
+            "color-mix(in srgb, rgb(255 0 0 / 0.5), blue)",
+            [85.0, 0.0, 170.0, 0.75],
+        );
+        assert_rgba(
+            "color-mix(in hsl, hsl(120deg 10% 20%), hsl(30deg 30% 40%))",
+            [84.0, 92.0, 61.0, 1.0],
+        );
+        assert_rgba(
+            "color-mix(in hsl, hsl(120deg 10% 20%) 25%, hsl(30deg 30% 40%))",
+            [112.0, 106.0, 67.0, 1.0],
+        );
+        assert_rgba(
+            "color-mix(in hsl, hsl(40deg 50% 50%), hsl(60deg 50% 50%))",
+            [191.25, 170.0, 63.75, 1.0],
+        );
+        assert_rgba(
+            "color-mix(in hsl longer hue, hsl(40deg 50% 50%), hsl(60deg 50% 50%))",
+            [63.75, 85.0, 191.25, 1.0],
+        );
+        assert_rgba(
+            "color-mix(in hsl decreasing hue, hsl(40deg 50% 50%), hsl(60deg 50% 50%))",
+            [63.75, 85.0, 191.25, 1.0],
+        );
+        assert_rgba(
+            "color-mix(in hsl increasing hue, hsl(40deg 50% 50%), hsl(60deg 50% 50%))",
+            [191.25, 170.0, 63.75, 1.0],
+        );
+        assert_rgba(
+            "color-mix(in hsl, white, blue)",
+            [159.375, 159.375, 223.125, 1.0],
+        );
+        assert_equivalent(
+            "color-mix(in oklch, oklch(0.5 0.1 30), oklch(0.7 0.2 90))",
+            "oklch(0.6 0.15 60)",
+        );
+        assert_equivalent(
+            "color-mix(in oklch, oklch(0.5 0.1 350), oklch(0.7 0.2 30))",
+            "oklch(0.6 0.15 10)",
+        );
+        assert_equivalent(
+            "color-mix(in lab, lab(10 20 30), lab(50 60 70))",
+            "lab(30 40 50)",
+        );
+        assert_equivalent(
+            "color-mix(in xyz, color(xyz 0.1 0.2 0.3), color(xyz 0.3 0.4 0.5))",
+            "color(xyz 0.2 0.3 0.4)",
+        );
+        assert_equivalent(
+            "color-mix(in srgb, color(srgb none 0.5 0.5), color(srgb 0.2 0.3 0.4))",
+            "color(srgb 0.2 0.4 0.45)",
+        );
+        assert_equivalent("color-mix(red, blue)", "color-mix(in oklab, red, blue)");
+        assert_equivalent(
+            "color-mix(in srgb, color-mix(in srgb, red, blue), blue)",
+            "color(srgb 0.25 0 0.75)",
+        );
+        assert_equivalent(
+            "color-mix(in srgb, red calc(10% + 15%), blue)",
+            "color-mix(in srgb, red 25%, blue)",
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_colors() {
+        for input in [
+            "",
+            "#ff",
+            "#fffff",
+            "#ggg",
+            "#fffffffff",
+            "notacolor",
+            "rgb(255, 0, 0, 0, 0)",
+            "rgb(255 0 0 0)",
+            "rgb(255, 0 0)",
+            "rgb(255 0 0, 1)",
+            "rgb(255, 0, 0 / 1)",
+            "rgb(255, 0, 0,)",
+            "rgb(255,, 0, 0)",
+            "rgb(10%, 50, 0)",
+            "rgb(none, 0, 0)",
+            "rgb(255 0 0 / 1 / 1)",
+            "rgb(10deg 0 0)",
+            "rgb(255 0)",
+            "rgb(255 0 0",
+            "rgb 255 0 0",
+            "rgb(255px 0 0)",
+            "hsl(0, 100, 50%)",
+            "hsl(10% 20% 30%)",
+            "hsl(none, 100%, 50%)",
+            "hwb(0, 0%, 0%)",
+            "lab(0, 0, 0)",
+            "lab(0 0 10deg)",
+            "oklch(0.5, 0.1, 20)",
+            "color(srgb 1 0)",
+            "color(unknown 1 0 0)",
+            "color(oklab 1 0 0)",
+            "color(srgb, 1, 0, 0)",
+            "rgb(calc(1+2) 0 0)",
+            "rgb(calc(10% + 10) 0 0)",
+            "rgb(calc(10% * 10%) 0 0)",
+            "rgb(calc(255 / 10%) 0 0)",
+            "rgb(calc(255) 0 0 / calc(10deg))",
+            "rgb(unknown(10) 0 0)",
+            "rgb(pi 0 0)",
+            "rgb(r g b)",
+            "rgb(from rebeccapurple r g)",
+            "rgb(from rebeccapurple, r, g, b)",
+            "rgb(from rebeccapurple l a b)",
+            "rgb(from rebeccapurple r g b alpha)",
+            "hsl(from rebeccapurple h s l, 0.5)",
+            "color(from rebeccapurple r g b)",
+            "color-mix(in srgb, red)",
+            "color-mix(in srgb red, blue)",
+            "color-mix(in srgb, red, blue, green)",
+            "color-mix(in srgb longer hue, red, blue)",
+            "color-mix(in oklch longer, red, blue)",
+            "color-mix(in unknown, red, blue)",
+            "color-mix(in srgb, red 0%, blue 0%)",
+            "color-mix(in srgb, red -10%, blue)",
+            "color-mix(in srgb, red 150%, blue)",
+            "color-mix(in srgb, red 10, blue)",
+            "color-mix(in srgb, red 10% 20%, blue)",
+        ] {
+            assert!(parse(input).is_none(), "{input} should be invalid");
+        }
+    }
+
+    #[test]
+    fn it_detects_notation() {
+        let notation = |input| css_color(input).ok().map(|(_, (notation, _))| notation);
+        assert_eq!(Some(Notation::Hex), notation("#fb0"));
+        assert_eq!(Some(Notation::Name), notation("red"));
+        assert_eq!(Some(Notation::Rgb), notation("rgb(from red r g b)"));
+        assert_eq!(Some(Notation::Hcl), notation("lch(50 20 30)"));
+        assert_eq!(
+            Some(Notation::DisplayP3),
+            notation("color(display-p3 1 0 0)")
+        );
+        assert_eq!(Some(Notation::Rgb), notation("color(srgb 1 0 0)"));
+        assert_eq!(
+            Some(Notation::Oklch),
+            notation("color-mix(in oklch, red, blue)")
+        );
+    }
+}
+
+/// Wraps a color parser, so that it only succeeds if it consumes the complete input,
+/// except for trailing whitespace, and tags the parsed color with the given notation.
+fn complete<'a, F>(
+    notation: Notation,
+    parser: F,
+) -> impl Parser<&'a str, Output = (Notation, Color), Error = nom::error::Error<&'a str>>
+where
+    F: Parser<&'a str, Output = Color, Error = nom::error::Error<&'a str>>,
+{
+    map(terminated(parser, (multispace0, eof)), move |color| {
+        (notation, color)
+    })
+}
+
+/// Parses a color in any of the supported notations, returning the detected notation alongside the color.
+///
+/// Each parser has to consume the complete input, so that partial matches do not shadow other notations.
+/// Hex codes are parsed with the given alpha position, or with the alpha value at the end, as used by CSS.
+/// CIELAB and LCH are parsed relative to the given reference white first, so they match the displayed
+/// values. Other valid CSS colors are parsed as specified by CSS, see [`css_color`], remaining inputs
+/// with the more lenient parsers of each notation.
+/// If no parser succeeds, the input is looked up as a color name.
+pub fn any_color(
+    input: &str,
+    alpha_position: AlphaPosition,
+    name_sources: ColorNameSources,
+    reference_white: ReferenceWhite,
+) -> IResult<&str, (Notation, Color)> {
+    let result = alt((
+        complete(Notation::Hex, |input| hex_color(input, alpha_position)),
+        complete(Notation::Lab, |input| cielab(input, reference_white)),
+        complete(Notation::Hcl, |input| lch(input, reference_white)),
+        terminated(css_color, (multispace0, eof)),
+        complete(Notation::Rgb, rgb),
+        complete(Notation::Hsl, hsl),
+        complete(Notation::Hsv, hsv),
+        complete(Notation::Cmyk, cmyk),
+        complete(Notation::Xyz, |input| xyz(input, reference_white)),
+        complete(Notation::Hwb, hwb),
+        complete(Notation::Lms, |input| lms(input, reference_white)),
+        complete(Notation::HunterLab, |input| {
+            hunter_lab(input, reference_white)
+        }),
+        complete(Notation::Oklab, oklab),
+        complete(Notation::Oklch, oklch),
+        complete(Notation::SrgbLinear, |input| {
+            color_function(input, RgbSpace::SrgbLinear)
+        }),
+        complete(Notation::DisplayP3, |input| {
+            color_function(input, RgbSpace::DisplayP3)
+        }),
+        complete(Notation::Rec2020, |input| {
+            color_function(input, RgbSpace::Rec2020)
+        }),
+        complete(Notation::A98Rgb, |input| {
+            color_function(input, RgbSpace::A98Rgb)
+        }),
+        complete(Notation::ProphotoRgb, |input| {
+            color_function(input, RgbSpace::ProphotoRgb)
+        }),
+        alt((
+            complete(Notation::Android, android),
+            complete(Notation::SwiftUi, swift_ui),
+            complete(Notation::Flutter, flutter),
+            complete(Notation::Qt, qt),
+            complete(Notation::Glsl, glsl),
+            complete(Notation::DotNet, dot_net),
+            complete(Notation::RustPalette, rust_palette),
+            complete(Notation::Latex, latex),
+            complete(Notation::Hex, |input| hex_color(input, AlphaPosition::End)),
+        )),
+    ))
+    .parse(input);
+
+    result.or_else(|err| {
+        // approximated names are prefixed, but should still be parsable
+        let name = input.trim().trim_start_matches('≈').trim();
+        color_names::color(name, name_sources)
+            .map(|color| ("", (Notation::Name, color)))
+            .ok_or(err)
+    })
+}
+
+#[cfg(test)]
+mod parse_any_color {
+    use super::*;
+
+    fn detect(input: &str) -> Option<Notation> {
+        any_color(
+            input,
+            AlphaPosition::None,
+            ColorNameSources::all(),
+            ReferenceWhite::default(),
+        )
+        .ok()
+        .map(|(_, (notation, _))| notation)
+    }
+
+    #[test]
+    fn it_detects_notation() {
+        assert_eq!(Some(Notation::Hex), detect("#ff8800"));
+        assert_eq!(Some(Notation::Hex), detect("ff880080"));
+        assert_eq!(Some(Notation::Rgb), detect("rgb(46, 52, 64)"));
+        assert_eq!(Some(Notation::Hsl), detect(" hsl(220, 16%, 22%) "));
+        assert_eq!(Some(Notation::Oklch), detect("oklch(70% 0.1 200)"));
+        assert_eq!(
+            Some(Notation::DisplayP3),
+            detect("color(display-p3 1 0 0 / 50%)")
+        );
+        assert_eq!(
+            Some(Notation::ProphotoRgb),
+            detect("color(prophoto-rgb 0 1 0)")
+        );
+        assert_eq!(Some(Notation::Lms), detect("L: 3.2, M: 3.5, S: 5.3"));
+        assert_eq!(
+            Some(Notation::HunterLab),
+            detect("L: 18.4, a: 0.4, b: -5.4")
+        );
+        assert_eq!(Some(Notation::Name), detect("red"));
+        assert_eq!(Some(Notation::Name), detect("≈ red"));
+        assert_eq!(Some(Notation::Hex), detect("#fb0"));
+        assert_eq!(Some(Notation::Rgb), detect("rgb(from red r g b / 50%)"));
+        assert_eq!(
+            Some(Notation::Oklab),
+            detect("color-mix(in oklab, red, blue)")
+        );
+        assert_eq!(Some(Notation::Android), detect("0xFF2E3440"));
+        assert_eq!(Some(Notation::Flutter), detect("Color(0xFF2E3440)"));
+        assert_eq!(Some(Notation::Glsl), detect("vec3(0.18, 0.2, 0.25)"));
+        assert_eq!(
+            Some(Notation::Latex),
+            detect(r"\definecolor{background}{HTML}{2E3440}")
+        );
+    }
+
+    #[test]
+    fn it_parses_rgb_numbers_like_browsers() {
+        // numbers are between 0 and 255, even with a decimal point
+        let (_, (_, color)) = any_color(
+            "rgb(1.0 0.0 0.0)",
+            AlphaPosition::None,
+            ColorNameSources::all(),
+            ReferenceWhite::default(),
+        )
+        .unwrap();
+        assert!((color.red - 1.0 / 255.0).abs() < 0.0001);
+        assert_eq!(0.0, color.green);
+    }
+
+    #[test]
+    fn it_parses_css_alpha() {
+        let (_, (_, color)) = any_color(
+            "rgb(255 0 0 / 0.5)",
+            AlphaPosition::None,
+            ColorNameSources::all(),
+            ReferenceWhite::default(),
+        )
+        .unwrap();
+        assert_eq!(0.5, color.alpha);
+    }
+
+    #[test]
+    fn it_parses_lab_relative_to_reference_white() {
+        let white = ReferenceWhite::new(Illuminant::A, StandardObserver::Two);
+        let expected = cielab("lab(50 40 59.5)", white).unwrap().1;
+        for input in ["lab(50 40 59.5)", "lab(50% 40 59.5 / 100%)"] {
+            let (_, (notation, color)) =
+                any_color(input, AlphaPosition::None, ColorNameSources::all(), white).unwrap();
+            assert_eq!(Notation::Lab, notation);
+            assert_eq!(expected, color);
+        }
+
+        let expected = lch("lch(50 72 56)", white).unwrap().1;
+        let (_, (notation, color)) = any_color(
+            "lch(50 72 56)",
+            AlphaPosition::None,
+            ColorNameSources::all(),
+            white,
+        )
+        .unwrap();
+        assert_eq!(Notation::Hcl, notation);
+        assert_eq!(expected, color);
+    }
+
+    #[test]
+    fn it_rejects_partial_input() {
+        assert_eq!(None, detect("#ff8800 and more"));
+        assert_eq!(None, detect("not a color"));
+    }
 }
@@ -0,0 +1,249 @@
+use palette::{IntoColor, Lcha, Oklcha};
+
+use super::color::Color;
+
+/// Kinds of color ramps, which can be generated from a base color.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum RampKind {
+    /// Lighter colors, mixed with white.
+    #[default]
+    Tints,
+    /// Darker colors, mixed with black.
+    Shades,
+    /// Less saturated colors, mixed with gray of the same lightness.
+    Tones,
+    /// Scale from a light tint to a dark shade, with the base color in the middle,
+    /// as used by design systems.
+    Scale,
+}
+
+//Convert from U32. Needed for converting from the settings AdwComboRow, which use indexes for values.
+impl From<u32> for RampKind {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::Shades,
+            2 => Self::Tones,
+            3 => Self::Scale,
+            _ => Self::Tints,
+        }
+    }
+}
+
+/// Color spaces, in which the lightness and chroma of ramps are interpolated.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum RampSpace {
+    /// Oklch, the cylindrical form of Oklab.
+    #[default]
+    Oklch,
+    /// CIELCh, the cylindrical form of CIELAB.
+    Lab,
+}
+
+//Convert from U32. Needed for converting from the settings AdwComboRow, which use indexes for values.
+impl From<u32> for RampSpace {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::Lab,
+            _ => Self::Oklch,
+        }
+    }
+}
+
+/// Amount of white and black mixed into the lightest tint and darkest shade of a
+/// [`RampKind::Scale`], so the scale does not end with pure white or black.
+const SCALE_LIMITS: (f32, f32) = (0.9, 0.8);
+
+/// Generates a ramp with the given number of steps from the color.
+///
+/// Tints, shades and tones start with the base color and move towards
+/// (but never reach) white, black or gray. Scales are ordered from light to dark,
+/// with the base color at the step labeled 500, see [`step_labels`].
+/// The hue and the alpha value of the color are kept.
+pub fn ramp(color: Color, kind: RampKind, space: RampSpace, steps: usize) -> Vec<Color> {
+    (0..steps)
+        .map(|step| match kind {
+            RampKind::Scale => {
+                let position = scale_position(step, steps);
+                if position < 0.0 {
+                    mix(color, RampKind::Tints, space, -position * SCALE_LIMITS.0)
+                } else {
+                    mix(color, RampKind::Shades, space, position * SCALE_LIMITS.1)
+                }
+            }
+            _ => mix(color, kind, space, step as f32 / steps as f32),
+        })
+        .collect()
+}
+
+/// Position of the step in a scale, between -1.0 (lightest) and 1.0 (darkest).
+///
+/// The base color is at the middle step. With an even number of steps, there is one
+/// more lighter than darker step.
+fn scale_position(step: usize, steps: usize) -> f32 {
+    let base = steps / 2;
+    if step < base {
+        -((base - step) as f32) / base as f32
+    } else if step > base {
+        (step - base) as f32 / (steps - 1 - base) as f32
+    } else {
+        0.0
+    }
+}
+
+/// Mixes the color towards the target of the ramp kind by the amount, between 0.0 and 1.0.
+fn mix(color: Color, kind: RampKind, space: RampSpace, amount: f32) -> Color {
+    // lightness and chroma of the target, relative to the base color
+    let target = |lightness: f32, chroma: f32| match kind {
+        RampKind::Tints => (
+            lightness + (1.0 - lightness) * amount,
+            chroma * (1.0 - amount),
+        ),
+        RampKind::Shades => (lightness * (1.0 - amount), chroma * (1.0 - amount)),
+        RampKind::Tones | RampKind::Scale => (lightness, chroma * (1.0 - amount)),
+    };
+
+    match space {
+        RampSpace::Oklch => {
+            let mut oklch: Oklcha = (*color).into_color();
+            (oklch.l, oklch.chroma) = target(oklch.l, oklch.chroma);
+            Color::from_palette(oklch)
+        }
+        RampSpace::Lab => {
+            let mut lch: Lcha = (*color).into_color();
+            let (lightness, chroma) = target(lch.l / 100.0, lch.chroma);
+            (lch.l, lch.chroma) = (lightness * 100.0, chroma);
+            Color::from_palette(lch)
+        }
+    }
+}
+
+/// Labels of the ramp steps, as used by design system scales.
+///
+/// The base color is labeled 500, lighter steps down to 50 and darker steps up to 950.
+/// Eleven steps of a scale are labeled 50, 100, 200, …, 900, 950; other numbers of steps
+/// are spread over the same range, rounded to tens. Tones are labeled like shades.
+pub fn step_labels(kind: RampKind, steps: usize) -> Vec<u32> {
+    let last = steps.saturating_sub(1).max(1) as f32;
+    (0..steps)
+        .map(|step| {
+            let position = match kind {
+                RampKind::Tints => -(step as f32) / last,
+                RampKind::Shades | RampKind::Tones => step as f32 / last,
+                RampKind::Scale => scale_position(step, steps),
+            };
+            // steps of 100 up to 100 and 900, the outermost steps are halved
+            let distance = position.abs();
+            let offset = if distance <= 0.8 {
+                distance * 500.0
+            } else {
+                400.0 + (distance - 0.8) * 250.0
+            };
+            let label = 500.0 + offset.copysign(position);
+            (label / 10.0).round() as u32 * 10
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod generate_ramp {
+    use super::*;
+
+    fn lightness(color: Color) -> f32 {
+        let oklch: Oklcha = (*color).into_color();
+        oklch.l
+    }
+
+    #[test]
+    fn it_starts_with_base_color() {
+        let color = Color::rgba(53, 132, 228, 255);
+        for kind in [RampKind::Tints, RampKind::Shades, RampKind::Tones] {
+            for space in [RampSpace::Oklch, RampSpace::Lab] {
+                let ramp = ramp(color, kind, space, 5);
+                assert_eq!(5, ramp.len());
+                assert_eq!(
+                    color.into_format::<u8, u8>(),
+                    ramp[0].into_format::<u8, u8>()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn it_changes_lightness() {
+        let color = Color::rgba(53, 132, 228, 255);
+
+        let tints = ramp(color, RampKind::Tints, RampSpace::Oklch, 5);
+        assert!(
+            tints
+                .windows(2)
+                .all(|pair| lightness(pair[0]) < lightness(pair[1]))
+        );
+
+        let shades = ramp(color, RampKind::Shades, RampSpace::Lab, 5);
+        assert!(
+            shades
+                .windows(2)
+                .all(|pair| lightness(pair[0]) > lightness(pair[1]))
+        );
+
+        let scale = ramp(color, RampKind::Scale, RampSpace::Oklch, 11);
+        assert!(
+            scale
+                .windows(2)
+                .all(|pair| lightness(pair[0]) > lightness(pair[1]))
+        );
+        assert_eq!(
+            color.into_format::<u8, u8>(),
+            scale[5].into_format::<u8, u8>()
+        );
+    }
+
+    #[test]
+    fn it_keeps_base_color_in_scale() {
+        let color = Color::rgba(53, 132, 228, 255);
+        for steps in [2, 10, 11, 20] {
+            let scale = ramp(color, RampKind::Scale, RampSpace::Oklch, steps);
+            let base = step_labels(RampKind::Scale, steps)
+                .iter()
+                .position(|label| *label == 500)
+                .unwrap();
+            assert_eq!(
+                color.into_format::<u8, u8>(),
+                scale[base].into_format::<u8, u8>()
+            );
+        }
+    }
+
+    #[test]
+    fn it_labels_steps() {
+        assert_eq!(
+            vec![50, 100, 200, 300, 400, 500, 600, 700, 800, 900, 950],
+            step_labels(RampKind::Scale, 11)
+        );
+        assert_eq!(vec![500, 250, 50], step_labels(RampKind::Tints, 3));
+        assert_eq!(vec![500, 750, 950], step_labels(RampKind::Shades, 3));
+        assert_eq!(vec![50, 500], step_labels(RampKind::Scale, 2));
+    }
+
+    #[test]
+    fn it_keeps_labels_in_range() {
+        for kind in [
+            RampKind::Tints,
+            RampKind::Shades,
+            RampKind::Tones,
+            RampKind::Scale,
+        ] {
+            for steps in 2..=20 {
+                let labels = step_labels(kind, steps);
+                assert_eq!(steps, labels.len());
+                assert!(labels.contains(&500));
+                assert!(labels.iter().all(|label| (50..=950).contains(label)));
+                // labels are used as names, so they have to be unique
+                let mut unique = labels.clone();
+                unique.sort();
+                unique.dedup();
+                assert_eq!(steps, unique.len());
+            }
+        }
+    }
+}
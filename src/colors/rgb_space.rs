@@ -0,0 +1,280 @@
+use palette::Xyz;
+
+use super::{
+    color::{Color, srgb_decode, srgb_encode},
+    illuminant::{Illuminant, ReferenceWhite, StandardObserver},
+};
+
+type Matrix = [[f32; 3]; 3];
+
+/// Conversion matrices from <https://www.w3.org/TR/css-color-4/#color-conversion-code>
+const LINEAR_SRGB_TO_XYZ: Matrix = [
+    [0.4123908, 0.35758433, 0.1804808],
+    [0.212639, 0.71516865, 0.07219232],
+    [0.01933082, 0.11919478, 0.95053214],
+];
+const XYZ_TO_LINEAR_SRGB: Matrix = [
+    [3.24097, -1.5373832, -0.49861076],
+    [-0.96924365, 1.8759675, 0.04155506],
+    [0.05563008, -0.20397696, 1.0569715],
+];
+const LINEAR_DISPLAY_P3_TO_XYZ: Matrix = [
+    [0.48657095, 0.26566768, 0.19821729],
+    [0.22897457, 0.69173855, 0.07928691],
+    [0.0, 0.04511338, 1.0439444],
+];
+const XYZ_TO_LINEAR_DISPLAY_P3: Matrix = [
+    [2.493497, -0.9313836, -0.40271077],
+    [-0.829489, 1.7626641, 0.02362469],
+    [0.03584583, -0.07617239, 0.9568845],
+];
+const LINEAR_REC2020_TO_XYZ: Matrix = [
+    [0.63695806, 0.1446169, 0.16888098],
+    [0.2627002, 0.67799807, 0.05930172],
+    [0.0, 0.02807269, 1.0609851],
+];
+const XYZ_TO_LINEAR_REC2020: Matrix = [
+    [1.7166512, -0.35567078, -0.2533663],
+    [-0.6666843, 1.6164812, 0.01576855],
+    [0.01763986, -0.04277061, 0.94210315],
+];
+const LINEAR_A98_RGB_TO_XYZ: Matrix = [
+    [0.57666904, 0.18555824, 0.18822865],
+    [0.29734498, 0.62736356, 0.07529146],
+    [0.02703136, 0.07068885, 0.99133754],
+];
+const XYZ_TO_LINEAR_A98_RGB: Matrix = [
+    [2.0415878, -0.565007, -0.34473136],
+    [-0.96924365, 1.8759675, 0.04155506],
+    [0.01344428, -0.11836239, 1.015175],
+];
+/// ProPhoto RGB uses D50 as its white point.
+const LINEAR_PROPHOTO_RGB_TO_XYZ: Matrix = [
+    [0.7977666, 0.1351813, 0.03134773],
+    [0.28807482, 0.7118352, 0.00008994],
+    [0.0, 0.0, 0.8251046],
+];
+const XYZ_TO_LINEAR_PROPHOTO_RGB: Matrix = [
+    [1.3457869, -0.25557208, -0.05110186],
+    [-0.5446307, 1.5082477, 0.02052745],
+    [0.0, 0.0, 1.2119676],
+];
+
+/// Predefined RGB color spaces of the CSS `color()` function.
+///
+/// Except for linear sRGB, they have a wider gamut than sRGB, so colors in these
+/// spaces may not be representable in sRGB.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RgbSpace {
+    /// sRGB without the transfer function.
+    SrgbLinear,
+    /// Display P3, used by many modern displays.
+    DisplayP3,
+    /// ITU-R BT.2020, used for ultra high definition television.
+    Rec2020,
+    /// Adobe RGB (1998).
+    A98Rgb,
+    /// ProPhoto RGB, also known as ROMM RGB.
+    ProphotoRgb,
+}
+
+impl RgbSpace {
+    /// Identifier of the color space, as used by the CSS `color()` function.
+    pub fn css_name(self) -> &'static str {
+        match self {
+            RgbSpace::SrgbLinear => "srgb-linear",
+            RgbSpace::DisplayP3 => "display-p3",
+            RgbSpace::Rec2020 => "rec2020",
+            RgbSpace::A98Rgb => "a98-rgb",
+            RgbSpace::ProphotoRgb => "prophoto-rgb",
+        }
+    }
+
+    /// Converts the color to the RGB values of this color space.
+    ///
+    /// The values are not clamped, so they can be outside of the range from 0.0 to 1.0.
+    pub fn to_rgb(self, color: Color) -> [f32; 3] {
+        let linear = color.to_linear();
+        let linear = [linear.red, linear.green, linear.blue];
+        let xyz = multiply(&LINEAR_SRGB_TO_XYZ, linear);
+
+        let linear = match self {
+            RgbSpace::SrgbLinear => linear,
+            RgbSpace::DisplayP3 => multiply(&XYZ_TO_LINEAR_DISPLAY_P3, xyz),
+            RgbSpace::Rec2020 => multiply(&XYZ_TO_LINEAR_REC2020, xyz),
+            RgbSpace::A98Rgb => multiply(&XYZ_TO_LINEAR_A98_RGB, xyz),
+            RgbSpace::ProphotoRgb => {
+                let xyz = prophoto_white().adapt_from_d65(Xyz::new(xyz[0], xyz[1], xyz[2]));
+                multiply(&XYZ_TO_LINEAR_PROPHOTO_RGB, [xyz.x, xyz.y, xyz.z])
+            }
+        };
+        linear.map(|value| self.encode(value))
+    }
+
+    /// Creates a color from the RGB values of this color space.
+    ///
+    /// The resulting sRGB values are not clamped, so the color may be out of the sRGB gamut.
+    pub fn to_color(self, rgb: [f32; 3], alpha: f32) -> Color {
+        let linear = rgb.map(|value| self.decode(value));
+
+        let linear = match self {
+            RgbSpace::SrgbLinear => linear,
+            RgbSpace::DisplayP3 => to_linear_srgb(multiply(&LINEAR_DISPLAY_P3_TO_XYZ, linear)),
+            RgbSpace::Rec2020 => to_linear_srgb(multiply(&LINEAR_REC2020_TO_XYZ, linear)),
+            RgbSpace::A98Rgb => to_linear_srgb(multiply(&LINEAR_A98_RGB_TO_XYZ, linear)),
+            RgbSpace::ProphotoRgb => {
+                let xyz = multiply(&LINEAR_PROPHOTO_RGB_TO_XYZ, linear);
+                let xyz = prophoto_white().adapt_to_d65(Xyz::new(xyz[0], xyz[1], xyz[2]));
+                to_linear_srgb([xyz.x, xyz.y, xyz.z])
+            }
+        };
+        let [red, green, blue] = linear;
+        Color::from_linear(palette::LinSrgba::new(red, green, blue, alpha))
+    }
+
+    /// Transfer function from the encoded to the linear values.
+    ///
+    /// Negative values are mirrored, as specified by CSS.
+    fn decode(self, value: f32) -> f32 {
+        let sign = value.signum();
+        let abs = value.abs();
+        match self {
+            RgbSpace::SrgbLinear => value,
+            RgbSpace::DisplayP3 => srgb_decode(value),
+            RgbSpace::Rec2020 => {
+                if abs < REC2020_BETA * 4.5 {
+                    value / 4.5
+                } else {
+                    sign * ((abs + REC2020_ALPHA - 1.0) / REC2020_ALPHA).powf(1.0 / 0.45)
+                }
+            }
+            RgbSpace::A98Rgb => sign * abs.powf(563.0 / 256.0),
+            RgbSpace::ProphotoRgb => {
+                if abs <= 16.0 / 512.0 {
+                    value / 16.0
+                } else {
+                    sign * abs.powf(1.8)
+                }
+            }
+        }
+    }
+
+    /// Transfer function from the linear to the encoded values.
+    fn encode(self, value: f32) -> f32 {
+        let sign = value.signum();
+        let abs = value.abs();
+        match self {
+            RgbSpace::SrgbLinear => value,
+            RgbSpace::DisplayP3 => srgb_encode(value),
+            RgbSpace::Rec2020 => {
+                if abs > REC2020_BETA {
+                    sign * (REC2020_ALPHA * abs.powf(0.45) - (REC2020_ALPHA - 1.0))
+                } else {
+                    value * 4.5
+                }
+            }
+            RgbSpace::A98Rgb => sign * abs.powf(256.0 / 563.0),
+            RgbSpace::ProphotoRgb => {
+                if abs >= 1.0 / 512.0 {
+                    sign * abs.powf(1.0 / 1.8)
+                } else {
+                    value * 16.0
+                }
+            }
+        }
+    }
+}
+
+/// Constants of the Rec. 2020 transfer function.
+const REC2020_ALPHA: f32 = 1.0992968;
+const REC2020_BETA: f32 = 0.018053968;
+
+fn prophoto_white() -> ReferenceWhite {
+    ReferenceWhite::new(Illuminant::D50, StandardObserver::Two)
+}
+
+fn to_linear_srgb(xyz: [f32; 3]) -> [f32; 3] {
+    multiply(&XYZ_TO_LINEAR_SRGB, xyz)
+}
+
+fn multiply(matrix: &Matrix, [a, b, c]: [f32; 3]) -> [f32; 3] {
+    matrix.map(|row| row[0] * a + row[1] * b + row[2] * c)
+}
+
+#[cfg(test)]
+mod convert_rgb_space {
+    use super::*;
+
+    const SPACES: [RgbSpace; 5] = [
+        RgbSpace::SrgbLinear,
+        RgbSpace::DisplayP3,
+        RgbSpace::Rec2020,
+        RgbSpace::A98Rgb,
+        RgbSpace::ProphotoRgb,
+    ];
+
+    fn assert_close(expected: [f32; 3], actual: [f32; 3]) {
+        assert!(
+            expected
+                .iter()
+                .zip(actual)
+                .all(|(expected, actual)| (expected - actual).abs() < 0.001),
+            "expected {expected:?}, got {actual:?}"
+        );
+    }
+
+    #[test]
+    fn it_keeps_white() {
+        let white = Color::rgba(255, 255, 255, 255);
+        for space in SPACES {
+            assert_close([1.0, 1.0, 1.0], space.to_rgb(white));
+        }
+    }
+
+    #[test]
+    fn it_round_trips() {
+        let color = Color::rgba(46, 52, 64, 255);
+        for space in SPACES {
+            let converted = space.to_color(space.to_rgb(color), 1.0);
+            assert_close(
+                [color.red, color.green, color.blue],
+                [converted.red, converted.green, converted.blue],
+            );
+        }
+    }
+
+    #[test]
+    fn it_detects_out_of_gamut_colors() {
+        assert!(
+            RgbSpace::DisplayP3
+                .to_color([0.5, 0.4, 0.3], 1.0)
+                .is_in_gamut()
+        );
+        assert!(
+            !RgbSpace::DisplayP3
+                .to_color([1.0, 0.0, 0.0], 1.0)
+                .is_in_gamut()
+        );
+        assert!(
+            !RgbSpace::Rec2020
+                .to_color([0.0, 1.0, 0.0], 1.0)
+                .is_in_gamut()
+        );
+    }
+
+    #[test]
+    fn it_converts_wide_gamut_colors() {
+        // values from https://colorjs.io
+        let red = RgbSpace::DisplayP3.to_color([1.0, 0.0, 0.0], 1.0);
+        assert_close([1.0931, -0.2267, -0.1501], [red.red, red.green, red.blue]);
+
+        let red = RgbSpace::Rec2020.to_color([1.0, 0.0, 0.0], 1.0);
+        assert_close([1.2482, -0.3879, -0.1435], [red.red, red.green, red.blue]);
+
+        let srgb_red = Color::rgba(255, 0, 0, 255);
+        assert_close(
+            [0.9175, 0.2003, 0.1386],
+            RgbSpace::DisplayP3.to_rgb(srgb_red),
+        );
+    }
+}
@@ -0,0 +1,513 @@
+use palette::LinSrgba;
+
+use super::color::Color;
+
+/// Sizes of the square around a pixel, whose colors are averaged when sampling an image.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum SampleSize {
+    #[default]
+    Single,
+    Three,
+    Five,
+    Nine,
+}
+
+//Convert from U32. Needed for converting from the settings AdwComboRow, which use indexes for values.
+impl From<u32> for SampleSize {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::Three,
+            2 => Self::Five,
+            3 => Self::Nine,
+            _ => Self::Single,
+        }
+    }
+}
+
+impl SampleSize {
+    /// The width and height of the square in pixels.
+    pub fn pixels(self) -> usize {
+        match self {
+            SampleSize::Single => 1,
+            SampleSize::Three => 3,
+            SampleSize::Five => 5,
+            SampleSize::Nine => 9,
+        }
+    }
+}
+
+/// Ways to reduce the colors of a region to a single color.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum RegionAverage {
+    /// The mean in linear light, see [`mean`].
+    #[default]
+    Mean,
+    /// The median of each channel, see [`median`].
+    Median,
+    /// The most common color, see [`dominant`].
+    Dominant,
+}
+
+//Convert from U32. Needed for converting from the settings AdwComboRow, which use indexes for values.
+impl From<u32> for RegionAverage {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::Median,
+            2 => Self::Dominant,
+            _ => Self::Mean,
+        }
+    }
+}
+
+impl RegionAverage {
+    /// Reduces the colors to a single color, returns [`None`] if there are no colors.
+    pub fn apply(self, colors: impl IntoIterator<Item = Color>) -> Option<Color> {
+        match self {
+            RegionAverage::Mean => mean(colors),
+            RegionAverage::Median => median(colors),
+            RegionAverage::Dominant => dominant(colors),
+        }
+    }
+}
+
+/// Shapes of a region, which is selected by dragging over an image.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum RegionShape {
+    /// A rectangle between the start and the end of the drag.
+    #[default]
+    Rectangle,
+    /// A circle around the start of the drag, through the end of it.
+    Circle,
+}
+
+//Convert from U32. Needed for converting from the settings AdwComboRow, which use indexes for values.
+impl From<u32> for RegionShape {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::Circle,
+            _ => Self::Rectangle,
+        }
+    }
+}
+
+/// A region of pixels of an image.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Region {
+    Rectangle {
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    },
+    Circle {
+        x: usize,
+        y: usize,
+        radius: usize,
+    },
+}
+
+impl Region {
+    /// The region spanned by dragging from the start to the end pixel, both included.
+    pub fn from_drag(shape: RegionShape, start: (usize, usize), end: (usize, usize)) -> Self {
+        match shape {
+            RegionShape::Rectangle => Region::Rectangle {
+                x: start.0.min(end.0),
+                y: start.1.min(end.1),
+                width: start.0.abs_diff(end.0) + 1,
+                height: start.1.abs_diff(end.1) + 1,
+            },
+            RegionShape::Circle => {
+                let (dx, dy) = (start.0.abs_diff(end.0), start.1.abs_diff(end.1));
+                Region::Circle {
+                    x: start.0,
+                    y: start.1,
+                    radius: ((dx * dx + dy * dy) as f64).sqrt().round() as usize,
+                }
+            }
+        }
+    }
+
+    /// The bounding box of the region, as `(x, y, width, height)`.
+    ///
+    /// The box may extend beyond the top left of the image for circles, so the coordinates
+    /// are signed.
+    pub fn bounds(&self) -> (isize, isize, usize, usize) {
+        match *self {
+            Region::Rectangle {
+                x,
+                y,
+                width,
+                height,
+            } => (x as isize, y as isize, width, height),
+            Region::Circle { x, y, radius } => (
+                x as isize - radius as isize,
+                y as isize - radius as isize,
+                2 * radius + 1,
+                2 * radius + 1,
+            ),
+        }
+    }
+
+    /// Whether the region contains the pixel.
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        match *self {
+            Region::Rectangle {
+                x: left,
+                y: top,
+                width,
+                height,
+            } => (left..left + width).contains(&x) && (top..top + height).contains(&y),
+            Region::Circle {
+                x: center_x,
+                y: center_y,
+                radius,
+            } => {
+                let (dx, dy) = (x.abs_diff(center_x), y.abs_diff(center_y));
+                dx * dx + dy * dy <= radius * radius
+            }
+        }
+    }
+}
+
+/// Pixels of an image with 8-bit RGBA values and straight alpha, as downloaded from a texture.
+#[derive(Debug, Clone, Copy)]
+pub struct Pixels<'a> {
+    data: &'a [u8],
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+impl<'a> Pixels<'a> {
+    /// Wraps the data of an image, where each row starts `stride` bytes after the previous one.
+    ///
+    /// Returns [`None`] if the data is too short for the size of the image.
+    pub fn new(data: &'a [u8], width: usize, height: usize, stride: usize) -> Option<Self> {
+        let required = match height {
+            0 => 0,
+            _ => stride * (height - 1) + width * 4,
+        };
+        (stride >= width * 4 && data.len() >= required).then_some(Self {
+            data,
+            width,
+            height,
+            stride,
+        })
+    }
+
+    /// Returns the color of the pixel, or [`None`] if it is outside of the image.
+    pub fn get(&self, x: usize, y: usize) -> Option<Color> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let offset = y * self.stride + x * 4;
+        let [red, green, blue, alpha] = self.data[offset..offset + 4] else {
+            return None;
+        };
+        Some(Color::rgba(red, green, blue, alpha))
+    }
+
+    /// Returns the colors of the pixels in the rectangle, clipped to the image.
+    pub fn region(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> impl Iterator<Item = Color> + '_ {
+        let columns = x.min(self.width)..x.saturating_add(width).min(self.width);
+        let rows = y.min(self.height)..y.saturating_add(height).min(self.height);
+        rows.flat_map(move |row| {
+            columns
+                .clone()
+                .filter_map(move |column| self.get(column, row))
+        })
+    }
+
+    /// Returns the colors of the pixels in the region, clipped to the image.
+    pub fn colors_in(&self, region: Region) -> impl Iterator<Item = Color> + '_ {
+        let (x, y, width, height) = region.bounds();
+        let (left, top) = (x.max(0) as usize, y.max(0) as usize);
+        let right = (x + width as isize).max(0) as usize;
+        let bottom = (y + height as isize).max(0) as usize;
+        let columns = left.min(self.width)..right.min(self.width);
+        let rows = top.min(self.height)..bottom.min(self.height);
+        rows.flat_map(move |row| {
+            columns
+                .clone()
+                .filter(move |column| region.contains(*column, row))
+                .filter_map(move |column| self.get(column, row))
+        })
+    }
+
+    /// Samples the color at the pixel, averaged over the square of the given size around it.
+    ///
+    /// Pixels outside of the image are ignored, see [`mean`].
+    pub fn sample(&self, x: usize, y: usize, size: SampleSize) -> Option<Color> {
+        let radius = size.pixels() / 2;
+        if size == SampleSize::Single {
+            return self.get(x, y);
+        }
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        mean(self.region(
+            x.saturating_sub(radius),
+            y.saturating_sub(radius),
+            x.min(radius) + radius + 1,
+            y.min(radius) + radius + 1,
+        ))
+    }
+}
+
+/// The mean of the colors, calculated in linear light.
+///
+/// Colors are weighted by their alpha value, so that transparent pixels do not darken the result.
+/// Returns [`None`] if there are no colors.
+pub fn mean(colors: impl IntoIterator<Item = Color>) -> Option<Color> {
+    let mut count = 0;
+    let mut sum = LinSrgba::new(0.0, 0.0, 0.0, 0.0);
+    for color in colors {
+        let linear = color.to_linear();
+        sum.red += linear.red * linear.alpha;
+        sum.green += linear.green * linear.alpha;
+        sum.blue += linear.blue * linear.alpha;
+        sum.alpha += linear.alpha;
+        count += 1;
+    }
+
+    if count == 0 {
+        return None;
+    }
+    if sum.alpha == 0.0 {
+        return Some(Color::rgba(0, 0, 0, 0));
+    }
+    Some(Color::from_linear(LinSrgba::new(
+        sum.red / sum.alpha,
+        sum.green / sum.alpha,
+        sum.blue / sum.alpha,
+        sum.alpha / count as f32,
+    )))
+}
+
+/// The median of each channel of the colors.
+///
+/// The color channels of fully transparent colors are ignored.
+/// Returns [`None`] if there are no colors.
+pub fn median(colors: impl IntoIterator<Item = Color>) -> Option<Color> {
+    fn middle(values: &mut [f32]) -> Option<f32> {
+        if values.is_empty() {
+            return None;
+        }
+        let index = values.len() / 2;
+        Some(*values.select_nth_unstable_by(index, f32::total_cmp).1)
+    }
+
+    let mut channels: [Vec<f32>; 4] = Default::default();
+    for color in colors {
+        channels[3].push(color.alpha);
+        if color.alpha > 0.0 {
+            channels[0].push(color.red);
+            channels[1].push(color.green);
+            channels[2].push(color.blue);
+        }
+    }
+
+    let alpha = middle(&mut channels[3])?;
+    let [red, green, blue, _] = &mut channels;
+    match (middle(red), middle(green), middle(blue)) {
+        (Some(red), Some(green), Some(blue)) => Some(Color::unclamped(palette::Srgba::new(
+            red, green, blue, alpha,
+        ))),
+        _ => Some(Color::rgba(0, 0, 0, 0)),
+    }
+}
+
+/// The most common color.
+///
+/// Colors are grouped by the upper 4 bits of each channel, the mean of the largest group
+/// is returned. Fully transparent colors are ignored, see [`mean`].
+/// Returns [`None`] if there are no colors.
+pub fn dominant(colors: impl IntoIterator<Item = Color>) -> Option<Color> {
+    let bucket = |color: &Color| {
+        let [red, green, blue] = [color.red, color.green, color.blue]
+            .map(|v| ((v.clamp(0.0, 1.0) * 255.0).round() as usize) >> 4);
+        (red << 8) | (green << 4) | blue
+    };
+
+    let colors = colors.into_iter().collect::<Vec<Color>>();
+    let mut counts = vec![0usize; 1 << 12];
+    for color in colors.iter().filter(|color| color.alpha > 0.0) {
+        counts[bucket(color)] += 1;
+    }
+    // prefer the first group on ties, so that the result is stable
+    let (largest, count) = counts
+        .iter()
+        .enumerate()
+        .max_by(|(a_index, a), (b_index, b)| a.cmp(b).then(b_index.cmp(a_index)))?;
+    if *count == 0 {
+        return mean(colors);
+    }
+
+    mean(
+        colors
+            .into_iter()
+            .filter(|color| color.alpha > 0.0 && bucket(color) == largest),
+    )
+}
+
+#[cfg(test)]
+mod sample_pixels {
+    use super::*;
+
+    /// A 3x2 image with a padded stride.
+    fn image() -> Vec<u8> {
+        vec![
+            255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 0, 0, //
+            0, 0, 0, 255, 255, 255, 255, 255, 255, 0, 0, 0, 0, 0,
+        ]
+    }
+
+    #[test]
+    fn it_gets_pixels() {
+        let data = image();
+        let pixels = Pixels::new(&data, 3, 2, 14).unwrap();
+        assert_eq!(Some(Color::rgba(0, 0, 255, 255)), pixels.get(2, 0));
+        assert_eq!(Some(Color::rgba(255, 255, 255, 255)), pixels.get(1, 1));
+        assert_eq!(None, pixels.get(3, 0));
+        assert_eq!(None, pixels.get(0, 2));
+        assert_eq!(
+            Some(Color::rgba(255, 0, 0, 255)),
+            pixels.sample(0, 0, SampleSize::Single)
+        );
+    }
+
+    #[test]
+    fn it_rejects_short_data() {
+        let data = image();
+        assert!(Pixels::new(&data, 4, 2, 14).is_none());
+        assert!(Pixels::new(&data[..20], 3, 2, 14).is_none());
+    }
+
+    #[test]
+    fn it_averages_in_linear_light() {
+        let black = Color::rgba(0, 0, 0, 255);
+        let white = Color::rgba(255, 255, 255, 255);
+        let gray = mean([black, white]).unwrap();
+        // the mean of 0.0 and 1.0 in linear light is lighter than 50% in sRGB
+        assert_eq!(188, (gray.red * 255.0).round() as u8);
+        assert_eq!(1.0, gray.alpha);
+    }
+
+    #[test]
+    fn it_ignores_transparent_colors() {
+        let red = Color::rgba(255, 0, 0, 255);
+        let transparent = Color::rgba(0, 0, 255, 0);
+        let mean = mean([red, transparent]).unwrap();
+        assert_eq!(
+            Color::rgba(255, 0, 0, 128).into_format::<u8, u8>(),
+            mean.into_format::<u8, u8>()
+        );
+        assert_eq!(None, super::mean([]));
+    }
+
+    #[test]
+    fn it_clips_the_sampled_square() {
+        let data = image();
+        let pixels = Pixels::new(&data, 3, 2, 14).unwrap();
+        // the transparent pixel in the corner does not change the color
+        let corner = pixels.sample(2, 1, SampleSize::Three).unwrap();
+        let expected = mean([
+            Color::rgba(0, 255, 0, 255),
+            Color::rgba(0, 0, 255, 255),
+            Color::rgba(255, 255, 255, 255),
+            Color::rgba(255, 0, 0, 0),
+        ])
+        .unwrap();
+        assert_eq!(expected, corner);
+        assert_eq!(6, pixels.region(0, 0, 10, 10).count());
+    }
+
+    #[test]
+    fn it_builds_regions_from_drags() {
+        assert_eq!(
+            Region::Rectangle {
+                x: 1,
+                y: 2,
+                width: 4,
+                height: 3,
+            },
+            Region::from_drag(RegionShape::Rectangle, (4, 2), (1, 4))
+        );
+        let circle = Region::from_drag(RegionShape::Circle, (5, 5), (8, 9));
+        assert_eq!(
+            Region::Circle {
+                x: 5,
+                y: 5,
+                radius: 5
+            },
+            circle
+        );
+        assert_eq!((0, 0, 11, 11), circle.bounds());
+        assert!(circle.contains(10, 5));
+        assert!(!circle.contains(9, 9));
+    }
+
+    #[test]
+    fn it_clips_regions() {
+        let data = image();
+        let pixels = Pixels::new(&data, 3, 2, 14).unwrap();
+        let circle = Region::Circle {
+            x: 0,
+            y: 0,
+            radius: 1,
+        };
+        assert_eq!(
+            vec![
+                Color::rgba(255, 0, 0, 255),
+                Color::rgba(0, 255, 0, 255),
+                Color::rgba(0, 0, 0, 255),
+            ],
+            pixels.colors_in(circle).collect::<Vec<Color>>()
+        );
+        let rectangle = Region::from_drag(RegionShape::Rectangle, (1, 0), (5, 5));
+        assert_eq!(4, pixels.colors_in(rectangle).count());
+    }
+
+    #[test]
+    fn it_takes_the_median() {
+        let colors = [
+            Color::rgba(10, 200, 0, 255),
+            Color::rgba(20, 100, 0, 255),
+            Color::rgba(250, 0, 0, 255),
+            Color::rgba(0, 0, 255, 0),
+        ];
+        let median = median(colors).unwrap();
+        assert_eq!(
+            Color::rgba(20, 100, 0, 255).into_format::<u8, u8>(),
+            median.into_format::<u8, u8>()
+        );
+        assert_eq!(None, super::median([]));
+    }
+
+    #[test]
+    fn it_finds_the_dominant_color() {
+        // both reds share the upper 4 bits of each channel, so they outnumber the blue
+        let colors = [
+            Color::rgba(0, 0, 255, 255),
+            Color::rgba(255, 0, 0, 255),
+            Color::rgba(245, 10, 5, 255),
+            Color::rgba(0, 255, 0, 0),
+            Color::rgba(0, 255, 0, 0),
+            Color::rgba(0, 255, 0, 0),
+        ];
+        let dominant = dominant(colors).unwrap();
+        assert_eq!(250, (dominant.red * 255.0).round() as u8);
+        assert_eq!(5, (dominant.green * 255.0).round() as u8);
+        assert!(dominant.blue < 0.02);
+        assert_eq!(
+            Some(Color::rgba(0, 0, 0, 0)),
+            super::dominant([Color::rgba(0, 255, 0, 0)])
+        );
+    }
+}
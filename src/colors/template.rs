@@ -0,0 +1,751 @@
+use gettextrs::gettext;
+use gtk::{gio, glib, prelude::*};
+use palette::{IntoColor, Lab, Lch, Oklab, Oklch, WithAlpha, convert::IntoColorUnclamped};
+
+use super::{
+    Notation,
+    cmyk::Cmyka,
+    color::{Color, ColorError},
+    gamut::GamutMapping,
+    hunterlab::HunterLab,
+    illuminant::ReferenceWhite,
+    parser,
+    rgb_space::RgbSpace,
+};
+
+/// Prefix of the identifiers of custom formats in the `format-order` and `visible-formats` settings.
+pub const CUSTOM_PREFIX: &str = "custom:";
+
+/// Largest number of digits after the decimal point in a placeholder.
+const MAX_PRECISION: usize = 10;
+
+/// Color spaces, which can be used in placeholders, with their identifiers.
+const SPACES: [(&str, Notation); 17] = [
+    ("rgb", Notation::Rgb),
+    ("hsl", Notation::Hsl),
+    ("hsv", Notation::Hsv),
+    ("cmyk", Notation::Cmyk),
+    ("xyz", Notation::Xyz),
+    ("cielab", Notation::Lab),
+    ("hwb", Notation::Hwb),
+    ("hcl", Notation::Hcl),
+    ("lms", Notation::Lms),
+    ("hunterlab", Notation::HunterLab),
+    ("oklab", Notation::Oklab),
+    ("oklch", Notation::Oklch),
+    ("srgb-linear", Notation::SrgbLinear),
+    ("display-p3", Notation::DisplayP3),
+    ("rec2020", Notation::Rec2020),
+    ("a98-rgb", Notation::A98Rgb),
+    ("prophoto-rgb", Notation::ProphotoRgb),
+];
+
+/// Names of the channels of the color space, in the order used by [`to_channels`].
+fn channel_names(notation: Notation) -> &'static [&'static str] {
+    match notation {
+        Notation::Rgb
+        | Notation::SrgbLinear
+        | Notation::DisplayP3
+        | Notation::Rec2020
+        | Notation::A98Rgb
+        | Notation::ProphotoRgb => &["r", "g", "b"],
+        Notation::Hsl => &["h", "s", "l"],
+        Notation::Hsv => &["h", "s", "v"],
+        Notation::Hwb => &["h", "w", "b"],
+        Notation::Cmyk => &["c", "m", "y", "k"],
+        Notation::Xyz => &["x", "y", "z"],
+        Notation::Lab | Notation::HunterLab | Notation::Oklab => &["l", "a", "b"],
+        Notation::Hcl | Notation::Oklch => &["l", "c", "h"],
+        Notation::Lms => &["l", "m", "s"],
+        Notation::Hex
+        | Notation::Name
+        | Notation::Android
+        | Notation::SwiftUi
+        | Notation::Flutter
+        | Notation::Qt
+        | Notation::Glsl
+        | Notation::DotNet
+        | Notation::RustPalette
+        | Notation::Latex => &[],
+    }
+}
+
+/// The value of the channel, which corresponds to `1.0` as fraction, `100%` or `FF`.
+fn channel_scale(notation: Notation, index: usize) -> f32 {
+    match (notation, channel_names(notation)[index]) {
+        (Notation::Rgb, _) => 255.0,
+        (Notation::Hsl | Notation::Hsv | Notation::Hwb | Notation::Hcl | Notation::Oklch, "h") => {
+            360.0
+        }
+        (Notation::Hsl | Notation::Hsv | Notation::Hwb | Notation::Cmyk | Notation::Xyz, _) => {
+            100.0
+        }
+        (Notation::Lab | Notation::Hcl | Notation::HunterLab, "l") => 100.0,
+        (Notation::Lab, _) => 125.0,
+        (Notation::Hcl, _) => 150.0,
+        (Notation::Oklab | Notation::Oklch, "a" | "b" | "c") => 0.4,
+        _ => 1.0,
+    }
+}
+
+/// Returns the channels of the color in the notation, in the units shown by the notation itself.
+fn to_channels(notation: Notation, color: Color, white: ReferenceWhite) -> Vec<f32> {
+    match notation {
+        Notation::Rgb => vec![color.red * 255.0, color.green * 255.0, color.blue * 255.0],
+        Notation::Hsl => {
+            let hsl: palette::Hsl = color.color.into_color();
+            let hue = hsl.hue.into_positive_degrees();
+            vec![hue, hsl.saturation * 100.0, hsl.lightness * 100.0]
+        }
+        Notation::Hsv => {
+            let hsv: palette::Hsv = color.color.into_color();
+            let hue = hsv.hue.into_positive_degrees();
+            vec![hue, hsv.saturation * 100.0, hsv.value * 100.0]
+        }
+        Notation::Hwb => {
+            let hwb: palette::Hwb = color.color.into_color();
+            let hue = hwb.hue.into_positive_degrees();
+            vec![hue, hwb.whiteness * 100.0, hwb.blackness * 100.0]
+        }
+        Notation::Cmyk => {
+            let cmyk: Cmyka = color.color.into_color();
+            [cmyk.cyan, cmyk.magenta, cmyk.yellow, cmyk.k]
+                .map(|value| value * 100.0)
+                .to_vec()
+        }
+        Notation::Xyz => {
+            let xyz = color.to_xyz(white);
+            vec![xyz.x * 100.0, xyz.y * 100.0, xyz.z * 100.0]
+        }
+        Notation::Lab => {
+            let lab = color.to_lab(white);
+            vec![lab.l, lab.a, lab.b]
+        }
+        Notation::Hcl => {
+            let lch: Lch<_> = color.to_lab(white).into_color();
+            vec![lch.l, lch.chroma, lch.hue.into_positive_degrees()]
+        }
+        Notation::Lms => {
+            let (long, medium, short) = color.to_lms(white);
+            vec![long, medium, short]
+        }
+        Notation::HunterLab => {
+            let lab = HunterLab::from_xyz(color.to_xyz(white), white.xyz());
+            vec![lab.l, lab.a, lab.b]
+        }
+        Notation::Oklab => {
+            let oklab = color.to_oklab();
+            vec![oklab.l, oklab.a, oklab.b]
+        }
+        Notation::Oklch => {
+            let oklch: Oklch = color.to_oklab().into_color_unclamped();
+            vec![oklch.l, oklch.chroma, oklch.hue.into_positive_degrees()]
+        }
+        Notation::SrgbLinear => RgbSpace::SrgbLinear.to_rgb(color).to_vec(),
+        Notation::DisplayP3 => RgbSpace::DisplayP3.to_rgb(color).to_vec(),
+        Notation::Rec2020 => RgbSpace::Rec2020.to_rgb(color).to_vec(),
+        Notation::A98Rgb => RgbSpace::A98Rgb.to_rgb(color).to_vec(),
+        Notation::ProphotoRgb => RgbSpace::ProphotoRgb.to_rgb(color).to_vec(),
+        Notation::Hex
+        | Notation::Name
+        | Notation::Android
+        | Notation::SwiftUi
+        | Notation::Flutter
+        | Notation::Qt
+        | Notation::Glsl
+        | Notation::DotNet
+        | Notation::RustPalette
+        | Notation::Latex => Vec::new(),
+    }
+}
+
+/// Creates a color from the channels returned by [`to_channels`].
+fn from_channels(notation: Notation, values: &[f32], alpha: f32, white: ReferenceWhite) -> Color {
+    let rgb_space = |space: RgbSpace| space.to_color([values[0], values[1], values[2]], alpha);
+    match notation {
+        Notation::Rgb => Color::from_palette(palette::Srgba::new(
+            values[0] / 255.0,
+            values[1] / 255.0,
+            values[2] / 255.0,
+            alpha,
+        )),
+        Notation::Hsl => Color::from_palette(palette::Hsla::new(
+            values[0],
+            values[1] / 100.0,
+            values[2] / 100.0,
+            alpha,
+        )),
+        Notation::Hsv => Color::from_palette(palette::Hsva::new(
+            values[0],
+            values[1] / 100.0,
+            values[2] / 100.0,
+            alpha,
+        )),
+        Notation::Hwb => Color::from_palette(palette::Hwba::new(
+            values[0],
+            values[1] / 100.0,
+            values[2] / 100.0,
+            alpha,
+        )),
+        Notation::Cmyk => Color::from_palette(Cmyka::new(
+            values[0] / 100.0,
+            values[1] / 100.0,
+            values[2] / 100.0,
+            values[3] / 100.0,
+            alpha,
+        )),
+        Notation::Xyz => Color::from_xyz(
+            palette::Xyz::new(values[0] / 100.0, values[1] / 100.0, values[2] / 100.0),
+            white,
+            alpha,
+        ),
+        Notation::Lab => Color::from_lab(Lab::new(values[0], values[1], values[2]), white, alpha),
+        Notation::Hcl => Color::from_lab(
+            Lch::new(values[0], values[1], values[2]).into_color_unclamped(),
+            white,
+            alpha,
+        ),
+        Notation::Lms => Color::from_lms(
+            values[0],
+            values[1],
+            values[2],
+            (alpha.clamp(0.0, 1.0) * 255.0).round() as u8,
+            white,
+        ),
+        Notation::HunterLab => Color::from_xyz(
+            HunterLab::new(values[0], values[1], values[2]).to_xyz(white.xyz()),
+            white,
+            alpha,
+        ),
+        Notation::Oklab => {
+            let linear: palette::LinSrgb =
+                Oklab::new(values[0], values[1], values[2]).into_color_unclamped();
+            Color::from_linear(linear.with_alpha(alpha))
+        }
+        Notation::Oklch => {
+            let linear: palette::LinSrgb =
+                Oklch::new(values[0], values[1], values[2]).into_color_unclamped();
+            Color::from_linear(linear.with_alpha(alpha))
+        }
+        Notation::SrgbLinear => rgb_space(RgbSpace::SrgbLinear),
+        Notation::DisplayP3 => rgb_space(RgbSpace::DisplayP3),
+        Notation::Rec2020 => rgb_space(RgbSpace::Rec2020),
+        Notation::A98Rgb => rgb_space(RgbSpace::A98Rgb),
+        Notation::ProphotoRgb => rgb_space(RgbSpace::ProphotoRgb),
+        Notation::Hex
+        | Notation::Name
+        | Notation::Android
+        | Notation::SwiftUi
+        | Notation::Flutter
+        | Notation::Qt
+        | Notation::Glsl
+        | Notation::DotNet
+        | Notation::RustPalette
+        | Notation::Latex => Color::default(),
+    }
+}
+
+/// How the value of a placeholder is written.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Style {
+    /// The value as shown by the notation itself, e.g. `0` to `255` for RGB.
+    Value,
+    /// The value as fraction, e.g. `0.5`.
+    Fraction,
+    /// The value as percentage, without the `%` sign.
+    Percent,
+    /// The fraction of the value as two hexadecimal digits, e.g. `7f` or `7F`.
+    Hex { uppercase: bool },
+}
+
+/// A channel of a color space, or the alpha value if the space is [`None`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Placeholder {
+    space: Option<Notation>,
+    index: usize,
+    style: Style,
+    precision: Option<usize>,
+}
+
+impl Placeholder {
+    /// Parses the content of a placeholder, such as `hsl.h` or `r:X`.
+    fn parse(content: &str) -> Result<Self, ColorError> {
+        let invalid = || {
+            ColorError::ParsingError(
+                gettext("Unknown placeholder “{}”").replace("{}", &format!("{{{content}}}")),
+            )
+        };
+        let (channel, format) = content.split_once(':').unwrap_or((content, ""));
+        let channel = channel.trim().to_ascii_lowercase();
+
+        let (space, index) = match channel.split_once('.') {
+            None if channel == "a" || channel == "alpha" => (None, Some(0)),
+            None => (Some(Notation::Rgb), channel_index(Notation::Rgb, &channel)),
+            Some((space, channel)) => {
+                let space = SPACES
+                    .iter()
+                    .find(|(name, _)| *name == space)
+                    .map(|(_, notation)| *notation)
+                    .ok_or_else(invalid)?;
+                (Some(space), channel_index(space, channel))
+            }
+        };
+        let index = index.ok_or_else(invalid)?;
+
+        let (style, precision) = match format.chars().next() {
+            Some('f') => (Style::Fraction, &format[1..]),
+            Some('%') => (Style::Percent, &format[1..]),
+            Some('x') => (Style::Hex { uppercase: false }, &format[1..]),
+            Some('X') => (Style::Hex { uppercase: true }, &format[1..]),
+            _ => (Style::Value, format),
+        };
+        let precision = match precision {
+            "" => None,
+            precision => Some(
+                precision
+                    .strip_prefix('.')
+                    .and_then(|digits| digits.parse::<usize>().ok())
+                    .filter(|_| !matches!(style, Style::Hex { .. }))
+                    .ok_or_else(invalid)?
+                    .min(MAX_PRECISION),
+            ),
+        };
+
+        Ok(Self {
+            space,
+            index,
+            style,
+            precision,
+        })
+    }
+
+    fn scale(&self) -> f32 {
+        self.space
+            .map_or(1.0, |space| channel_scale(space, self.index))
+    }
+
+    /// Writes the value, which is in the units of the notation.
+    fn format(&self, value: f32, precision: usize) -> String {
+        let fraction = value / self.scale();
+        // RGB values are integers, unless a precision is given
+        let default_precision = if self.space == Some(Notation::Rgb) && self.style == Style::Value {
+            0
+        } else {
+            precision
+        };
+        let precision = self.precision.unwrap_or(default_precision);
+
+        let value = match self.style {
+            Style::Value => value,
+            Style::Fraction => fraction,
+            Style::Percent => fraction * 100.0,
+            Style::Hex { uppercase } => {
+                let byte = (fraction.clamp(0.0, 1.0) * 255.0).round() as u8;
+                return if uppercase {
+                    format!("{byte:02X}")
+                } else {
+                    format!("{byte:02x}")
+                };
+            }
+        };
+        // avoid showing `-0`
+        let value = format!("{value:.precision$}");
+        if value.trim_start_matches(['-', '0', '.']).is_empty() {
+            value.trim_start_matches('-').to_owned()
+        } else {
+            value
+        }
+    }
+
+    /// Parses a value written by [`Placeholder::format`], returning it in the units of the notation.
+    fn parse_value<'a>(&self, input: &'a str) -> Option<(&'a str, f32)> {
+        if let Style::Hex { .. } = self.style {
+            let digits = input.get(..2)?;
+            let byte = u8::from_str_radix(digits, 16).ok()?;
+            return Some((&input[2..], byte as f32 / 255.0 * self.scale()));
+        }
+
+        let (rest, value) = parser::number(input).ok()?;
+        let value = match self.style {
+            Style::Fraction => value * self.scale(),
+            Style::Percent => value / 100.0 * self.scale(),
+            _ => value,
+        };
+        Some((rest, value))
+    }
+}
+
+fn channel_index(space: Notation, channel: &str) -> Option<usize> {
+    channel_names(space)
+        .iter()
+        .position(|name| *name == channel)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Text(String),
+    Placeholder(Placeholder),
+}
+
+/// A user defined format, which writes the channels of a color into a template.
+///
+/// Placeholders are written in braces, such as `{hsl.h}`, where the name of the color space is the
+/// same as in the `format-order` setting. The color space can be omitted for RGB (`{r}`, `{g}`, `{b}`)
+/// and the alpha value (`{a}` or `{alpha}`). A placeholder can be followed by a format:
+/// - `{r:.2}` shows the value with two digits after the decimal point
+/// - `{r:f}` shows the value as fraction, e.g. `0.5`
+/// - `{r:%}` shows the value as percentage, without the `%` sign
+/// - `{r:x}` or `{r:X}` show the value as two hexadecimal digits
+///
+/// `{{` and `}}` are written as literal braces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    name: String,
+    pattern: String,
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    /// Creates a template from the pattern.
+    ///
+    /// Fails if the pattern contains unknown placeholders, or does not contain all channels
+    /// of at least one color space, as colors could not be parsed back.
+    pub fn new(name: impl Into<String>, pattern: &str) -> Result<Self, ColorError> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut chars = pattern.chars().peekable();
+        while let Some(char) = chars.next() {
+            match char {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut content = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(char) => content.push(char),
+                            None => {
+                                return Err(ColorError::ParsingError(gettext(
+                                    "Unmatched “{” in template",
+                                )));
+                            }
+                        }
+                    }
+                    let placeholder = Placeholder::parse(&content)?;
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+                    segments.push(Segment::Placeholder(placeholder));
+                }
+                '}' => {
+                    return Err(ColorError::ParsingError(gettext(
+                        "Unmatched “}” in template",
+                    )));
+                }
+                _ => text.push(char),
+            }
+        }
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+
+        let template = Self {
+            name: name.into(),
+            pattern: pattern.to_owned(),
+            segments,
+        };
+        if template.spaces().next().is_none() {
+            return Err(ColorError::ParsingError(gettext(
+                "The template has to contain all channels of a color space",
+            )));
+        }
+        Ok(template)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Identifier of the template in the `format-order` and `visible-formats` settings.
+    pub fn identifier(&self) -> String {
+        format!("{CUSTOM_PREFIX}{}", self.name)
+    }
+
+    fn placeholders(&self) -> impl Iterator<Item = &Placeholder> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Placeholder(placeholder) => Some(placeholder),
+            Segment::Text(_) => None,
+        })
+    }
+
+    /// Color spaces, of which all channels are contained in the template, in the order of their first channel.
+    fn spaces(&self) -> impl Iterator<Item = Notation> + '_ {
+        let mut spaces: Vec<Notation> = Vec::new();
+        for space in self
+            .placeholders()
+            .filter_map(|placeholder| placeholder.space)
+        {
+            if !spaces.contains(&space) {
+                spaces.push(space);
+            }
+        }
+        spaces.into_iter().filter(|space| {
+            (0..channel_names(*space).len()).all(|index| {
+                self.placeholders().any(|placeholder| {
+                    placeholder.space == Some(*space) && placeholder.index == index
+                })
+            })
+        })
+    }
+
+    /// Whether the template contains channels of a color space, which can only represent colors in the sRGB gamut.
+    pub fn is_srgb(&self) -> bool {
+        self.placeholders()
+            .filter_map(|placeholder| placeholder.space)
+            .any(|space| space.is_srgb())
+    }
+
+    /// Writes the color into the template.
+    ///
+    /// Channels of sRGB based color spaces are taken from the color mapped into the sRGB gamut.
+    pub fn format(
+        &self,
+        color: Color,
+        precision: usize,
+        reference_white: ReferenceWhite,
+        gamut_mapping: GamutMapping,
+    ) -> String {
+        let mapped = gamut_mapping.apply(color);
+        let mut channels: Vec<(Notation, Vec<f32>)> = Vec::new();
+
+        let mut output = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => output.push_str(text),
+                Segment::Placeholder(placeholder) => {
+                    let value = match placeholder.space {
+                        None => color.alpha,
+                        Some(space) => {
+                            let values = match channels.iter().find(|(cached, _)| *cached == space)
+                            {
+                                Some((_, values)) => values,
+                                None => {
+                                    let color = if space.is_srgb() { mapped } else { color };
+                                    channels
+                                        .push((space, to_channels(space, color, reference_white)));
+                                    &channels[channels.len() - 1].1
+                                }
+                            };
+                            values[placeholder.index]
+                        }
+                    };
+                    output.push_str(&placeholder.format(value, precision));
+                }
+            }
+        }
+        output
+    }
+
+    /// Parses a color written by [`Template::format`].
+    ///
+    /// Whitespace in the template is optional, and text is compared ignoring the case.
+    /// The color is created from the first color space, of which all channels are contained.
+    pub fn parse(&self, input: &str, reference_white: ReferenceWhite) -> Result<Color, ColorError> {
+        let error = || ColorError::ParsingError("Input does not match the template".to_owned());
+
+        let mut values: Vec<(Placeholder, f32)> = Vec::new();
+        let mut input = input.trim();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => {
+                    for char in text.chars() {
+                        if char.is_whitespace() {
+                            input = input.trim_start();
+                            continue;
+                        }
+                        let mut chars = input.chars();
+                        match chars.next() {
+                            Some(next) if next.to_lowercase().eq(char.to_lowercase()) => {
+                                input = chars.as_str();
+                            }
+                            _ => return Err(error()),
+                        }
+                    }
+                }
+                Segment::Placeholder(placeholder) => {
+                    let (rest, value) = placeholder.parse_value(input).ok_or_else(error)?;
+                    values.push((*placeholder, value));
+                    input = rest;
+                }
+            }
+        }
+        if !input.trim().is_empty() {
+            return Err(error());
+        }
+
+        let value = |space: Option<Notation>, index: usize| {
+            values
+                .iter()
+                .find(|(placeholder, _)| placeholder.space == space && placeholder.index == index)
+                .map(|(_, value)| *value)
+        };
+        let space = self.spaces().next().ok_or_else(error)?;
+        let channels = (0..channel_names(space).len())
+            .filter_map(|index| value(Some(space), index))
+            .collect::<Vec<f32>>();
+        let alpha = value(None, 0).unwrap_or(1.0).clamp(0.0, 1.0);
+
+        Ok(from_channels(space, &channels, alpha, reference_white))
+    }
+
+    /// Returns the templates stored in the `custom-formats` setting.
+    ///
+    /// Invalid templates are skipped.
+    pub fn load(settings: &gio::Settings) -> Vec<Template> {
+        settings
+            .get::<Vec<(String, String)>>("custom-formats")
+            .into_iter()
+            .filter_map(|(name, pattern)| match Template::new(name, &pattern) {
+                Ok(template) => Some(template),
+                Err(err) => {
+                    tracing::warn!("Skipping invalid custom format {}: {}", pattern, err);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Stores the templates in the `custom-formats` setting.
+    pub fn store(settings: &gio::Settings, templates: &[Template]) -> Result<(), glib::BoolError> {
+        let templates = templates
+            .iter()
+            .map(|template| (template.name.clone(), template.pattern.clone()))
+            .collect::<Vec<_>>();
+        settings.set("custom-formats", templates)
+    }
+}
+
+#[cfg(test)]
+mod format_template {
+    use super::*;
+
+    fn format(pattern: &str, color: Color) -> String {
+        Template::new("Test", pattern).unwrap().format(
+            color,
+            2,
+            ReferenceWhite::default(),
+            GamutMapping::default(),
+        )
+    }
+
+    fn round_trip(pattern: &str) {
+        let template = Template::new("Test", pattern).unwrap();
+        let color = Color::rgba(46, 52, 64, 128);
+        let output = template.format(color, 6, ReferenceWhite::default(), GamutMapping::Clip);
+        let parsed = template
+            .parse(&output, ReferenceWhite::default())
+            .unwrap_or_else(|err| panic!("failed to parse {output}: {err}"));
+        assert_eq!(
+            color.into_format::<u8, u8>(),
+            parsed.into_format::<u8, u8>(),
+            "{pattern}: {output}"
+        );
+    }
+
+    #[test]
+    fn it_formats_channels() {
+        let color = Color::rgba(17, 34, 51, 255);
+        assert_eq!(
+            "Color(0xFF112233)",
+            format("Color(0x{a:X}{r:X}{g:X}{b:X})", color)
+        );
+        assert_eq!(
+            "new Color(17, 34, 51)",
+            format("new Color({r}, {g}, {b})", color)
+        );
+        assert_eq!(
+            "UIColor(red: 0.067, green: 0.133, blue: 0.200, alpha: 1.000)",
+            format(
+                "UIColor(red: {r:f.3}, green: {g:f.3}, blue: {b:f.3}, alpha: {a:.3})",
+                color
+            )
+        );
+        assert_eq!(
+            "vec3(0.1, 0.1, 0.2)",
+            format("vec3({r:f.1}, {g:f.1}, {b:f.1})", color)
+        );
+        assert_eq!("#112233", format("#{r:x}{g:x}{b:x}", color));
+        assert_eq!(
+            "210 0.50 13.33",
+            format("{hsl.h:.0} {hsl.s:f} {hsl.l:%}", color)
+        );
+        assert_eq!(
+            "{210, 50%, 13%}",
+            format("{{{hsl.h:.0}, {hsl.s:.0}%, {hsl.l:.0}%}}", color)
+        );
+    }
+
+    #[test]
+    fn it_parses_formatted_colors() {
+        for pattern in [
+            "Color(0x{a:X}{r:X}{g:X}{b:X})",
+            "rgba({r}, {g}, {b}, {a})",
+            "UIColor(red: {r:f}, green: {g:f}, blue: {b:f}, alpha: {a})",
+            "vec4({r:f}, {g:f}, {b:f}, {a:f})",
+            "{hsl.h} {hsl.s:%} {hsl.l:f} / {alpha:%}",
+            "hsv({hsv.h}, {hsv.s}, {hsv.v}) {a}",
+            "{cmyk.c}|{cmyk.m}|{cmyk.y}|{cmyk.k}|{a:x}",
+            "XYZ {xyz.x} {xyz.y} {xyz.z} {a}",
+            "L={cielab.l} a={cielab.a} b={cielab.b} alpha={a}",
+            "{hcl.l:f} {hcl.c:f} {hcl.h:f} {a}",
+            "{lms.l} {lms.m} {lms.s} {a}",
+            "{hunterlab.l} {hunterlab.a} {hunterlab.b} {a}",
+            "{hwb.h} {hwb.w} {hwb.b} {a}",
+            "ok({oklab.l}; {oklab.a}; {oklab.b}; {a})",
+            "ok({oklch.l:%}; {oklch.c:%}; {oklch.h}; {a})",
+            "p3 {display-p3.r} {display-p3.g} {display-p3.b} {a}",
+            "{prophoto-rgb.r} {prophoto-rgb.g} {prophoto-rgb.b} {a}",
+        ] {
+            round_trip(pattern);
+        }
+    }
+
+    #[test]
+    fn it_parses_leniently() {
+        let template = Template::new("Test", "Color( {r}, {g}, {b} )").unwrap();
+        let white = ReferenceWhite::default();
+        assert_eq!(
+            Color::rgba(17, 34, 51, 255),
+            template.parse("  color(17,34,51)", white).unwrap()
+        );
+        assert!(template.parse("Color(17, 34)", white).is_err());
+        assert!(template.parse("Color(17, 34, 51) and more", white).is_err());
+    }
+
+    #[test]
+    fn it_rejects_invalid_templates() {
+        for pattern in [
+            "{r} {g}",
+            "{r} {g} {b",
+            "{r} {g} {b}}",
+            "{r} {g} {b} {q}",
+            "{r} {g} {b} {unknown.r}",
+            "{hsl.h} {hsl.s} {hsl.x}",
+            "{r:.x} {g} {b}",
+            "{r:X.2} {g} {b}",
+            "no placeholders",
+        ] {
+            assert!(Template::new("Test", pattern).is_err(), "{pattern}");
+        }
+    }
+}
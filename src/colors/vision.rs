@@ -0,0 +1,235 @@
+use gtk::{gio, prelude::SettingsExt};
+use palette::{Xyz, white_point::Any};
+
+use super::{
+    color::Color,
+    illuminant::{Illuminant, ReferenceWhite, StandardObserver},
+};
+
+/// Types of color vision deficiencies, by the affected cone.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Deficiency {
+    /// Missing or anomalous long-wavelength (red) cones.
+    Protan,
+    /// Missing or anomalous medium-wavelength (green) cones.
+    Deutan,
+    /// Missing or anomalous short-wavelength (blue) cones.
+    Tritan,
+}
+
+impl Deficiency {
+    /// Converts the identifier used in the settings, where `none` means normal vision.
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "protan" => Some(Self::Protan),
+            "deutan" => Some(Self::Deutan),
+            "tritan" => Some(Self::Tritan),
+            _ => None,
+        }
+    }
+}
+
+/// Methods of simulating dichromacy.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum SimulationMethod {
+    /// Projection onto two half-planes, anchored at monochromatic wavelengths.
+    ///
+    /// Based on Brettel, Viénot and Mollon (1997), Computerized simulation of color appearance for dichromats.
+    #[default]
+    Brettel,
+    /// Projection onto a single plane, anchored at the primaries of the display.
+    ///
+    /// Based on Viénot, Brettel and Mollon (1999), Digital video colourmaps for checking the legibility of displays by dichromats.
+    Vienot,
+}
+
+//Convert from U32. Needed for converting from the settings AdwComboRow, which use indexes for values.
+impl From<u32> for SimulationMethod {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::Vienot,
+            _ => Self::Brettel,
+        }
+    }
+}
+
+/// Simulation of a color vision deficiency.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Simulation {
+    pub deficiency: Deficiency,
+    /// Severity between 0.0 (normal vision) and 1.0 (dichromacy, e.g. protanopia).
+    /// Values in between simulate anomalous trichromacy, e.g. protanomaly.
+    pub severity: f32,
+    pub method: SimulationMethod,
+}
+
+/// CIE 1931 2° color matching functions of the anchor wavelengths used by Brettel.
+const XYZ_475: (f32, f32, f32) = (0.1421, 0.1126, 1.0419);
+const XYZ_575: (f32, f32, f32) = (0.8425, 0.9154, 0.0018);
+const XYZ_485: (f32, f32, f32) = (0.05795, 0.1693, 0.6162);
+const XYZ_660: (f32, f32, f32) = (0.1649, 0.061, 0.0);
+
+impl Simulation {
+    /// Create a new simulation of the deficiency.
+    pub fn new(deficiency: Deficiency, severity: f32, method: SimulationMethod) -> Self {
+        Self {
+            deficiency,
+            severity: severity.clamp(0.0, 1.0),
+            method,
+        }
+    }
+
+    /// Returns the simulation selected in the settings, if any.
+    pub fn from_settings(settings: &gio::Settings) -> Option<Self> {
+        let deficiency = Deficiency::from_id(&settings.string("vision-deficiency"))?;
+        Some(Self::new(
+            deficiency,
+            settings.uint("vision-severity") as f32 / 100.0,
+            SimulationMethod::from(settings.int("vision-simulation-method") as u32),
+        ))
+    }
+
+    /// Simulates how the color is seen with the deficiency.
+    ///
+    /// The color is projected in the LMS color space, normalized to the equal energy
+    /// white point, so that neutral colors stay the same. Anomalous trichromacy is
+    /// approximated by interpolating between the color and the dichromat projection.
+    pub fn apply(&self, color: Color) -> Color {
+        // LMS is normalized to illuminant E, so white is (1.0, 1.0, 1.0)
+        let white = ReferenceWhite::new(Illuminant::E, StandardObserver::Two);
+        let lms = color.to_lms(white);
+        let lms = [lms.0, lms.1, lms.2];
+        let neutral = [1.0, 1.0, 1.0];
+
+        let normal = match self.method {
+            SimulationMethod::Brettel => {
+                let (first, second) = match self.deficiency {
+                    Deficiency::Protan | Deficiency::Deutan => (XYZ_475, XYZ_575),
+                    Deficiency::Tritan => (XYZ_485, XYZ_660),
+                };
+                let first = spectral_lms(first);
+                let second = spectral_lms(second);
+
+                // the separation plane contains the neutral axis and the missing cone axis,
+                // the color is projected onto the half-plane on its side
+                let mut axis = [0.0; 3];
+                axis[self.cone()] = 1.0;
+                let separation = cross(neutral, axis);
+                if dot(lms, separation).signum() == dot(first, separation).signum() {
+                    cross(neutral, first)
+                } else {
+                    cross(neutral, second)
+                }
+            }
+            SimulationMethod::Vienot => {
+                let anchor = match self.deficiency {
+                    Deficiency::Protan | Deficiency::Deutan => Color::rgba(0, 0, 255, 255),
+                    Deficiency::Tritan => Color::rgba(255, 0, 0, 255),
+                };
+                let anchor = anchor.to_lms(white);
+                cross(neutral, [anchor.0, anchor.1, anchor.2])
+            }
+        };
+
+        // replace the missing cone response, so the color lies on the projection plane
+        let cone = self.cone();
+        let mut simulated = lms;
+        simulated[cone] = -(0..3)
+            .filter(|&i| i != cone)
+            .map(|i| normal[i] * lms[i])
+            .sum::<f32>()
+            / normal[cone];
+
+        let mix = |i: usize| lms[i] + (simulated[i] - lms[i]) * self.severity;
+        Color::from_lms(
+            mix(0),
+            mix(1),
+            mix(2),
+            (color.alpha * 255.0).round() as u8,
+            white,
+        )
+    }
+
+    /// Index of the affected cone in LMS.
+    fn cone(&self) -> usize {
+        match self.deficiency {
+            Deficiency::Protan => 0,
+            Deficiency::Deutan => 1,
+            Deficiency::Tritan => 2,
+        }
+    }
+}
+
+/// LMS values of a monochromatic light with the given XYZ values.
+fn spectral_lms((x, y, z): (f32, f32, f32)) -> [f32; 3] {
+    let (long, medium, short) = Color::xyz_to_lms(Xyz::<Any, f32>::new(x, y, z));
+    [long, medium, short]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[cfg(test)]
+mod simulate_deficiency {
+    use super::*;
+
+    const DEFICIENCIES: [Deficiency; 3] =
+        [Deficiency::Protan, Deficiency::Deutan, Deficiency::Tritan];
+    const METHODS: [SimulationMethod; 2] = [SimulationMethod::Brettel, SimulationMethod::Vienot];
+
+    fn assert_similar(expected: Color, actual: Color) {
+        let difference = (expected.red - actual.red).abs()
+            + (expected.green - actual.green).abs()
+            + (expected.blue - actual.blue).abs();
+        assert!(difference < 0.02, "expected {expected}, got {actual}");
+    }
+
+    #[test]
+    fn it_keeps_neutral_colors() {
+        for deficiency in DEFICIENCIES {
+            for method in METHODS {
+                let simulation = Simulation::new(deficiency, 1.0, method);
+                for gray in [0, 64, 128, 255] {
+                    let color = Color::rgba(gray, gray, gray, 255);
+                    assert_similar(color, simulation.apply(color));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_keeps_colors_without_severity() {
+        let color = Color::rgba(200, 40, 90, 255);
+        for deficiency in DEFICIENCIES {
+            let simulation = Simulation::new(deficiency, 0.0, SimulationMethod::Brettel);
+            assert_similar(color, simulation.apply(color));
+        }
+    }
+
+    #[test]
+    fn it_confuses_red_and_green() {
+        let red = Color::rgba(200, 60, 40, 255);
+        let green = Color::rgba(110, 120, 40, 255);
+        let distance = |a: Color, b: Color| {
+            (a.red - b.red).abs() + (a.green - b.green).abs() + (a.blue - b.blue).abs()
+        };
+
+        for deficiency in [Deficiency::Protan, Deficiency::Deutan] {
+            for method in METHODS {
+                let simulation = Simulation::new(deficiency, 1.0, method);
+                assert!(
+                    distance(simulation.apply(red), simulation.apply(green)) < distance(red, green)
+                );
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 mod application;
+mod cli;
 #[rustfmt::skip]
 mod config;
 mod colors;
@@ -14,7 +15,10 @@ use self::application::App;
 use self::config::{GETTEXT_PACKAGE, LOCALEDIR, RESOURCES_FILE};
 
 fn main() -> ExitCode {
-    tracing_subscriber::fmt::init();
+    // log to stderr, so the output of the command line interface is not polluted
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .init();
 
     // ensure Adwaita will be used
     unsafe {
@@ -26,6 +30,12 @@ fn main() -> ExitCode {
     gettextrs::bindtextdomain(GETTEXT_PACKAGE, LOCALEDIR).expect("Unable to bind the text domain");
     gettextrs::textdomain(GETTEXT_PACKAGE).expect("Unable to switch to the text domain");
 
+    // convert colors without starting the user interface
+    let args = std::env::args().collect::<Vec<String>>();
+    if args.get(1).is_some_and(|arg| arg == cli::CONVERT_COMMAND) {
+        return cli::convert(&args[2..]);
+    }
+
     glib::set_application_name(&gettext("Eyedropper"));
 
     let res = gio::Resource::load(RESOURCES_FILE).expect("Could not load gresource file");
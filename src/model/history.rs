@@ -1,18 +1,57 @@
+use gettextrs::gettext;
 use glib::Object;
-use glib::prelude::*;
 use glib::subclass::prelude::*;
-use gtk::glib;
+use gtk::prelude::*;
+use gtk::{gio, glib};
 
 use crate::colors::color::Color;
 
+/// Maximum number of colors in the history.
+///
+/// The whole history is stored after every change, so it must not grow indefinitely.
+pub const HISTORY_LIMIT: u32 = 500;
+
+/// Where a color of the history came from.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, glib::Enum, glib::Variant)]
+#[variant_enum(enum)]
+#[enum_type(name = "ColorSource")]
+pub enum ColorSource {
+    /// Restored from a history without sources.
+    #[default]
+    Unknown,
+    Screen,
+    Image,
+    Typed,
+    Random,
+    SearchProvider,
+    Editor,
+    Import,
+}
+
+impl ColorSource {
+    /// Describes where the color came from, `None` if it is unknown.
+    pub fn description(&self) -> Option<String> {
+        let description = match self {
+            ColorSource::Unknown => return None,
+            ColorSource::Screen => gettext("Picked from the screen"),
+            ColorSource::Image => gettext("Picked from an image"),
+            ColorSource::Typed => gettext("Entered"),
+            ColorSource::Random => gettext("Random color"),
+            ColorSource::SearchProvider => gettext("From the search"),
+            ColorSource::Editor => gettext("Created in Eyedropper"),
+            ColorSource::Import => gettext("Imported from a palette"),
+        };
+        Some(description)
+    }
+}
+
 mod imp {
-    use std::cell::Cell;
+    use std::cell::{Cell, RefCell};
 
     use glib::{
         Properties,
         subclass::{prelude::ObjectImpl, types::ObjectSubclass},
     };
-    use gtk::gdk;
 
     use super::*;
 
@@ -20,13 +59,24 @@ mod imp {
     #[properties(wrapper_type = super::HistoryObject)]
     pub struct HistoryObject {
         #[property(get, set)]
-        pub color: Cell<gdk::RGBA>,
+        pub color: Cell<Color>,
+        /// When the color was picked, `None` for colors restored from a history without timestamps.
+        #[property(get, set, nullable)]
+        pub picked: RefCell<Option<glib::DateTime>>,
+        #[property(get, set, builder(ColorSource::default()))]
+        pub source: Cell<ColorSource>,
+        /// User defined label or note, empty if there is none.
+        #[property(get, set)]
+        pub label: RefCell<String>,
     }
 
     impl Default for HistoryObject {
         fn default() -> Self {
             Self {
-                color: Cell::new(gdk::RGBA::BLACK),
+                color: Cell::new(Color::rgba(0, 0, 0, 255)),
+                picked: Default::default(),
+                source: Default::default(),
+                label: Default::default(),
             }
         }
     }
@@ -46,8 +96,109 @@ glib::wrapper! {
 }
 
 impl HistoryObject {
-    pub fn new(color: Color) -> Self {
-        let color: gtk::gdk::RGBA = color.into();
-        Object::builder().property("color", color).build()
+    /// Creates a new history entry, which was picked just now.
+    pub fn new(color: Color, source: ColorSource) -> Self {
+        Object::builder()
+            .property("color", color)
+            .property("picked", glib::DateTime::now_local().ok())
+            .property("source", source)
+            .build()
+    }
+}
+
+/// Removes the entry of the color from the history and returns it.
+///
+/// Returns `None` if the history does not contain the color.
+pub fn take_entry(history: &gio::ListStore, color: Color) -> Option<HistoryObject> {
+    let index = history.find_with_equal_func(|item| {
+        item.downcast_ref::<HistoryObject>().unwrap().color() == color
+    })?;
+    let item = history.item(index).and_downcast::<HistoryObject>();
+    history.remove(index);
+    item
+}
+
+/// Moves the entry of the color to the front of the history.
+///
+/// As the color is only selected again, the entry keeps where and when it was picked.
+/// Returns `false` if the history does not contain the color.
+pub fn select_entry(history: &gio::ListStore, color: Color) -> bool {
+    let Some(item) = take_entry(history, color) else {
+        return false;
+    };
+    history.insert(0, &item);
+    true
+}
+
+/// Removes the oldest entries exceeding the [`HISTORY_LIMIT`].
+pub fn truncate(history: &gio::ListStore) {
+    let length = history.n_items();
+    if length > HISTORY_LIMIT {
+        history.splice(
+            HISTORY_LIMIT,
+            length - HISTORY_LIMIT,
+            &[] as &[HistoryObject],
+        );
+    }
+}
+
+#[cfg(test)]
+mod update_history {
+    use super::*;
+
+    fn history(colors: &[(Color, ColorSource)]) -> gio::ListStore {
+        let history = gio::ListStore::new::<HistoryObject>();
+        for &(color, source) in colors {
+            history.append(&HistoryObject::new(color, source));
+        }
+        history
+    }
+
+    fn entry(history: &gio::ListStore, index: u32) -> HistoryObject {
+        history.item(index).and_downcast::<HistoryObject>().unwrap()
+    }
+
+    #[test]
+    fn it_keeps_the_source_of_selected_entries() {
+        let red = Color::rgba(255, 0, 0, 255);
+        let blue = Color::rgba(0, 0, 255, 255);
+        let history = history(&[(red, ColorSource::Screen), (blue, ColorSource::Import)]);
+        let picked = entry(&history, 1).picked();
+
+        assert!(select_entry(&history, blue));
+        assert_eq!(2, history.n_items());
+        let selected = entry(&history, 0);
+        assert_eq!(blue, selected.color());
+        assert_eq!(ColorSource::Import, selected.source());
+        assert_eq!(picked, selected.picked());
+        assert_eq!(red, entry(&history, 1).color());
+
+        assert!(!select_entry(&history, Color::rgba(0, 255, 0, 255)));
+        assert_eq!(2, history.n_items());
+    }
+
+    #[test]
+    fn it_takes_entries() {
+        let red = Color::rgba(255, 0, 0, 255);
+        let history = history(&[(red, ColorSource::Typed)]);
+        assert_eq!(None, take_entry(&history, Color::rgba(0, 0, 255, 255)));
+        assert_eq!(
+            Some(ColorSource::Typed),
+            take_entry(&history, red).map(|item| item.source())
+        );
+        assert_eq!(0, history.n_items());
+    }
+
+    #[test]
+    fn it_limits_the_history() {
+        let colors = (0..=HISTORY_LIMIT)
+            .map(|value| (Color::rgba(0, 0, 0, value as u8), ColorSource::Random))
+            .collect::<Vec<_>>();
+        let history = history(&colors);
+        truncate(&history);
+        assert_eq!(HISTORY_LIMIT, history.n_items());
+        // the oldest entry is removed
+        let last = HISTORY_LIMIT - 1;
+        assert_eq!(colors[last as usize].0, entry(&history, last).color());
     }
 }
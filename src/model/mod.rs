@@ -1 +1,3 @@
 pub mod history;
+pub mod palette;
+pub mod palette_color;
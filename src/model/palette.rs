@@ -0,0 +1,276 @@
+use std::path::{Path, PathBuf};
+
+use gettextrs::gettext;
+use glib::Object;
+use glib::subclass::prelude::*;
+use gtk::prelude::*;
+use gtk::{gio, glib};
+
+use crate::colors::color::Color;
+use crate::config;
+use crate::model::palette_color::PaletteColor;
+
+/// Type of the serialized palette library, the name and the colors with their labels of each palette.
+type Library = Vec<(String, Vec<(Color, String)>)>;
+
+mod imp {
+    use std::cell::RefCell;
+
+    use glib::{
+        Properties,
+        subclass::{prelude::ObjectImpl, types::ObjectSubclass},
+    };
+
+    use super::*;
+
+    #[derive(Debug, Properties)]
+    #[properties(wrapper_type = super::Palette)]
+    pub struct Palette {
+        #[property(get, set)]
+        pub name: RefCell<String>,
+        /// The [`PaletteColor`]s of the palette.
+        pub colors: gio::ListStore,
+    }
+
+    impl Default for Palette {
+        fn default() -> Self {
+            Self {
+                name: Default::default(),
+                colors: gio::ListStore::new::<PaletteColor>(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for Palette {
+        const NAME: &'static str = "Palette";
+        type Type = super::Palette;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for Palette {}
+}
+
+glib::wrapper! {
+    /// A named collection of colors in the palette library.
+    pub struct Palette(ObjectSubclass<imp::Palette>);
+}
+
+impl Palette {
+    pub fn new(name: &str) -> Self {
+        Object::builder().property("name", name).build()
+    }
+
+    /// The [`PaletteColor`]s of the palette.
+    pub fn colors(&self) -> gio::ListStore {
+        self.imp().colors.clone()
+    }
+
+    /// Appends the color to the palette.
+    pub fn add_color(&self, color: Color, label: &str) {
+        self.colors().append(&PaletteColor::new(color, label));
+    }
+
+    /// Returns the colors of the palette with their labels.
+    pub fn entries(&self) -> Vec<(Color, String)> {
+        self.colors()
+            .snapshot()
+            .iter()
+            .filter_map(Cast::downcast_ref::<PaletteColor>)
+            .map(|color| (color.color(), color.label()))
+            .collect()
+    }
+
+    /// The file storing the palette library, in the user data directory.
+    pub fn library_path() -> PathBuf {
+        glib::user_data_dir()
+            .join(config::APP_ID)
+            .join("palettes.gvariant")
+    }
+
+    /// Returns a name for a new palette, which is not used by any palette of the library.
+    pub fn unused_name(library: &gio::ListStore) -> String {
+        let names = library
+            .snapshot()
+            .iter()
+            .filter_map(Cast::downcast_ref::<Palette>)
+            .map(Palette::name)
+            .collect::<Vec<_>>();
+        (1..)
+            .map(|number| gettext("Palette {}").replace("{}", &number.to_string()))
+            .find(|name| !names.contains(name))
+            .unwrap()
+    }
+
+    /// Loads the palette library from the file.
+    ///
+    /// Returns an empty library if the file does not exist, fails if it cannot be read
+    /// or does not contain a palette library.
+    pub fn load(path: &Path) -> Result<Vec<Palette>, glib::Error> {
+        let contents = match glib::file_get_contents(path) {
+            Ok(contents) => contents,
+            Err(err) if err.matches(glib::FileError::Noent) => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let text = std::str::from_utf8(&contents)
+            .map_err(|err| glib::Error::new(gio::IOErrorEnum::InvalidData, &err.to_string()))?;
+        let library = glib::Variant::parse(Some(&Library::static_variant_type()), text)?
+            .get::<Library>()
+            .ok_or_else(|| {
+                glib::Error::new(gio::IOErrorEnum::InvalidData, "Not a palette library")
+            })?;
+
+        Ok(library
+            .into_iter()
+            .map(|(name, colors)| {
+                let palette = Palette::new(&name);
+                for (color, label) in colors {
+                    palette.add_color(color, &label);
+                }
+                palette
+            })
+            .collect())
+    }
+
+    /// Loads the palette library from its file, see [`Palette::library_path`].
+    ///
+    /// A file which cannot be loaded is moved to a backup next to it, so the palettes in it
+    /// are not overwritten when the library is saved again.
+    pub fn load_library() -> Vec<Palette> {
+        let path = Palette::library_path();
+        match Palette::load(&path) {
+            Ok(palettes) => palettes,
+            Err(err) => {
+                tracing::error!("Failed to load palettes: {}", err);
+                Palette::back_up(&path);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Moves the file to a backup file, with the additional extension `.bak`.
+    fn back_up(path: &Path) {
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(".bak");
+        match std::fs::rename(path, &backup) {
+            Ok(()) => tracing::warn!("Moved unreadable palettes to {}", backup.display()),
+            Err(err) => tracing::error!("Failed to back up palettes: {}", err),
+        }
+    }
+
+    /// Stores the palettes of the library in the file, replacing its previous content.
+    pub fn store(path: &Path, library: &gio::ListStore) -> Result<(), glib::Error> {
+        let palettes = library
+            .snapshot()
+            .iter()
+            .filter_map(Cast::downcast_ref::<Palette>)
+            .map(|palette| (palette.name(), palette.entries()))
+            .collect::<Library>();
+        if let Some(directory) = path.parent() {
+            gio::File::for_path(directory)
+                .make_directory_with_parents(gio::Cancellable::NONE)
+                .or_else(|err| {
+                    if err.matches(gio::IOErrorEnum::Exists) {
+                        Ok(())
+                    } else {
+                        Err(err)
+                    }
+                })?;
+        }
+        glib::file_set_contents(path, palettes.to_variant().print(true).as_bytes())
+    }
+
+    /// Stores the palette library in its file, see [`Palette::library_path`].
+    ///
+    /// Failures are only logged, as the palettes are still available in this session.
+    pub fn save_library(library: &gio::ListStore) {
+        if let Err(err) = Palette::store(&Palette::library_path(), library) {
+            tracing::error!("Failed to save palettes: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod store_palettes {
+    use super::*;
+
+    /// Directory of a single test, which is removed again when dropped, even if the test fails.
+    struct TestDir(PathBuf);
+
+    impl TestDir {
+        fn new(test: &str) -> Self {
+            Self(std::env::temp_dir().join(format!("eyedropper-{}-{}", std::process::id(), test)))
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn it_restores_stored_palettes() {
+        let first = Palette::new("Nord");
+        first.add_color(Color::rgba(46, 52, 64, 255), "Polar Night");
+        first.add_color(Color::rgba(136, 192, 208, 128), "");
+        // colors outside of the sRGB gamut are kept exactly
+        let wide = Color::unclamped(palette::Srgba::new(1.2, -0.1, 0.333, 1.0));
+        first.add_color(wide, "Wide");
+        let second = Palette::new("Empty");
+
+        let library = gio::ListStore::new::<Palette>();
+        library.extend_from_slice(&[first.clone(), second]);
+        let dir = TestDir::new("restore");
+        let path = dir.0.join("palettes.gvariant");
+        Palette::store(&path, &library).unwrap();
+
+        let restored = Palette::load(&path).unwrap();
+        assert_eq!(2, restored.len());
+        assert_eq!("Nord", restored[0].name());
+        assert_eq!(first.entries(), restored[0].entries());
+        assert_eq!("Empty", restored[1].name());
+        assert!(restored[1].entries().is_empty());
+
+        // storing again replaces the previous library
+        library.remove(0);
+        Palette::store(&path, &library).unwrap();
+        assert_eq!(1, Palette::load(&path).unwrap().len());
+    }
+
+    #[test]
+    fn it_loads_an_empty_library_without_a_file() {
+        let dir = TestDir::new("missing");
+        assert!(
+            Palette::load(&dir.0.join("missing.gvariant"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn it_keeps_unreadable_libraries() {
+        let dir = TestDir::new("unreadable");
+        std::fs::create_dir_all(&dir.0).unwrap();
+        let path = dir.0.join("palettes.gvariant");
+        std::fs::write(&path, "not a palette library").unwrap();
+        assert!(Palette::load(&path).is_err());
+
+        Palette::back_up(&path);
+        assert!(!path.exists());
+        assert_eq!(
+            "not a palette library",
+            std::fs::read_to_string(dir.0.join("palettes.gvariant.bak")).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_finds_an_unused_name() {
+        let library = gio::ListStore::new::<Palette>();
+        assert_eq!("Palette 1", Palette::unused_name(&library));
+        library.extend_from_slice(&[Palette::new("Palette 1"), Palette::new("Palette 3")]);
+        assert_eq!("Palette 2", Palette::unused_name(&library));
+        library.append(&Palette::new("Palette 2"));
+        assert_eq!("Palette 4", Palette::unused_name(&library));
+    }
+}
@@ -0,0 +1,59 @@
+use glib::Object;
+use glib::prelude::*;
+use glib::subclass::prelude::*;
+use gtk::glib;
+
+use crate::colors::color::Color;
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use glib::{
+        Properties,
+        subclass::{prelude::ObjectImpl, types::ObjectSubclass},
+    };
+
+    use super::*;
+
+    #[derive(Debug, Properties)]
+    #[properties(wrapper_type = super::PaletteColor)]
+    pub struct PaletteColor {
+        #[property(get, set)]
+        pub color: Cell<Color>,
+        /// Optional user defined label, empty if there is none.
+        #[property(get, set)]
+        pub label: RefCell<String>,
+    }
+
+    impl Default for PaletteColor {
+        fn default() -> Self {
+            Self {
+                color: Cell::new(Color::rgba(0, 0, 0, 255)),
+                label: Default::default(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PaletteColor {
+        const NAME: &'static str = "PaletteColor";
+        type Type = super::PaletteColor;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for PaletteColor {}
+}
+
+glib::wrapper! {
+    /// A color of a [`Palette`](super::palette::Palette), with an optional label.
+    pub struct PaletteColor(ObjectSubclass<imp::PaletteColor>);
+}
+
+impl PaletteColor {
+    pub fn new(color: Color, label: &str) -> Self {
+        Object::builder()
+            .property("color", color)
+            .property("label", label)
+            .build()
+    }
+}
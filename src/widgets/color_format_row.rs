@@ -9,7 +9,11 @@ use gtk::{glib, prelude::ObjectExt};
 use crate::colors::Notation;
 use crate::colors::color::Color;
 use crate::colors::color_names::ColorNameSources;
+use crate::colors::gamut::GamutMapping;
+use crate::colors::illuminant::ReferenceWhite;
 use crate::colors::position::AlphaPosition;
+use crate::colors::template::Template;
+use crate::model::history::ColorSource;
 
 mod imp {
     use std::cell::{Cell, RefCell};
@@ -37,6 +41,8 @@ mod imp {
         pub color: RefCell<String>,
         #[property(construct_only, get, builder(colors::Notation::default()))]
         pub color_format: Cell<colors::Notation>,
+        /// User defined format, which is used instead of the `color_format`.
+        pub template: RefCell<Option<Template>>,
     }
 
     impl Default for ColorFormatRow {
@@ -48,6 +54,7 @@ mod imp {
                 tooltip: RefCell::default(),
                 color: RefCell::default(),
                 color_format: Cell::default(),
+                template: RefCell::default(),
             }
         }
     }
@@ -83,7 +90,17 @@ mod imp {
                     let name_flags =
                         ColorNameSources::from_bits(widget.settings.uint("name-sources-flag"))
                             .unwrap_or(ColorNameSources::empty());
-                    let Ok(color) = obj.color_format().parse(text.as_str(), name_flags) else {
+                    let parsed = match &*widget.template.borrow() {
+                        Some(template) => {
+                            let (_, reference_white) = Notation::parse_settings();
+                            template.parse(text.as_str(), reference_white)
+                        }
+                        None => obj.color_format().parse(text.as_str(), name_flags),
+                    };
+                    // fall back to detecting the notation, so colors can be pasted into any row
+                    let Ok(color) = parsed.or_else(|_| {
+                        Notation::detect(text.as_str(), name_flags).map(|(_, color)| color)
+                    }) else {
                         tracing::debug!("Failed to parse color: {}", text);
                         obj.show_error();
                         return;
@@ -91,8 +108,11 @@ mod imp {
                     obj.display_color(color);
                     obj.show_success();
 
-                    obj.activate_action("win.set-color", Some(&color.hex().to_variant()))
-                        .expect("Failed to set color");
+                    obj.activate_action(
+                        "win.set-exact-color",
+                        Some(&(color, ColorSource::Typed).to_variant()),
+                    )
+                    .expect("Failed to set color");
                 }
             ));
 
@@ -129,13 +149,32 @@ impl ColorFormatRow {
             .build()
     }
 
+    /// Creates a row showing colors in the user defined format.
+    pub fn with_template(template: Template) -> Self {
+        let row = glib::Object::new::<Self>();
+        row.imp().template.replace(Some(template));
+        row
+    }
+
     /// Updates the displayed color to the given color.
     ///
     /// The displayed color format is determined by the `color_format` of
-    /// the widget.
+    /// the widget. If the format is based on sRGB, colors outside of its gamut
+    /// are mapped into it, which is indicated by a warning.
     pub fn display_color(&self, color: Color) {
-        let alpha_position = AlphaPosition::from(self.imp().settings.int("alpha-position") as u32);
+        let gamut_mapping = GamutMapping::from(self.imp().settings.int("gamut-mapping") as u32);
         let precision = self.imp().settings.uint("precision-digits") as usize;
+        let reference_white = ReferenceWhite::from_settings(&self.imp().settings);
+
+        if let Some(template) = &*self.imp().template.borrow() {
+            let out_of_gamut = template.is_srgb() && !color.is_in_gamut();
+            self.set_color(template.format(color, precision, reference_white, gamut_mapping));
+            self.show_gamut_warning(out_of_gamut);
+            return;
+        }
+
+        let out_of_gamut = self.color_format().is_srgb() && !color.is_in_gamut();
+        let alpha_position = AlphaPosition::from(self.imp().settings.int("alpha-position") as u32);
         let rgb_decimal_notation = self.imp().settings.int("rgb-notation") == 1;
         let name_sources =
             ColorNameSources::from_bits(self.imp().settings.uint("name-sources-flag"))
@@ -146,8 +185,27 @@ impl ColorFormatRow {
             rgb_decimal_notation,
             precision,
             name_sources,
+            reference_white,
+            gamut_mapping,
         );
         self.set_color(color);
+        self.show_gamut_warning(out_of_gamut);
+    }
+
+    /// Shows or hides a warning icon in the entry, indicating that the color
+    /// is out of the sRGB gamut and has been mapped into it.
+    fn show_gamut_warning(&self, out_of_gamut: bool) {
+        let entry = &self.imp().entry;
+        if out_of_gamut {
+            entry.set_secondary_icon_name(Some("dialog-warning-symbolic"));
+            entry.set_secondary_icon_tooltip_text(Some(&gettext(
+                "Color is out of the sRGB gamut and has been mapped into it",
+            )));
+            entry.add_css_class("warning");
+        } else {
+            entry.set_secondary_icon_name(None);
+            entry.remove_css_class("warning");
+        }
     }
 
     /// Switches the button next to the entry.
@@ -164,7 +222,11 @@ impl ColorFormatRow {
         } else {
             button.set_icon_name("edit-copy-symbolic");
             button.remove_css_class("suggested-action");
-            self.set_tooltip(self.color_format().display_copy_string());
+            let tooltip = match &*self.imp().template.borrow() {
+                Some(template) => gettext("Copy {}").replace("{}", template.name()),
+                None => self.color_format().display_copy_string(),
+            };
+            self.set_tooltip(tooltip);
         }
     }
 
@@ -0,0 +1,285 @@
+use std::str::FromStr;
+
+use gettextrs::gettext;
+use glib::translate::IntoGlib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use crate::colors::Notation;
+use crate::colors::color::Color;
+use crate::colors::color_names::{self, ColorNameSources};
+use crate::colors::gamut::GamutMapping;
+use crate::colors::illuminant::ReferenceWhite;
+use crate::colors::palette_file;
+use crate::colors::position::AlphaPosition;
+use crate::colors::ramp::{self, RampKind, RampSpace};
+use crate::colors::template::CUSTOM_PREFIX;
+use crate::config;
+use crate::widgets::color_swatch::ColorSwatch;
+
+/// Target of the copy action, which copies the ramp as CSS variables.
+const CSS_VARIABLES: &str = "css";
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use super::*;
+
+    #[derive(Debug, gtk::CompositeTemplate)]
+    #[template(resource = "/com/github/finefindus/eyedropper/ui/color-ramp.ui")]
+    pub struct ColorRamp {
+        pub(super) settings: gio::Settings,
+        pub(super) color: Cell<Option<Color>>,
+        pub(super) colors: RefCell<Vec<Color>>,
+        #[template_child]
+        pub(super) kind_dropdown: TemplateChild<gtk::DropDown>,
+        #[template_child]
+        pub(super) space_dropdown: TemplateChild<gtk::DropDown>,
+        #[template_child]
+        pub(super) steps_button: TemplateChild<gtk::SpinButton>,
+        #[template_child]
+        pub(super) copy_button: TemplateChild<gtk::MenuButton>,
+        #[template_child]
+        pub(super) swatch_box: TemplateChild<gtk::Box>,
+    }
+
+    impl Default for ColorRamp {
+        fn default() -> Self {
+            Self {
+                settings: gio::Settings::new(config::APP_ID),
+                color: Cell::new(None),
+                colors: Default::default(),
+                kind_dropdown: TemplateChild::default(),
+                space_dropdown: TemplateChild::default(),
+                steps_button: TemplateChild::default(),
+                copy_button: TemplateChild::default(),
+                swatch_box: TemplateChild::default(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ColorRamp {
+        const NAME: &'static str = "ColorRamp";
+        type ParentType = gtk::Box;
+        type Type = super::ColorRamp;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+            klass.install_action(
+                "ramp.copy",
+                Some(glib::VariantTy::STRING),
+                |ramp, _, var| {
+                    if let Some(target) = var.and_then(|v| v.get::<String>()) {
+                        ramp.copy(&target);
+                    }
+                },
+            );
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ColorRamp {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.settings
+                .bind("ramp-kind", &*self.kind_dropdown, "selected")
+                .build();
+
+            self.settings
+                .bind("ramp-color-space", &*self.space_dropdown, "selected")
+                .build();
+
+            self.settings
+                .bind("ramp-steps", &*self.steps_button, "value")
+                .build();
+
+            // custom formats can be added to the format rows at any time
+            self.settings.connect_changed(
+                Some("format-order"),
+                glib::clone!(
+                    #[weak(rename_to = ramp)]
+                    self,
+                    move |_, _| ramp.update_menu()
+                ),
+            );
+            self.update_menu();
+        }
+
+        fn dispose(&self) {
+            self.dispose_template();
+        }
+    }
+
+    impl WidgetImpl for ColorRamp {}
+    impl BoxImpl for ColorRamp {}
+
+    #[gtk::template_callbacks]
+    impl ColorRamp {
+        /// Generates the ramp from the current color and shows it as swatches.
+        #[template_callback]
+        pub(super) fn update_ramp(&self) {
+            let Some(color) = self.color.get() else {
+                return;
+            };
+
+            let colors = ramp::ramp(
+                color,
+                RampKind::from(self.kind_dropdown.selected()),
+                RampSpace::from(self.space_dropdown.selected()),
+                self.steps_button.value() as usize,
+            );
+
+            while let Some(child) = self.swatch_box.first_child() {
+                self.swatch_box.remove(&child);
+            }
+            for color in &colors {
+                let swatch = ColorSwatch::new(*color);
+                // the swatches share the width of the format rows
+                swatch.set_size_request(8, 34);
+                self.swatch_box.append(&swatch);
+            }
+            self.colors.replace(colors);
+            self.update_menu();
+        }
+
+        /// Rebuilds the copy menu, offering all notations in the order of the format rows,
+        /// and the CSS variables named after the current color and ramp.
+        pub(super) fn update_menu(&self) {
+            let menu = gio::Menu::new();
+            let order: Vec<String> = self.settings.get("format-order");
+            for item in order {
+                if item.starts_with(CUSTOM_PREFIX) {
+                    continue;
+                }
+                let Ok(notation) = Notation::from_str(&item) else {
+                    continue;
+                };
+                if notation == Notation::Name {
+                    continue;
+                }
+                menu.append(
+                    Some(&notation.display_copy_string()),
+                    Some(&gio::Action::print_detailed_name(
+                        "ramp.copy",
+                        Some(&item.to_variant()),
+                    )),
+                );
+            }
+
+            let variables = self.obj().css_variables();
+            let label = match (variables.first(), variables.last()) {
+                (Some(first), Some(last)) => gettext("Copy as CSS Variables ({})")
+                    .replace("{}", &format!("--{} … --{}", first, last)),
+                _ => gettext("Copy as CSS Variables"),
+            };
+            menu.append(
+                Some(&label),
+                Some(&gio::Action::print_detailed_name(
+                    "ramp.copy",
+                    Some(&CSS_VARIABLES.to_variant()),
+                )),
+            );
+            self.copy_button.set_menu_model(Some(&menu));
+        }
+    }
+}
+
+glib::wrapper! {
+    /// Strip of tints, shades or tones generated from the current color.
+    pub struct ColorRamp(ObjectSubclass<imp::ColorRamp>)
+    @extends gtk::Widget, gtk::Box,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl ColorRamp {
+    /// Updates the ramp to be generated from the given color.
+    pub fn display_color(&self, color: Color) {
+        self.imp().color.set(Some(color));
+        self.imp().update_ramp();
+    }
+
+    /// Names of the CSS variables of the ramp, named after the base color and the
+    /// labels of the steps, e.g. `blue-500`.
+    fn css_variables(&self) -> Vec<String> {
+        let imp = self.imp();
+        let name_sources = ColorNameSources::from_bits(imp.settings.uint("name-sources-flag"))
+            .unwrap_or(ColorNameSources::empty());
+        let name = imp
+            .color
+            .get()
+            .and_then(|color| {
+                color_names::name(color, name_sources).or_else(|| {
+                    color_names::nearest_name(color, name_sources).map(|(name, _)| name)
+                })
+            })
+            .map(|name| palette_file::slug(&name))
+            .unwrap_or_else(|| "color".to_owned());
+
+        let kind = RampKind::from(imp.kind_dropdown.selected());
+        ramp::step_labels(kind, imp.colors.borrow().len())
+            .iter()
+            .map(|label| format!("{}-{}", name, label))
+            .collect()
+    }
+
+    /// Copies the whole ramp to the clipboard, either in the notation with the given
+    /// identifier or as CSS variables.
+    fn copy(&self, target: &str) {
+        let imp = self.imp();
+        let colors = imp.colors.borrow();
+        let name_sources = ColorNameSources::from_bits(imp.settings.uint("name-sources-flag"))
+            .unwrap_or(ColorNameSources::empty());
+        let gamut_mapping = GamutMapping::from(imp.settings.int("gamut-mapping") as u32);
+
+        let text = if target == CSS_VARIABLES {
+            let variables = self
+                .css_variables()
+                .iter()
+                .zip(colors.iter())
+                .map(|(variable, color)| {
+                    format!(
+                        "  --{}: {};",
+                        variable,
+                        palette_file::css_value(*color, gamut_mapping)
+                    )
+                })
+                .collect::<Vec<String>>();
+            format!(":root {{\n{}\n}}", variables.join("\n"))
+        } else {
+            let Ok(notation) = Notation::from_str(target) else {
+                return;
+            };
+            let reference_white = ReferenceWhite::from_settings(&imp.settings);
+            colors
+                .iter()
+                .map(|color| {
+                    notation.as_str(
+                        *color,
+                        AlphaPosition::from(imp.settings.int("alpha-position") as u32),
+                        imp.settings.int("rgb-notation") == 1,
+                        imp.settings.uint("precision-digits") as usize,
+                        name_sources,
+                        reference_white,
+                        gamut_mapping,
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join("\n")
+        };
+
+        tracing::debug!("Copied ramp: {text}");
+        self.clipboard().set_text(&text);
+        self.activate_action(
+            "win.show-toast",
+            Some(&(gettext("Copied ramp"), adw::ToastPriority::High.into_glib()).to_variant()),
+        )
+        .expect("Failed to show toast");
+    }
+}
@@ -0,0 +1,106 @@
+use glib::Object;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use crate::colors::{
+    Notation, color::Color, color_names::ColorNameSources, gamut::GamutMapping,
+    illuminant::ReferenceWhite, position::AlphaPosition,
+};
+use crate::config;
+
+mod imp {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[derive(Debug, glib::Properties)]
+    #[properties(wrapper_type = super::ColorSwatch)]
+    pub struct ColorSwatch {
+        #[property(get, set = Self::set_color)]
+        color: Cell<Color>,
+        settings: gio::Settings,
+    }
+
+    impl Default for ColorSwatch {
+        fn default() -> Self {
+            Self {
+                color: Cell::new(Color::default()),
+                settings: gio::Settings::new(config::APP_ID),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ColorSwatch {
+        const NAME: &'static str = "ColorSwatch";
+        type ParentType = gtk::Button;
+        type Type = super::ColorSwatch;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for ColorSwatch {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.set_size_request(36, 34);
+            obj.add_css_class("flat");
+            obj.add_css_class("shadow");
+            obj.set_action_name(Some("win.set-color"));
+        }
+    }
+
+    impl WidgetImpl for ColorSwatch {
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            let widget = self.obj();
+            let rect =
+                gtk::graphene::Rect::new(0.0, 0.0, widget.width() as f32, widget.height() as f32);
+            snapshot.push_rounded_clip(&gtk::gsk::RoundedRect::from_rect(rect, 6.0));
+            snapshot.append_color(&self.color.get().into(), &rect);
+            snapshot.pop();
+            // draw the hover and focus indicators of the flat button on top
+            self.parent_snapshot(snapshot);
+        }
+    }
+
+    impl ButtonImpl for ColorSwatch {}
+
+    impl ColorSwatch {
+        fn set_color(&self, color: Color) {
+            self.color.set(color);
+
+            let gamut_mapping = GamutMapping::from(self.settings.int("gamut-mapping") as u32);
+            let tooltip = if color.alpha != 1.0 {
+                gamut_mapping.apply(color).hex()
+            } else {
+                Notation::Hex.as_str(
+                    color,
+                    AlphaPosition::None,
+                    false,
+                    2,
+                    ColorNameSources::empty(),
+                    ReferenceWhite::default(),
+                    gamut_mapping,
+                )
+            };
+
+            let obj = self.obj();
+            obj.set_tooltip_text(Some(&tooltip));
+            obj.set_action_target_value(Some(&color.to_variant()));
+            obj.queue_draw();
+        }
+    }
+}
+
+glib::wrapper! {
+    /// Button showing a single color, which sets it as the current color when clicked.
+    pub struct ColorSwatch(ObjectSubclass<imp::ColorSwatch>)
+    @extends gtk::Widget, gtk::Button,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Actionable;
+}
+
+impl ColorSwatch {
+    pub fn new(color: Color) -> Self {
+        Object::builder().property("color", color).build()
+    }
+}
@@ -0,0 +1,209 @@
+use adw::prelude::ActionRowExt;
+use gettextrs::gettext;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gdk, gio, glib};
+
+use crate::colors::color::Color;
+use crate::colors::contrast::{Contrast, TextSize, WcagLevel};
+use crate::colors::gamut::GamutMapping;
+use crate::config;
+
+mod imp {
+    use std::cell::Cell;
+
+    use adw::subclass::dialog::AdwDialogImpl;
+
+    use super::*;
+
+    #[derive(Debug, gtk::CompositeTemplate)]
+    #[template(resource = "/com/github/finefindus/eyedropper/ui/contrast-dialog.ui")]
+    pub struct ContrastDialog {
+        pub(super) settings: gio::Settings,
+        #[template_child]
+        pub(super) text_button: TemplateChild<gtk::ColorDialogButton>,
+        #[template_child]
+        pub(super) background_button: TemplateChild<gtk::ColorDialogButton>,
+        #[template_child]
+        pub(super) ratio_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(super) aa_normal_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(super) aa_large_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(super) aaa_normal_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(super) aaa_large_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(super) lightness_contrast_row: TemplateChild<adw::ActionRow>,
+        pub(super) css_provider: gtk::CssProvider,
+        /// The exact text and background colors, whose values are rounded in the color buttons.
+        pub(super) exact_colors: Cell<(Option<Color>, Option<Color>)>,
+    }
+
+    impl Default for ContrastDialog {
+        fn default() -> Self {
+            Self {
+                settings: gio::Settings::new(config::APP_ID),
+                text_button: TemplateChild::default(),
+                background_button: TemplateChild::default(),
+                ratio_row: TemplateChild::default(),
+                aa_normal_row: TemplateChild::default(),
+                aa_large_row: TemplateChild::default(),
+                aaa_normal_row: TemplateChild::default(),
+                aaa_large_row: TemplateChild::default(),
+                lightness_contrast_row: TemplateChild::default(),
+                css_provider: Default::default(),
+                exact_colors: Default::default(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ContrastDialog {
+        const NAME: &'static str = "ContrastDialog";
+        type Type = super::ContrastDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ContrastDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            // setup CSS provider to update the colors of the sample text
+            gtk::style_context_add_provider_for_display(
+                &self.obj().display(),
+                &self.css_provider,
+                gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+            );
+        }
+
+        fn dispose(&self) {
+            gtk::style_context_remove_provider_for_display(
+                &self.obj().display(),
+                &self.css_provider,
+            );
+            self.dispose_template();
+        }
+    }
+
+    impl WidgetImpl for ContrastDialog {}
+    impl AdwDialogImpl for ContrastDialog {}
+
+    #[gtk::template_callbacks]
+    impl ContrastDialog {
+        /// Returns both colors, preferring the exact colors if the buttons still show them.
+        fn colors(&self) -> (Color, Color) {
+            let exact = |exact: Option<Color>, button: &gtk::ColorDialogButton| {
+                let shown = Color::from(button.rgba());
+                exact
+                    .filter(|exact| Color::from(gdk::RGBA::from(*exact)) == shown)
+                    .unwrap_or(shown)
+            };
+            let (text, background) = self.exact_colors.get();
+            (
+                exact(text, &self.text_button),
+                exact(background, &self.background_button),
+            )
+        }
+
+        /// Updates the contrast results and the sample text, after one of the colors changed.
+        #[template_callback]
+        pub(super) fn update_contrast(&self) {
+            self.css_provider.load_from_string(&format!(
+                ".contrast-preview {{ color: {}; background-color: {}; }}",
+                self.text_button.rgba(),
+                self.background_button.rgba()
+            ));
+
+            // the contrast is measured on the screen, which only shows sRGB colors
+            let gamut_mapping = GamutMapping::from(self.settings.int("gamut-mapping") as u32);
+            let (text, background) = self.colors();
+            let contrast =
+                Contrast::new(gamut_mapping.apply(text), gamut_mapping.apply(background));
+            self.ratio_row.set_subtitle(&format!(
+                "{:.2}:1",
+                (contrast.ratio * 100.0).floor() / 100.0
+            ));
+            self.lightness_contrast_row
+                .set_subtitle(&format!("{:.1}", contrast.lightness_contrast));
+
+            for (row, level, size) in [
+                (&self.aa_normal_row, WcagLevel::Aa, TextSize::Normal),
+                (&self.aa_large_row, WcagLevel::Aa, TextSize::Large),
+                (&self.aaa_normal_row, WcagLevel::Aaa, TextSize::Normal),
+                (&self.aaa_large_row, WcagLevel::Aaa, TextSize::Large),
+            ] {
+                let passes = contrast.passes(level, size);
+                row.set_subtitle(&if passes {
+                    gettext("Pass")
+                } else {
+                    gettext("Fail")
+                });
+                if passes {
+                    row.remove_css_class("error");
+                    row.add_css_class("success");
+                } else {
+                    row.remove_css_class("success");
+                    row.add_css_class("error");
+                }
+            }
+        }
+
+        /// Swaps the text and background color.
+        #[template_callback]
+        fn swap_colors(&self) {
+            let (text, background) = self.exact_colors.get();
+            self.exact_colors.set((background, text));
+            let text = self.text_button.rgba();
+            self.text_button.set_rgba(&self.background_button.rgba());
+            self.background_button.set_rgba(&text);
+        }
+
+        /// Pick the background color from the desktop using [ashpd].
+        #[template_callback]
+        async fn pick_background(&self) {
+            let root = self.obj().root().expect("Failed to get dialog root");
+            let identifier = ashpd::WindowIdentifier::from_native(&root).await;
+            let request = ashpd::desktop::screenshot::ColorRequest::default()
+                .identifier(identifier)
+                .send()
+                .await;
+
+            match request.and_then(|req| req.response()) {
+                Ok(color) => self.background_button.set_rgba(&gdk::RGBA::from(color)),
+                Err(err) => tracing::error!("Failed to pick background color: {}", err),
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct ContrastDialog(ObjectSubclass<imp::ContrastDialog>)
+    @extends gtk::Widget, adw::Dialog,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl ContrastDialog {
+    /// Create a new dialog, comparing the text color on the background color.
+    pub fn new(text: Color, background: Color) -> Self {
+        let dialog: Self = glib::Object::new();
+        dialog
+            .imp()
+            .exact_colors
+            .set((Some(text), Some(background)));
+        dialog.imp().text_button.set_rgba(&text.into());
+        dialog.imp().background_button.set_rgba(&background.into());
+        dialog.imp().update_contrast();
+        dialog
+    }
+}
@@ -0,0 +1,218 @@
+use adw::prelude::*;
+use gettextrs::gettext;
+use gtk::subclass::prelude::*;
+use gtk::{gdk, gio, glib};
+
+use crate::colors::Notation;
+use crate::colors::color::Color;
+use crate::colors::difference::{Difference, Metric};
+use crate::config;
+
+mod imp {
+    use std::cell::Cell;
+
+    use adw::subclass::dialog::AdwDialogImpl;
+
+    use super::*;
+
+    #[derive(Debug, gtk::CompositeTemplate)]
+    #[template(resource = "/com/github/finefindus/eyedropper/ui/difference-dialog.ui")]
+    pub struct DifferenceDialog {
+        pub(super) settings: gio::Settings,
+        #[template_child]
+        pub(super) reference_button: TemplateChild<gtk::ColorDialogButton>,
+        #[template_child]
+        pub(super) sample_button: TemplateChild<gtk::ColorDialogButton>,
+        #[template_child]
+        pub(super) metric_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(super) tolerance_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(super) result_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(super) cie76_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(super) cie94_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(super) ciede2000_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(super) cmc_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(super) oklab_row: TemplateChild<adw::ActionRow>,
+        pub(super) css_provider: gtk::CssProvider,
+        /// The exact reference and sample colors, whose values are rounded in the color buttons.
+        pub(super) exact_colors: Cell<(Option<Color>, Option<Color>)>,
+    }
+
+    impl Default for DifferenceDialog {
+        fn default() -> Self {
+            Self {
+                settings: gio::Settings::new(config::APP_ID),
+                reference_button: TemplateChild::default(),
+                sample_button: TemplateChild::default(),
+                metric_row: TemplateChild::default(),
+                tolerance_row: TemplateChild::default(),
+                result_row: TemplateChild::default(),
+                cie76_row: TemplateChild::default(),
+                cie94_row: TemplateChild::default(),
+                ciede2000_row: TemplateChild::default(),
+                cmc_row: TemplateChild::default(),
+                oklab_row: TemplateChild::default(),
+                css_provider: Default::default(),
+                exact_colors: Default::default(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for DifferenceDialog {
+        const NAME: &'static str = "DifferenceDialog";
+        type Type = super::DifferenceDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for DifferenceDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.settings
+                .bind("difference-metric", &*self.metric_row, "selected")
+                .build();
+
+            self.settings
+                .bind("difference-tolerance", &*self.tolerance_row, "value")
+                .build();
+
+            // setup CSS provider to update the colors of the preview
+            gtk::style_context_add_provider_for_display(
+                &self.obj().display(),
+                &self.css_provider,
+                gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+            );
+        }
+
+        fn dispose(&self) {
+            gtk::style_context_remove_provider_for_display(
+                &self.obj().display(),
+                &self.css_provider,
+            );
+            self.dispose_template();
+        }
+    }
+
+    impl WidgetImpl for DifferenceDialog {}
+    impl AdwDialogImpl for DifferenceDialog {}
+
+    #[gtk::template_callbacks]
+    impl DifferenceDialog {
+        /// Returns both colors, preferring the exact colors if the buttons still show them.
+        fn colors(&self) -> (Color, Color) {
+            let exact = |exact: Option<Color>, button: &gtk::ColorDialogButton| {
+                let shown = Color::from(button.rgba());
+                exact
+                    .filter(|exact| Color::from(gdk::RGBA::from(*exact)) == shown)
+                    .unwrap_or(shown)
+            };
+            let (reference, sample) = self.exact_colors.get();
+            (
+                exact(reference, &self.reference_button),
+                exact(sample, &self.sample_button),
+            )
+        }
+
+        /// Updates the differences and the tolerance result, after one of the colors changed.
+        #[template_callback]
+        pub(super) fn update_difference(&self) {
+            self.css_provider.load_from_string(&format!(
+                ".difference-reference {{ background-color: {}; }} .difference-sample {{ background-color: {}; }}",
+                self.reference_button.rgba(),
+                self.sample_button.rgba()
+            ));
+
+            let (reference, sample) = self.colors();
+            let (_, reference_white) = Notation::parse_settings();
+            let difference = Difference::new(reference, sample, reference_white);
+
+            self.cie76_row
+                .set_subtitle(&format!("{:.2}", difference.cie76));
+            self.cie94_row
+                .set_subtitle(&format!("{:.2}", difference.cie94));
+            self.ciede2000_row
+                .set_subtitle(&format!("{:.2}", difference.ciede2000));
+            self.cmc_row.set_subtitle(&format!("{:.2}", difference.cmc));
+            self.oklab_row
+                .set_subtitle(&format!("{:.4}", difference.oklab));
+
+            let metric = Metric::from(self.metric_row.selected());
+            let passes = difference.get(metric) <= self.tolerance_row.value() as f32;
+            self.result_row.set_subtitle(&if passes {
+                gettext("Within Tolerance")
+            } else {
+                gettext("Out of Tolerance")
+            });
+            if passes {
+                self.result_row.remove_css_class("error");
+                self.result_row.add_css_class("success");
+            } else {
+                self.result_row.remove_css_class("success");
+                self.result_row.add_css_class("error");
+            }
+        }
+
+        /// Swaps the reference and sample color.
+        #[template_callback]
+        fn swap_colors(&self) {
+            let (reference, sample) = self.exact_colors.get();
+            self.exact_colors.set((sample, reference));
+            let reference = self.reference_button.rgba();
+            self.reference_button.set_rgba(&self.sample_button.rgba());
+            self.sample_button.set_rgba(&reference);
+        }
+
+        /// Pick the sample color from the desktop using [ashpd].
+        #[template_callback]
+        async fn pick_sample(&self) {
+            let root = self.obj().root().expect("Failed to get dialog root");
+            let identifier = ashpd::WindowIdentifier::from_native(&root).await;
+            let request = ashpd::desktop::screenshot::ColorRequest::default()
+                .identifier(identifier)
+                .send()
+                .await;
+
+            match request.and_then(|req| req.response()) {
+                Ok(color) => self.sample_button.set_rgba(&gdk::RGBA::from(color)),
+                Err(err) => tracing::error!("Failed to pick sample color: {}", err),
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct DifferenceDialog(ObjectSubclass<imp::DifferenceDialog>)
+    @extends gtk::Widget, adw::Dialog,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl DifferenceDialog {
+    /// Create a new dialog, comparing the sample to the reference color.
+    pub fn new(reference: Color, sample: Color) -> Self {
+        let dialog: Self = glib::Object::new();
+        dialog
+            .imp()
+            .exact_colors
+            .set((Some(reference), Some(sample)));
+        dialog.imp().reference_button.set_rgba(&reference.into());
+        dialog.imp().sample_button.set_rgba(&sample.into());
+        dialog.imp().update_difference();
+        dialog
+    }
+}
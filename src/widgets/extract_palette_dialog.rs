@@ -0,0 +1,163 @@
+use adw::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gdk, gio, glib};
+
+use crate::colors::Notation;
+use crate::colors::color::Color;
+use crate::colors::color_names::ColorNameSources;
+use crate::colors::extract::{Swatch, extract_palette};
+use crate::colors::gamut::GamutMapping;
+use crate::colors::illuminant::ReferenceWhite;
+use crate::colors::position::AlphaPosition;
+use crate::colors::sample::Pixels;
+use crate::config;
+use crate::widgets::color_swatch::ColorSwatch;
+
+/// Maximum number of pixels used for extracting the palette, larger images are subsampled.
+const MAX_SAMPLES: usize = 1 << 16;
+
+mod imp {
+    use std::cell::RefCell;
+
+    use adw::subclass::dialog::AdwDialogImpl;
+
+    use super::*;
+
+    #[derive(Debug, gtk::CompositeTemplate)]
+    #[template(resource = "/com/github/finefindus/eyedropper/ui/extract-palette-dialog.ui")]
+    pub struct ExtractPaletteDialog {
+        pub(super) settings: gio::Settings,
+        #[template_child]
+        pub(super) picture: TemplateChild<gtk::Picture>,
+        #[template_child]
+        pub(super) count_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(super) palette_list: TemplateChild<gtk::ListBox>,
+        /// The sampled colors of the image.
+        pub(super) colors: RefCell<Vec<Color>>,
+        pub(super) palette: RefCell<Vec<Swatch>>,
+    }
+
+    impl Default for ExtractPaletteDialog {
+        fn default() -> Self {
+            Self {
+                settings: gio::Settings::new(config::APP_ID),
+                picture: TemplateChild::default(),
+                count_row: TemplateChild::default(),
+                palette_list: TemplateChild::default(),
+                colors: Default::default(),
+                palette: Default::default(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ExtractPaletteDialog {
+        const NAME: &'static str = "ExtractPaletteDialog";
+        type Type = super::ExtractPaletteDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ExtractPaletteDialog {}
+    impl WidgetImpl for ExtractPaletteDialog {}
+    impl AdwDialogImpl for ExtractPaletteDialog {}
+
+    #[gtk::template_callbacks]
+    impl ExtractPaletteDialog {
+        fn gamut_mapping(&self) -> GamutMapping {
+            GamutMapping::from(self.settings.int("gamut-mapping") as u32)
+        }
+
+        /// Formats the color as hex code with the settings of the user, the same way it is
+        /// shown once added to the history.
+        fn hex(&self, color: Color) -> String {
+            Notation::Hex.as_str(
+                color,
+                AlphaPosition::from(self.settings.int("alpha-position") as u32),
+                self.settings.int("rgb-notation") == 1,
+                self.settings.uint("precision-digits") as usize,
+                ColorNameSources::from_bits(self.settings.uint("name-sources-flag"))
+                    .unwrap_or(ColorNameSources::empty()),
+                ReferenceWhite::from_settings(&self.settings),
+                self.gamut_mapping(),
+            )
+        }
+
+        /// Extracts the palette and shows each color with its coverage.
+        #[template_callback]
+        pub(super) fn update_palette(&self) {
+            let palette = extract_palette(
+                self.colors.borrow().iter().copied(),
+                self.count_row.value() as usize,
+            );
+
+            self.palette_list.remove_all();
+            for swatch in &palette {
+                let color = self.gamut_mapping().apply(swatch.color);
+                let row = adw::ActionRow::builder()
+                    .title(self.hex(color))
+                    .title_selectable(true)
+                    .subtitle(format!("{:.1} %", swatch.coverage * 100.0))
+                    .build();
+                let color_swatch = ColorSwatch::new(color);
+                color_swatch.set_valign(gtk::Align::Center);
+                row.add_prefix(&color_swatch);
+                self.palette_list.append(&row);
+            }
+            self.palette.replace(palette);
+        }
+
+        /// Adds the extracted palette to the history.
+        #[template_callback]
+        fn add_to_history(&self) {
+            let gamut_mapping = self.gamut_mapping();
+            let colors = self
+                .palette
+                .borrow()
+                .iter()
+                .map(|swatch| gamut_mapping.apply(swatch.color))
+                .collect::<Vec<Color>>();
+            let obj = self.obj();
+            obj.activate_action("win.add-colors", Some(&colors.to_variant()))
+                .expect("Failed to call win.add-colors action");
+            obj.close();
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct ExtractPaletteDialog(ObjectSubclass<imp::ExtractPaletteDialog>)
+    @extends gtk::Widget, adw::Dialog,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl ExtractPaletteDialog {
+    /// Create a new dialog, extracting the palette of the image.
+    pub fn new(texture: &gdk::Texture) -> Self {
+        let dialog: Self = glib::Object::new();
+        let imp = dialog.imp();
+        imp.picture.set_paintable(Some(texture));
+
+        let mut downloader = gdk::TextureDownloader::new(texture);
+        downloader.set_format(gdk::MemoryFormat::R8g8b8a8);
+        let (bytes, stride) = downloader.download_bytes();
+        let (width, height) = (texture.width() as usize, texture.height() as usize);
+        if let Some(pixels) = Pixels::new(&bytes, width, height, stride) {
+            let step = (width * height).div_ceil(MAX_SAMPLES).max(1);
+            imp.colors
+                .replace(pixels.region(0, 0, width, height).step_by(step).collect());
+        }
+
+        imp.update_palette();
+        dialog
+    }
+}
@@ -0,0 +1,253 @@
+use adw::prelude::*;
+use gettextrs::gettext;
+use glib::translate::IntoGlib;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use crate::colors::color::Color;
+use crate::colors::gamut::GamutMapping;
+use crate::colors::gradient::{Gradient, GradientFormat};
+use crate::colors::mix::{HueInterpolation, MixSpace};
+use crate::config;
+use crate::widgets::color_swatch::ColorSwatch;
+
+mod imp {
+    use std::cell::RefCell;
+
+    use adw::subclass::dialog::AdwDialogImpl;
+
+    use super::*;
+
+    #[derive(Debug, gtk::CompositeTemplate)]
+    #[template(resource = "/com/github/finefindus/eyedropper/ui/gradient-dialog.ui")]
+    pub struct GradientDialog {
+        pub(super) settings: gio::Settings,
+        #[template_child]
+        pub(super) stops_box: TemplateChild<gtk::FlowBox>,
+        #[template_child]
+        pub(super) history_box: TemplateChild<gtk::FlowBox>,
+        #[template_child]
+        pub(super) space_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(super) hue_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(super) format_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(super) code_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(super) count_row: TemplateChild<adw::SpinRow>,
+        pub(super) css_provider: gtk::CssProvider,
+        pub(super) stops: RefCell<Vec<Color>>,
+    }
+
+    impl Default for GradientDialog {
+        fn default() -> Self {
+            Self {
+                settings: gio::Settings::new(config::APP_ID),
+                stops_box: TemplateChild::default(),
+                history_box: TemplateChild::default(),
+                space_row: TemplateChild::default(),
+                hue_row: TemplateChild::default(),
+                format_row: TemplateChild::default(),
+                code_row: TemplateChild::default(),
+                count_row: TemplateChild::default(),
+                css_provider: Default::default(),
+                stops: Default::default(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for GradientDialog {
+        const NAME: &'static str = "GradientDialog";
+        type Type = super::GradientDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for GradientDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.settings
+                .bind("gradient-color-space", &*self.space_row, "selected")
+                .build();
+
+            self.settings
+                .bind("gradient-hue-interpolation", &*self.hue_row, "selected")
+                .build();
+
+            self.settings
+                .bind("gradient-format", &*self.format_row, "selected")
+                .build();
+
+            // setup CSS provider to update the preview
+            gtk::style_context_add_provider_for_display(
+                &self.obj().display(),
+                &self.css_provider,
+                gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+            );
+        }
+
+        fn dispose(&self) {
+            gtk::style_context_remove_provider_for_display(
+                &self.obj().display(),
+                &self.css_provider,
+            );
+            self.dispose_template();
+        }
+    }
+
+    impl WidgetImpl for GradientDialog {}
+    impl AdwDialogImpl for GradientDialog {}
+
+    #[gtk::template_callbacks]
+    impl GradientDialog {
+        /// Returns the gradient through the stops, with the selected interpolation.
+        pub(super) fn gradient(&self) -> Gradient {
+            Gradient::new(
+                self.stops.borrow().clone(),
+                MixSpace::from(self.space_row.selected()),
+                HueInterpolation::from(self.hue_row.selected()),
+            )
+        }
+
+        fn gamut_mapping(&self) -> GamutMapping {
+            GamutMapping::from(self.settings.int("gamut-mapping") as u32)
+        }
+
+        /// Updates the preview and the exported code of the gradient.
+        #[template_callback]
+        pub(super) fn update_gradient(&self) {
+            self.hue_row
+                .set_sensitive(MixSpace::from(self.space_row.selected()).is_polar());
+
+            let gradient = self.gradient();
+            if gradient.stops().len() < 2 {
+                self.css_provider
+                    .load_from_string(".gradient-preview { background-image: none; }");
+                self.code_row
+                    .set_subtitle(&gettext("Add at least two stops"));
+                return;
+            }
+
+            self.css_provider.load_from_string(&format!(
+                ".gradient-preview {{ background-image: {}; }}",
+                gradient.preview_css(self.gamut_mapping())
+            ));
+            let format = GradientFormat::from(self.format_row.selected());
+            self.code_row
+                .set_subtitle(&gradient.export(format, self.gamut_mapping()));
+        }
+
+        /// Copies the exported gradient to the clipboard.
+        #[template_callback]
+        fn copy_gradient(&self) {
+            let gradient = self.gradient();
+            if gradient.stops().len() < 2 {
+                return;
+            }
+
+            let format = GradientFormat::from(self.format_row.selected());
+            let text = gradient.export(format, self.gamut_mapping());
+            tracing::debug!("Copied gradient: {text}");
+            let obj = self.obj();
+            obj.clipboard().set_text(&text);
+            obj.activate_action(
+                "win.show-toast",
+                Some(
+                    &(
+                        gettext("Copied gradient"),
+                        adw::ToastPriority::High.into_glib(),
+                    )
+                        .to_variant(),
+                ),
+            )
+            .expect("Failed to show toast");
+        }
+
+        /// Adds the sampled palette of the gradient to the history.
+        #[template_callback]
+        fn add_to_history(&self) {
+            let gamut_mapping = self.gamut_mapping();
+            let colors = self
+                .gradient()
+                .palette(self.count_row.value() as usize)
+                .into_iter()
+                .map(|color| gamut_mapping.apply(color))
+                .collect::<Vec<Color>>();
+            self.obj()
+                .activate_action("win.add-colors", Some(&colors.to_variant()))
+                .expect("Failed to call win.add-colors action");
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct GradientDialog(ObjectSubclass<imp::GradientDialog>)
+    @extends gtk::Widget, adw::Dialog,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl GradientDialog {
+    /// Create a new dialog, with the history colors as available stops.
+    ///
+    /// The first two colors of the history are used as initial stops.
+    pub fn new(history: &[Color]) -> Self {
+        let dialog: Self = glib::Object::new();
+        let imp = dialog.imp();
+
+        for color in history {
+            let swatch = Self::swatch(*color);
+            swatch.connect_clicked(glib::clone!(
+                #[weak]
+                dialog,
+                #[strong]
+                color,
+                move |_| {
+                    dialog.imp().stops.borrow_mut().push(color);
+                    dialog.update_stops();
+                }
+            ));
+            imp.history_box.append(&swatch);
+        }
+
+        imp.stops.replace(history.iter().take(2).copied().collect());
+        dialog.update_stops();
+        dialog
+    }
+
+    /// Creates a swatch of the color, which does not set the color when clicked.
+    fn swatch(color: Color) -> ColorSwatch {
+        let swatch = ColorSwatch::new(color);
+        swatch.set_action_name(None);
+        swatch
+    }
+
+    /// Shows the current stops and updates the gradient.
+    fn update_stops(&self) {
+        let imp = self.imp();
+        imp.stops_box.remove_all();
+        for (index, color) in imp.stops.borrow().iter().enumerate() {
+            let swatch = Self::swatch(*color);
+            swatch.connect_clicked(glib::clone!(
+                #[weak(rename_to = dialog)]
+                self,
+                move |_| {
+                    dialog.imp().stops.borrow_mut().remove(index);
+                    dialog.update_stops();
+                }
+            ));
+            imp.stops_box.append(&swatch);
+        }
+        imp.update_gradient();
+    }
+}
@@ -0,0 +1,129 @@
+use adw::prelude::ComboRowExt;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use crate::colors::color::Color;
+use crate::colors::harmony::{Harmony, HueSpace};
+use crate::config;
+use crate::widgets::color_swatch::ColorSwatch;
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use adw::subclass::dialog::AdwDialogImpl;
+
+    use super::*;
+
+    #[derive(Debug, gtk::CompositeTemplate)]
+    #[template(resource = "/com/github/finefindus/eyedropper/ui/harmony-dialog.ui")]
+    pub struct HarmonyDialog {
+        pub(super) settings: gio::Settings,
+        pub(super) color: Cell<Option<Color>>,
+        pub(super) colors: RefCell<Vec<Color>>,
+        #[template_child]
+        pub(super) harmony_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(super) space_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(super) swatch_box: TemplateChild<gtk::Box>,
+    }
+
+    impl Default for HarmonyDialog {
+        fn default() -> Self {
+            Self {
+                settings: gio::Settings::new(config::APP_ID),
+                color: Cell::new(None),
+                colors: Default::default(),
+                harmony_row: TemplateChild::default(),
+                space_row: TemplateChild::default(),
+                swatch_box: TemplateChild::default(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HarmonyDialog {
+        const NAME: &'static str = "HarmonyDialog";
+        type Type = super::HarmonyDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for HarmonyDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.settings
+                .bind("harmony", &*self.harmony_row, "selected")
+                .build();
+
+            self.settings
+                .bind("harmony-color-space", &*self.space_row, "selected")
+                .build();
+        }
+
+        fn dispose(&self) {
+            self.dispose_template();
+        }
+    }
+
+    impl WidgetImpl for HarmonyDialog {}
+    impl AdwDialogImpl for HarmonyDialog {}
+
+    #[gtk::template_callbacks]
+    impl HarmonyDialog {
+        /// Generates the harmony of the selected type and shows its colors as swatches.
+        #[template_callback]
+        pub(super) fn update_harmony(&self) {
+            let Some(color) = self.color.get() else {
+                return;
+            };
+
+            let harmony = Harmony::from(self.harmony_row.selected());
+            let space = HueSpace::from(self.space_row.selected());
+            let colors = harmony.colors(color, space);
+
+            while let Some(child) = self.swatch_box.first_child() {
+                self.swatch_box.remove(&child);
+            }
+            for color in &colors {
+                self.swatch_box.append(&ColorSwatch::new(*color));
+            }
+            self.colors.replace(colors);
+        }
+
+        /// Adds all colors of the harmony to the history.
+        #[template_callback]
+        fn add_to_history(&self) {
+            let colors = self.colors.borrow().clone();
+            self.obj()
+                .activate_action("win.add-colors", Some(&colors.to_variant()))
+                .expect("Failed to call win.add-colors action");
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct HarmonyDialog(ObjectSubclass<imp::HarmonyDialog>)
+    @extends gtk::Widget, adw::Dialog,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl HarmonyDialog {
+    /// Create a new dialog, showing the harmonies of the color.
+    pub fn new(color: Color) -> Self {
+        let dialog: Self = glib::Object::new();
+        dialog.imp().color.set(Some(color));
+        dialog.imp().update_harmony();
+        dialog
+    }
+}
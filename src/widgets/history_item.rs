@@ -3,13 +3,18 @@ use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 use gtk::{glib, prelude::ObjectExt};
 
+use crate::colors::vision::Simulation;
+use crate::model::history::HistoryObject;
+
 mod imp {
 
-    use std::cell::Cell;
+    use std::cell::{Cell, RefCell};
 
     use crate::colors::{
-        Notation, color::Color, color_names::ColorNameSources, position::AlphaPosition,
+        Notation, color::Color, color_names::ColorNameSources, gamut::GamutMapping,
+        illuminant::ReferenceWhite, position::AlphaPosition,
     };
+    use crate::model::history::ColorSource;
 
     use super::*;
 
@@ -18,10 +23,24 @@ mod imp {
     #[properties(wrapper_type = super::HistoryItem)]
     pub struct HistoryItem {
         #[property(get, set = Self::set_color)]
-        color: Cell<gtk::gdk::RGBA>,
+        color: Cell<Color>,
+        #[property(get, set, nullable)]
+        picked: RefCell<Option<glib::DateTime>>,
+        #[property(get, set, builder(ColorSource::default()))]
+        source: Cell<ColorSource>,
+        /// User defined label of the entry, editable in the popover.
+        ///
+        /// Not called `label`, which would shadow the label of the button. Edits are only
+        /// applied when the popover is closed, not for every typed character.
+        #[property(get, set)]
+        note: RefCell<String>,
+        /// Simulated color vision deficiency, which is applied when drawing the color.
+        pub(super) simulation: Cell<Option<Simulation>>,
         #[template_child]
         popover: TemplateChild<gtk::PopoverMenu>,
         #[template_child]
+        note_entry: TemplateChild<gtk::Entry>,
+        #[template_child]
         right_click_gesture: TemplateChild<gtk::GestureClick>,
         #[template_child]
         press_gesture: TemplateChild<gtk::GestureLongPress>,
@@ -30,8 +49,13 @@ mod imp {
     impl Default for HistoryItem {
         fn default() -> Self {
             Self {
-                color: Cell::new(gtk::gdk::RGBA::TRANSPARENT),
+                color: Cell::new(Color::default()),
+                picked: Default::default(),
+                source: Default::default(),
+                note: Default::default(),
+                simulation: Cell::new(None),
                 popover: TemplateChild::default(),
+                note_entry: TemplateChild::default(),
                 right_click_gesture: TemplateChild::default(),
                 press_gesture: TemplateChild::default(),
             }
@@ -48,18 +72,28 @@ mod imp {
             klass.bind_template();
             klass.bind_template_callbacks();
             klass.install_action("history.remove", None, |item, _, _value| {
-                item.activate_action(
-                    "win.remove-item",
-                    Some(&Color::from(item.color()).hex().to_variant()),
-                )
-                .expect("Failed to call win.set-color action");
+                item.activate_action("win.remove-item", Some(&item.color().to_variant()))
+                    .expect("Failed to call win.set-color action");
+            });
+            klass.install_action("history.contrast", None, |item, _, _value| {
+                item.activate_action("win.check-contrast", Some(&item.color().to_variant()))
+                    .expect("Failed to call win.check-contrast action");
+            });
+            klass.install_action("history.compare", None, |item, _, _value| {
+                item.activate_action("win.compare-colors", Some(&item.color().to_variant()))
+                    .expect("Failed to call win.compare-colors action");
+            });
+            klass.install_action("history.mix", None, |item, _, _value| {
+                item.activate_action("win.mix-colors", Some(&item.color().to_variant()))
+                    .expect("Failed to call win.mix-colors action");
+            });
+            klass.install_action("history.add-to-palette", None, |item, _, _value| {
+                item.activate_action("win.add-to-palette", Some(&item.color().to_variant()))
+                    .expect("Failed to call win.add-to-palette action");
             });
             klass.install_action("history.clicked", None, |item, _, _value| {
-                item.activate_action(
-                    "win.set-color",
-                    Some(&Color::from(item.color()).hex().to_variant()),
-                )
-                .expect("Failed to call win.set-color action");
+                item.activate_action("win.select-history-item", Some(&item.color().to_variant()))
+                    .expect("Failed to call win.select-history-item action");
             });
         }
 
@@ -81,7 +115,11 @@ mod imp {
             let rect = gtk::graphene::Rect::new(0.0, 0.0, 36.0, 34.0);
             snapshot.translate(&gtk::graphene::Point::new(-10.0, -5.0));
             snapshot.push_rounded_clip(&gtk::gsk::RoundedRect::from_rect(rect, 6.0));
-            snapshot.append_color(&self.color.get(), &rect);
+            let color = match self.simulation.get() {
+                Some(simulation) => simulation.apply(self.color.get()),
+                None => self.color.get(),
+            };
+            snapshot.append_color(&color.into(), &rect);
             snapshot.pop();
             // translate back so that the focus ring is centered correctly
             snapshot.translate(&gtk::graphene::Point::new(10.0, 5.0));
@@ -92,7 +130,7 @@ mod imp {
 
     #[gtk::template_callbacks]
     impl HistoryItem {
-        pub(super) fn set_color(&self, color: gtk::gdk::RGBA) {
+        pub(super) fn set_color(&self, color: Color) {
             self.color.set(color);
             self.obj().queue_draw();
         }
@@ -103,18 +141,63 @@ mod imp {
         }
 
         #[template_callback]
-        fn tooltip(&self, color: gtk::gdk::RGBA) -> String {
-            if color.alpha() != 1.0 {
-                Color::from(color).hex()
+        fn on_note_activate(&self) {
+            self.popover.popdown();
+        }
+
+        /// Applies the note edited in the popover.
+        #[template_callback]
+        fn on_popover_closed(&self) {
+            let note = self.note_entry.text().trim().to_owned();
+            if note != *self.note.borrow() {
+                self.obj().set_note(note);
+            }
+        }
+
+        #[template_callback]
+        fn tooltip(
+            &self,
+            color: Color,
+            note: String,
+            source: ColorSource,
+            picked: Option<glib::DateTime>,
+        ) -> String {
+            let hex = if color.alpha != 1.0 {
+                color.hex()
             } else {
                 Notation::Hex.as_str(
-                    color.into(),
+                    color,
                     AlphaPosition::None,
                     false,
                     2,
                     ColorNameSources::empty(),
+                    ReferenceWhite::default(),
+                    GamutMapping::default(),
                 )
-            }
+            };
+
+            [
+                Some(note).filter(|note| !note.is_empty()),
+                Some(hex),
+                Some(self.details(source, picked)).filter(|details| !details.is_empty()),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<String>>()
+            .join("\n")
+        }
+
+        /// Describes where and when the color was picked, empty if neither is known.
+        #[template_callback]
+        fn details(&self, source: ColorSource, picked: Option<glib::DateTime>) -> String {
+            let picked = picked
+                .and_then(|picked| picked.format("%x %R").ok())
+                .map(|picked| picked.to_string());
+            [source.description(), picked]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<String>>()
+                .join(", ")
         }
     }
 }
@@ -126,7 +209,33 @@ glib::wrapper! {
 }
 
 impl HistoryItem {
-    pub fn new(color: gtk::gdk::RGBA) -> Self {
-        Object::builder().property("color", color).build()
+    /// Creates a new item showing the history entry, as seen with the simulated color
+    /// vision deficiency.
+    ///
+    /// The item follows changes of when and where the color was picked. Editing the note
+    /// of the item also changes the label of the entry.
+    pub fn new(history_object: &HistoryObject, simulation: Option<Simulation>) -> Self {
+        let item: Self = Object::builder()
+            .property("color", history_object.color())
+            .build();
+        for property in ["picked", "source"] {
+            history_object
+                .bind_property(property, &item, property)
+                .sync_create()
+                .build();
+        }
+        history_object
+            .bind_property("label", &item, "note")
+            .bidirectional()
+            .sync_create()
+            .build();
+        item.imp().simulation.set(simulation);
+        item
+    }
+
+    /// Changes the simulated color vision deficiency, which is applied when drawing the color.
+    pub fn set_simulation(&self, simulation: Option<Simulation>) {
+        self.imp().simulation.set(simulation);
+        self.queue_draw();
     }
 }
@@ -0,0 +1,434 @@
+use adw::prelude::*;
+use gettextrs::gettext;
+use glib::translate::IntoGlib;
+use gtk::subclass::prelude::*;
+use gtk::{gdk, gio, glib};
+
+use crate::colors::color::Color;
+use crate::colors::gamut::GamutMapping;
+use crate::colors::sample::{Pixels, Region, RegionAverage, RegionShape, SampleSize};
+use crate::config;
+use crate::model::history::ColorSource;
+use crate::widgets::extract_palette_dialog::ExtractPaletteDialog;
+use crate::widgets::image_view::ImageView;
+
+/// Factor by which the zoom changes in each step.
+const ZOOM_STEP: f64 = 1.25;
+const MIN_ZOOM: f64 = 0.05;
+const MAX_ZOOM: f64 = 32.0;
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use adw::subclass::dialog::AdwDialogImpl;
+
+    use super::*;
+
+    #[derive(Debug, gtk::CompositeTemplate)]
+    #[template(resource = "/com/github/finefindus/eyedropper/ui/image-picker-dialog.ui")]
+    pub struct ImagePickerDialog {
+        pub(super) settings: gio::Settings,
+        #[template_child]
+        pub(super) toast_overlay: TemplateChild<adw::ToastOverlay>,
+        #[template_child]
+        pub(super) scrolled_window: TemplateChild<gtk::ScrolledWindow>,
+        #[template_child]
+        pub(super) image_view: TemplateChild<ImageView>,
+        #[template_child]
+        pub(super) hex_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub(super) size_dropdown: TemplateChild<gtk::DropDown>,
+        #[template_child]
+        pub(super) shape_dropdown: TemplateChild<gtk::DropDown>,
+        #[template_child]
+        pub(super) average_dropdown: TemplateChild<gtk::DropDown>,
+        pub(super) css_provider: gtk::CssProvider,
+        /// The downloaded pixels of the image, with their width, height and stride.
+        pub(super) pixels: RefCell<Option<(glib::Bytes, usize, usize, usize)>>,
+        /// The position in the image view, at which the current drag started.
+        pub(super) drag_start: Cell<Option<(f64, f64)>>,
+    }
+
+    impl Default for ImagePickerDialog {
+        fn default() -> Self {
+            Self {
+                settings: gio::Settings::new(config::APP_ID),
+                toast_overlay: TemplateChild::default(),
+                scrolled_window: TemplateChild::default(),
+                image_view: TemplateChild::default(),
+                hex_label: TemplateChild::default(),
+                size_dropdown: TemplateChild::default(),
+                shape_dropdown: TemplateChild::default(),
+                average_dropdown: TemplateChild::default(),
+                css_provider: Default::default(),
+                pixels: Default::default(),
+                drag_start: Default::default(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ImagePickerDialog {
+        const NAME: &'static str = "ImagePickerDialog";
+        type Type = super::ImagePickerDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+
+            klass.install_action_async("image.open", None, |dialog, _, _| async move {
+                dialog.choose_image().await;
+            });
+            klass.install_action("image.extract-palette", None, |dialog, _, _| {
+                if let Some(texture) = dialog.imp().image_view.texture() {
+                    ExtractPaletteDialog::new(&texture).present(Some(dialog));
+                }
+            });
+            klass.install_action("image.zoom-in", None, |dialog, _, _| {
+                dialog.set_zoom(dialog.imp().image_view.zoom() * ZOOM_STEP);
+            });
+            klass.install_action("image.zoom-out", None, |dialog, _, _| {
+                dialog.set_zoom(dialog.imp().image_view.zoom() / ZOOM_STEP);
+            });
+            klass.install_action("image.zoom-fit", None, |dialog, _, _| {
+                dialog.zoom_to_fit();
+            });
+            klass.add_binding_action(
+                gdk::Key::plus,
+                gdk::ModifierType::CONTROL_MASK,
+                "image.zoom-in",
+            );
+            klass.add_binding_action(
+                gdk::Key::minus,
+                gdk::ModifierType::CONTROL_MASK,
+                "image.zoom-out",
+            );
+            klass.add_binding_action(
+                gdk::Key::_0,
+                gdk::ModifierType::CONTROL_MASK,
+                "image.zoom-fit",
+            );
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ImagePickerDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.obj()
+                .action_set_enabled("image.extract-palette", false);
+
+            self.settings
+                .bind("image-sample-size", &*self.size_dropdown, "selected")
+                .build();
+
+            self.settings
+                .bind("region-shape", &*self.shape_dropdown, "selected")
+                .build();
+
+            self.settings
+                .bind("region-average", &*self.average_dropdown, "selected")
+                .build();
+
+            // replace the image with dropped files
+            let drop_target = gtk::DropTarget::new(gio::File::static_type(), gdk::DragAction::COPY);
+            drop_target.connect_drop(glib::clone!(
+                #[weak(rename_to = dialog)]
+                self.obj(),
+                #[upgrade_or]
+                false,
+                move |_, value, _, _| {
+                    let Ok(file) = value.get::<gio::File>() else {
+                        return false;
+                    };
+                    glib::spawn_future_local(async move {
+                        dialog.load_image(&file).await;
+                    });
+                    true
+                }
+            ));
+            self.obj().add_controller(drop_target);
+
+            // setup CSS provider to update the color of the preview
+            gtk::style_context_add_provider_for_display(
+                &self.obj().display(),
+                &self.css_provider,
+                gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+            );
+        }
+
+        fn dispose(&self) {
+            gtk::style_context_remove_provider_for_display(
+                &self.obj().display(),
+                &self.css_provider,
+            );
+            self.dispose_template();
+        }
+    }
+
+    impl WidgetImpl for ImagePickerDialog {}
+    impl AdwDialogImpl for ImagePickerDialog {}
+
+    #[gtk::template_callbacks]
+    impl ImagePickerDialog {
+        /// Samples the color of the image at the position in the image view.
+        ///
+        /// Depending on the selected size, the colors of the surrounding pixels are averaged.
+        pub(super) fn sample(&self, x: f64, y: f64) -> Option<Color> {
+            let (x, y) = self.image_view.pixel_at(x, y)?;
+            let pixels = self.pixels.borrow();
+            let (bytes, width, height, stride) = pixels.as_ref()?;
+            Pixels::new(bytes, *width, *height, *stride)?.sample(
+                x,
+                y,
+                SampleSize::from(self.size_dropdown.selected()),
+            )
+        }
+
+        /// Averages the colors of the region of the image.
+        pub(super) fn average(&self, region: Region) -> Option<Color> {
+            let pixels = self.pixels.borrow();
+            let (bytes, width, height, stride) = pixels.as_ref()?;
+            let pixels = Pixels::new(bytes, *width, *height, *stride)?;
+            RegionAverage::from(self.average_dropdown.selected()).apply(pixels.colors_in(region))
+        }
+
+        /// Shows the color in the preview and as hex code.
+        fn show_color(&self, color: Color) {
+            let gamut_mapping = GamutMapping::from(self.settings.int("gamut-mapping") as u32);
+            let rgba = gdk::RGBA::from(gamut_mapping.apply(color));
+            self.css_provider.load_from_string(&format!(
+                ".image-picker-preview {{ background-color: {}; }}",
+                rgba
+            ));
+            let mut hex = color.hex();
+            if color.alpha >= 1.0 {
+                hex.truncate(7);
+            }
+            self.hex_label.set_label(&hex);
+        }
+
+        /// Sets the color as the current color and closes the dialog.
+        fn pick(&self, color: Color) {
+            let obj = self.obj();
+            obj.activate_action(
+                "win.set-exact-color",
+                Some(&(color, ColorSource::Image).to_variant()),
+            )
+            .expect("Failed to call win.set-exact-color action");
+            obj.close();
+        }
+
+        /// The region between the start of the drag and the current position.
+        ///
+        /// Returns [`None`] if the drag does not span more than a single pixel.
+        fn drag_region(&self, offset_x: f64, offset_y: f64) -> Option<Region> {
+            let (x, y) = self.drag_start.get()?;
+            let start = self.image_view.pixel_at(x, y)?;
+            let end = self
+                .image_view
+                .clamped_pixel_at(x + offset_x, y + offset_y)?;
+            (start != end).then(|| {
+                Region::from_drag(
+                    RegionShape::from(self.shape_dropdown.selected()),
+                    start,
+                    end,
+                )
+            })
+        }
+
+        /// Shows the color below the cursor.
+        #[template_callback]
+        fn on_image_motion(&self, x: f64, y: f64) {
+            // while dragging, the average of the region is shown
+            if self.drag_start.get().is_some() {
+                return;
+            }
+            if let Some(color) = self.sample(x, y) {
+                self.show_color(color);
+            }
+        }
+
+        #[template_callback]
+        fn on_drag_begin(&self, x: f64, y: f64) {
+            self.drag_start.set(Some((x, y)));
+        }
+
+        /// Outlines the dragged region and shows its average color.
+        #[template_callback]
+        fn on_drag_update(&self, offset_x: f64, offset_y: f64) {
+            let region = self.drag_region(offset_x, offset_y);
+            self.image_view.set_selection(region);
+            if let Some(color) = region.and_then(|region| self.average(region)) {
+                self.show_color(color);
+            }
+        }
+
+        /// Sets the clicked color, or the average color of the dragged region, as the current
+        /// color.
+        #[template_callback]
+        fn on_drag_end(&self, offset_x: f64, offset_y: f64) {
+            let region = self.drag_region(offset_x, offset_y);
+            let Some((x, y)) = self.drag_start.take() else {
+                return;
+            };
+            self.image_view.set_selection(None);
+
+            let color = match region {
+                Some(region) => self.average(region),
+                None => self.sample(x + offset_x, y + offset_y),
+            };
+            if let Some(color) = color {
+                self.pick(color);
+            }
+        }
+
+        /// Zooms with the scroll wheel while the control key is pressed.
+        #[template_callback]
+        fn on_scroll(&self, _dx: f64, dy: f64, controller: &gtk::EventControllerScroll) -> bool {
+            if !controller
+                .current_event_state()
+                .contains(gdk::ModifierType::CONTROL_MASK)
+            {
+                return false;
+            }
+            let zoom = self.image_view.zoom() * ZOOM_STEP.powf(-dy);
+            self.obj().set_zoom(zoom);
+            true
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct ImagePickerDialog(ObjectSubclass<imp::ImagePickerDialog>)
+    @extends gtk::Widget, adw::Dialog,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl ImagePickerDialog {
+    /// Create a new dialog, without an image.
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    /// Lets the user choose an image file and loads it.
+    pub async fn choose_image(&self) {
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some(&gettext("Images")));
+        for mime_type in ["image/png", "image/jpeg", "image/webp"] {
+            filter.add_mime_type(mime_type);
+        }
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&filter);
+
+        let dialog = gtk::FileDialog::builder()
+            .title(gettext("Open Image"))
+            .filters(&filters)
+            .default_filter(&filter)
+            .modal(true)
+            .build();
+
+        let root = self.root().and_downcast::<gtk::Window>();
+        match dialog.open_future(root.as_ref()).await {
+            Ok(file) => self.load_image(&file).await,
+            Err(err) => {
+                tracing::debug!("Failed to choose image file: {}", err);
+                // there is nothing to pick from without an image
+                if self.imp().image_view.texture().is_none() {
+                    self.close();
+                }
+            }
+        }
+    }
+
+    /// Loads the image file and shows it, zoomed to fit into the dialog.
+    pub async fn load_image(&self, file: &gio::File) {
+        let (bytes, _etag) = match file.load_bytes_future().await {
+            Ok(result) => result,
+            Err(err) => {
+                tracing::error!("Failed to read image: {}", err);
+                self.show_error();
+                return;
+            }
+        };
+        let texture = match gdk::Texture::from_bytes(&bytes) {
+            Ok(texture) => texture,
+            Err(err) => {
+                tracing::error!("Failed to load image: {}", err);
+                self.show_error();
+                return;
+            }
+        };
+
+        let mut downloader = gdk::TextureDownloader::new(&texture);
+        downloader.set_format(gdk::MemoryFormat::R8g8b8a8);
+        let (pixels, stride) = downloader.download_bytes();
+
+        let imp = self.imp();
+        imp.pixels.replace(Some((
+            pixels,
+            texture.width() as usize,
+            texture.height() as usize,
+            stride,
+        )));
+        imp.image_view.set_texture(Some(&texture));
+        self.action_set_enabled("image.extract-palette", true);
+        self.zoom_to_fit();
+    }
+
+    /// Shows that the image could not be opened.
+    ///
+    /// The previous image is kept, without one the dialog is closed, as there is nothing to pick from.
+    fn show_error(&self) {
+        let message = gettext("Failed to open image");
+        let imp = self.imp();
+        if imp.image_view.texture().is_some() {
+            let toast = adw::Toast::new(&message);
+            toast.set_priority(adw::ToastPriority::High);
+            imp.toast_overlay.add_toast(toast);
+            return;
+        }
+
+        self.activate_action(
+            "win.show-toast",
+            Some(&(message, adw::ToastPriority::High.into_glib()).to_variant()),
+        )
+        .expect("Failed to show toast");
+        self.close();
+    }
+
+    fn set_zoom(&self, zoom: f64) {
+        self.imp()
+            .image_view
+            .set_zoom(zoom.clamp(MIN_ZOOM, MAX_ZOOM));
+    }
+
+    /// Zooms the image, so that it fits into the dialog, without enlarging small images.
+    fn zoom_to_fit(&self) {
+        let imp = self.imp();
+        let Some(texture) = imp.image_view.texture() else {
+            return;
+        };
+
+        // before the dialog is shown, the size is not allocated yet
+        let (width, height) = match (imp.scrolled_window.width(), imp.scrolled_window.height()) {
+            (0, _) | (_, 0) => (self.content_width(), self.content_height() - 100),
+            size => size,
+        };
+        let zoom = (width as f64 / texture.width() as f64)
+            .min(height as f64 / texture.height() as f64)
+            .min(1.0);
+        self.set_zoom(zoom);
+    }
+}
+
+impl Default for ImagePickerDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
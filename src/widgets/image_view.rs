@@ -0,0 +1,171 @@
+use glib::Object;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gdk, glib, graphene, gsk};
+
+use crate::colors::sample::Region;
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use super::*;
+
+    #[derive(Debug, glib::Properties)]
+    #[properties(wrapper_type = super::ImageView)]
+    pub struct ImageView {
+        #[property(get, set = Self::set_texture, nullable)]
+        texture: RefCell<Option<gdk::Texture>>,
+        /// Size of a pixel of the image on the screen.
+        #[property(get, set = Self::set_zoom, minimum = 0.01, maximum = 64.0, default = 1.0)]
+        zoom: Cell<f64>,
+        /// Region of the image, which is outlined.
+        pub(super) selection: Cell<Option<Region>>,
+    }
+
+    impl Default for ImageView {
+        fn default() -> Self {
+            Self {
+                texture: Default::default(),
+                zoom: Cell::new(1.0),
+                selection: Default::default(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ImageView {
+        const NAME: &'static str = "ImageView";
+        type ParentType = gtk::Widget;
+        type Type = super::ImageView;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.set_css_name("image-view");
+        }
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for ImageView {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj()
+                .set_cursor(gdk::Cursor::from_name("crosshair", None).as_ref());
+        }
+    }
+
+    impl WidgetImpl for ImageView {
+        fn measure(&self, orientation: gtk::Orientation, _for_size: i32) -> (i32, i32, i32, i32) {
+            let size = self.texture.borrow().as_ref().map_or(0, |texture| {
+                let size = match orientation {
+                    gtk::Orientation::Horizontal => texture.width(),
+                    _ => texture.height(),
+                };
+                (size as f64 * self.zoom.get()).ceil() as i32
+            });
+            (size, size, -1, -1)
+        }
+
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            let Some(texture) = self.texture.borrow().clone() else {
+                return;
+            };
+            let zoom = self.zoom.get() as f32;
+            let rect = graphene::Rect::new(
+                0.0,
+                0.0,
+                texture.width() as f32 * zoom,
+                texture.height() as f32 * zoom,
+            );
+            // keep the pixels sharp when zooming in
+            let filter = if zoom > 1.0 {
+                gsk::ScalingFilter::Nearest
+            } else {
+                gsk::ScalingFilter::Trilinear
+            };
+            snapshot.append_scaled_texture(&texture, filter, &rect);
+
+            if let Some(region) = self.selection.get() {
+                snapshot_region(snapshot, region, zoom, zoom);
+            }
+        }
+    }
+
+    impl ImageView {
+        fn set_texture(&self, texture: Option<gdk::Texture>) {
+            self.texture.replace(texture);
+            self.obj().queue_resize();
+        }
+
+        fn set_zoom(&self, zoom: f64) {
+            self.zoom.set(zoom);
+            self.obj().queue_resize();
+        }
+    }
+}
+
+glib::wrapper! {
+    /// Widget showing an image, which can be zoomed without blurring its pixels.
+    pub struct ImageView(ObjectSubclass<imp::ImageView>)
+    @extends gtk::Widget,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl ImageView {
+    pub fn new() -> Self {
+        Object::builder().build()
+    }
+
+    /// Returns the pixel of the image at the position in the widget.
+    pub fn pixel_at(&self, x: f64, y: f64) -> Option<(usize, usize)> {
+        let texture = self.texture()?;
+        let (x, y) = (x / self.zoom(), y / self.zoom());
+        (x >= 0.0 && y >= 0.0 && x < texture.width() as f64 && y < texture.height() as f64)
+            .then_some((x as usize, y as usize))
+    }
+
+    /// Returns the pixel of the image closest to the position in the widget.
+    pub fn clamped_pixel_at(&self, x: f64, y: f64) -> Option<(usize, usize)> {
+        let texture = self.texture()?;
+        let x = (x / self.zoom()).clamp(0.0, (texture.width() - 1) as f64);
+        let y = (y / self.zoom()).clamp(0.0, (texture.height() - 1) as f64);
+        Some((x as usize, y as usize))
+    }
+
+    /// Outlines the region of the image, or removes the outline.
+    pub fn set_selection(&self, region: Option<Region>) {
+        self.imp().selection.set(region);
+        self.queue_draw();
+    }
+}
+
+/// Outlines the region in black and white, so that it is visible on any color.
+///
+/// The pixels of the region are scaled by the given factors.
+pub fn snapshot_region(snapshot: &gtk::Snapshot, region: Region, scale_x: f32, scale_y: f32) {
+    let (x, y, width, height) = region.bounds();
+    let rect = graphene::Rect::new(
+        x as f32 * scale_x,
+        y as f32 * scale_y,
+        width as f32 * scale_x,
+        height as f32 * scale_y,
+    );
+    let radius = match region {
+        Region::Rectangle { .. } => 0.0,
+        Region::Circle { .. } => rect.width().min(rect.height()) / 2.0,
+    };
+    snapshot.append_border(
+        &gsk::RoundedRect::from_rect(rect.inset_r(-1.0, -1.0), radius + 1.0),
+        &[1.0; 4],
+        &[gdk::RGBA::WHITE; 4],
+    );
+    snapshot.append_border(
+        &gsk::RoundedRect::from_rect(rect, radius),
+        &[1.0; 4],
+        &[gdk::RGBA::BLACK; 4],
+    );
+}
+
+impl Default for ImageView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,275 @@
+use glib::Object;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gdk, glib, graphene, gsk};
+
+use crate::colors::color::Color;
+use crate::colors::sample::{Pixels, Region, RegionAverage};
+use crate::widgets::image_view::snapshot_region;
+
+/// Number of pixels shown in each direction of the loupe, odd to have a center pixel.
+const LOUPE_PIXELS: usize = 15;
+/// Size of a single pixel inside the loupe.
+const PIXEL_SIZE: f32 = 12.0;
+/// Space between the loupe and the hex readout.
+const PADDING: f32 = 6.0;
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct Magnifier {
+        pub(super) texture: RefCell<Option<gdk::Texture>>,
+        /// The downloaded pixels of the texture, with their stride.
+        pub(super) pixels: RefCell<Option<(glib::Bytes, usize)>>,
+        /// The selected pixel of the texture.
+        pub(super) pixel: Cell<Option<(usize, usize)>>,
+        /// The selected region of the texture, with its average color.
+        pub(super) selection: Cell<Option<(Region, Option<Color>)>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for Magnifier {
+        const NAME: &'static str = "Magnifier";
+        type ParentType = gtk::Widget;
+        type Type = super::Magnifier;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.set_css_name("magnifier");
+        }
+    }
+
+    impl ObjectImpl for Magnifier {
+        fn constructed(&self) {
+            self.parent_constructed();
+            // the loupe marks the selected pixel instead of the cursor
+            self.obj()
+                .set_cursor(gdk::Cursor::from_name("none", None).as_ref());
+        }
+    }
+
+    impl WidgetImpl for Magnifier {
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            let Some(texture) = self.texture.borrow().clone() else {
+                return;
+            };
+            let widget = self.obj();
+            let (width, height) = (widget.width() as f32, widget.height() as f32);
+            snapshot.append_texture(&texture, &graphene::Rect::new(0.0, 0.0, width, height));
+
+            let (texture_width, texture_height) = (texture.width() as f32, texture.height() as f32);
+            if let Some((region, _)) = self.selection.get() {
+                snapshot_region(
+                    snapshot,
+                    region,
+                    width / texture_width,
+                    height / texture_height,
+                );
+            }
+
+            let Some((x, y)) = self.pixel.get() else {
+                return;
+            };
+            let center = graphene::Point::new(
+                (x as f32 + 0.5) * width / texture_width,
+                (y as f32 + 0.5) * height / texture_height,
+            );
+
+            let size = LOUPE_PIXELS as f32 * PIXEL_SIZE;
+            let loupe =
+                graphene::Rect::new(center.x() - size / 2.0, center.y() - size / 2.0, size, size);
+            let circle = gsk::RoundedRect::from_rect(loupe, size / 2.0);
+            snapshot.append_outset_shadow(
+                &circle,
+                &gdk::RGBA::new(0.0, 0.0, 0.0, 0.3),
+                0.0,
+                2.0,
+                0.0,
+                8.0,
+            );
+
+            snapshot.push_rounded_clip(&circle);
+            // zoom the texture, so that the selected pixel is in the center of the loupe
+            snapshot.append_scaled_texture(
+                &texture,
+                gsk::ScalingFilter::Nearest,
+                &graphene::Rect::new(
+                    center.x() - (x as f32 + 0.5) * PIXEL_SIZE,
+                    center.y() - (y as f32 + 0.5) * PIXEL_SIZE,
+                    texture_width * PIXEL_SIZE,
+                    texture_height * PIXEL_SIZE,
+                ),
+            );
+
+            let grid = gdk::RGBA::new(0.5, 0.5, 0.5, 0.4);
+            for i in 1..LOUPE_PIXELS {
+                let offset = i as f32 * PIXEL_SIZE;
+                snapshot.append_color(
+                    &grid,
+                    &graphene::Rect::new(loupe.x() + offset, loupe.y(), 1.0, size),
+                );
+                snapshot.append_color(
+                    &grid,
+                    &graphene::Rect::new(loupe.x(), loupe.y() + offset, size, 1.0),
+                );
+            }
+
+            // outline the selected pixel in black and white, so it is visible on any color
+            let cell = graphene::Rect::new(
+                center.x() - PIXEL_SIZE / 2.0,
+                center.y() - PIXEL_SIZE / 2.0,
+                PIXEL_SIZE,
+                PIXEL_SIZE,
+            );
+            snapshot.append_border(
+                &gsk::RoundedRect::from_rect(cell, 0.0),
+                &[2.0; 4],
+                &[gdk::RGBA::BLACK; 4],
+            );
+            snapshot.append_border(
+                &gsk::RoundedRect::from_rect(cell.inset_r(-1.0, -1.0), 0.0),
+                &[1.0; 4],
+                &[gdk::RGBA::WHITE; 4],
+            );
+            snapshot.pop();
+
+            snapshot.append_border(&circle, &[2.0; 4], &[gdk::RGBA::WHITE; 4]);
+
+            if let Some(color) = widget.color() {
+                self.snapshot_readout(snapshot, &color.to_string(), &loupe);
+            }
+        }
+    }
+
+    impl Magnifier {
+        /// Shows the hex code below the loupe, or above it at the bottom of the widget.
+        fn snapshot_readout(&self, snapshot: &gtk::Snapshot, text: &str, loupe: &graphene::Rect) {
+            let layout = self.obj().create_pango_layout(Some(text));
+            let (_, extents) = layout.pixel_extents();
+            let (text_width, text_height) = (extents.width() as f32, extents.height() as f32);
+
+            let label_height = text_height + PADDING;
+            let mut y = loupe.y() + loupe.height() + PADDING;
+            if y + label_height > self.obj().height() as f32 {
+                y = loupe.y() - PADDING - label_height;
+            }
+            let label = graphene::Rect::new(
+                loupe.x() + (loupe.width() - text_width) / 2.0 - PADDING,
+                y,
+                text_width + 2.0 * PADDING,
+                label_height,
+            );
+
+            snapshot.push_rounded_clip(&gsk::RoundedRect::from_rect(label, label_height / 2.0));
+            snapshot.append_color(&gdk::RGBA::new(0.0, 0.0, 0.0, 0.75), &label);
+            snapshot.pop();
+
+            snapshot.save();
+            snapshot.translate(&graphene::Point::new(
+                label.x() + PADDING,
+                label.y() + PADDING / 2.0,
+            ));
+            snapshot.append_layout(&layout, &gdk::RGBA::WHITE);
+            snapshot.restore();
+        }
+    }
+}
+
+glib::wrapper! {
+    /// Widget showing a screenshot with a loupe, which magnifies the pixels around the
+    /// selected pixel.
+    pub struct Magnifier(ObjectSubclass<imp::Magnifier>)
+    @extends gtk::Widget,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Magnifier {
+    pub fn new(texture: &gdk::Texture) -> Self {
+        let magnifier: Self = Object::builder().build();
+        let imp = magnifier.imp();
+
+        let mut downloader = gdk::TextureDownloader::new(texture);
+        downloader.set_format(gdk::MemoryFormat::R8g8b8a8);
+        imp.pixels.replace(Some(downloader.download_bytes()));
+        imp.texture.replace(Some(texture.clone()));
+        magnifier
+    }
+
+    /// Selects the pixel of the texture at the position in the widget.
+    pub fn select_at(&self, x: f64, y: f64) {
+        let Some(texture) = self.imp().texture.borrow().clone() else {
+            return;
+        };
+        if self.width() == 0 || self.height() == 0 {
+            return;
+        }
+        let x = x * texture.width() as f64 / self.width() as f64;
+        let y = y * texture.height() as f64 / self.height() as f64;
+        self.select(
+            x.clamp(0.0, (texture.width() - 1) as f64) as usize,
+            y.clamp(0.0, (texture.height() - 1) as f64) as usize,
+        );
+    }
+
+    /// Moves the selected pixel by the given number of pixels, staying inside the texture.
+    pub fn move_by(&self, dx: isize, dy: isize) {
+        let Some(texture) = self.imp().texture.borrow().clone() else {
+            return;
+        };
+        let (x, y) = self.imp().pixel.get().unwrap_or_default();
+        self.select(
+            x.saturating_add_signed(dx)
+                .min(texture.width() as usize - 1),
+            y.saturating_add_signed(dy)
+                .min(texture.height() as usize - 1),
+        );
+    }
+
+    fn select(&self, x: usize, y: usize) {
+        self.imp().pixel.set(Some((x, y)));
+        self.queue_draw();
+    }
+
+    /// The selected pixel of the texture.
+    pub fn pixel(&self) -> Option<(usize, usize)> {
+        self.imp().pixel.get()
+    }
+
+    /// Outlines the region and averages its colors, or removes the selected region.
+    pub fn set_selection(&self, region: Option<Region>, average: RegionAverage) {
+        let selection = region.map(|region| {
+            let color = self
+                .with_pixels(|pixels| average.apply(pixels.colors_in(region)))
+                .flatten();
+            (region, color)
+        });
+        self.imp().selection.set(selection);
+        self.queue_draw();
+    }
+
+    /// The average color of the selected region, or the color of the selected pixel.
+    pub fn color(&self) -> Option<Color> {
+        if let Some((_, color)) = self.imp().selection.get() {
+            return color;
+        }
+        let (x, y) = self.pixel()?;
+        self.with_pixels(|pixels| pixels.get(x, y)).flatten()
+    }
+
+    fn with_pixels<T>(&self, f: impl FnOnce(Pixels) -> T) -> Option<T> {
+        let imp = self.imp();
+        let texture = imp.texture.borrow();
+        let texture = texture.as_ref()?;
+        let pixels = imp.pixels.borrow();
+        let (bytes, stride) = pixels.as_ref()?;
+        Pixels::new(
+            bytes,
+            texture.width() as usize,
+            texture.height() as usize,
+            *stride,
+        )
+        .map(f)
+    }
+}
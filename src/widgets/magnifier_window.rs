@@ -0,0 +1,263 @@
+use futures::channel::oneshot;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gdk, gio, glib};
+
+use crate::colors::color::Color;
+use crate::colors::sample::{Region, RegionAverage, RegionShape};
+use crate::config;
+use crate::widgets::magnifier::Magnifier;
+
+/// Number of pixels the selection moves with the arrow keys while shift is pressed.
+const FAST_STEP: isize = 10;
+
+mod imp {
+    use std::cell::{Cell, OnceCell, RefCell};
+
+    use super::*;
+
+    #[derive(Debug)]
+    pub struct MagnifierWindow {
+        pub(super) settings: gio::Settings,
+        pub(super) magnifier: OnceCell<Magnifier>,
+        /// Sends the picked color, or is dropped when the picking is cancelled.
+        pub(super) sender: RefCell<Option<oneshot::Sender<Color>>>,
+        /// The pixel at which the current drag started.
+        pub(super) drag_start: Cell<Option<(usize, usize)>>,
+        /// The monitor showing the window, `None` if it is unknown.
+        pub(super) monitor: RefCell<Option<gdk::Monitor>>,
+    }
+
+    impl Default for MagnifierWindow {
+        fn default() -> Self {
+            Self {
+                settings: gio::Settings::new(config::APP_ID),
+                magnifier: Default::default(),
+                sender: Default::default(),
+                drag_start: Default::default(),
+                monitor: Default::default(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for MagnifierWindow {
+        const NAME: &'static str = "MagnifierWindow";
+        type ParentType = gtk::Window;
+        type Type = super::MagnifierWindow;
+    }
+
+    impl ObjectImpl for MagnifierWindow {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.set_decorated(false);
+            obj.set_modal(true);
+
+            let key_controller = gtk::EventControllerKey::new();
+            key_controller.connect_key_pressed(glib::clone!(
+                #[weak]
+                obj,
+                #[upgrade_or]
+                glib::Propagation::Proceed,
+                move |_, key, _, modifier| obj.on_key_pressed(key, modifier)
+            ));
+            obj.add_controller(key_controller);
+
+            // dropping the sender cancels the picking
+            obj.connect_close_request(|window| {
+                window.imp().sender.take();
+                glib::Propagation::Proceed
+            });
+        }
+    }
+
+    impl WidgetImpl for MagnifierWindow {}
+    impl WindowImpl for MagnifierWindow {}
+}
+
+glib::wrapper! {
+    /// Fullscreen window for picking a pixel from a screenshot with a magnifier.
+    pub struct MagnifierWindow(ObjectSubclass<imp::MagnifierWindow>)
+    @extends gtk::Widget, gtk::Window,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl MagnifierWindow {
+    /// Creates a window for picking from the screenshot of the whole desktop.
+    ///
+    /// The window is shown on the monitor of the parent, with the part of the screenshot
+    /// showing this monitor.
+    pub fn new(parent: &impl IsA<gtk::Window>, screenshot: &gdk::Texture) -> Self {
+        let window: Self = glib::Object::builder()
+            .property("transient-for", parent)
+            .build();
+
+        let monitor = parent
+            .as_ref()
+            .surface()
+            .and_then(|surface| surface.display().monitor_at_surface(&surface));
+        let screenshot = monitor
+            .as_ref()
+            .and_then(|monitor| crop_to_monitor(screenshot, monitor))
+            .unwrap_or_else(|| screenshot.clone());
+        window.imp().monitor.replace(monitor);
+
+        let magnifier = Magnifier::new(&screenshot);
+
+        let motion = gtk::EventControllerMotion::new();
+        motion.connect_motion(glib::clone!(
+            #[weak]
+            magnifier,
+            move |_, x, y| magnifier.select_at(x, y)
+        ));
+        magnifier.add_controller(motion);
+
+        // pick with the primary button, cancel with any other button
+        let drag = gtk::GestureDrag::builder().button(0).build();
+        drag.connect_drag_begin(glib::clone!(
+            #[weak]
+            window,
+            move |gesture, x, y| {
+                if gesture.current_button() != gdk::BUTTON_PRIMARY {
+                    window.close();
+                    return;
+                }
+                let magnifier = window.imp().magnifier.get().unwrap();
+                magnifier.select_at(x, y);
+                window.imp().drag_start.set(magnifier.pixel());
+            }
+        ));
+        drag.connect_drag_update(glib::clone!(
+            #[weak]
+            window,
+            move |gesture, offset_x, offset_y| {
+                if let Some((x, y)) = gesture.start_point() {
+                    window.select_region(x + offset_x, y + offset_y);
+                }
+            }
+        ));
+        drag.connect_drag_end(glib::clone!(
+            #[weak]
+            window,
+            move |gesture, offset_x, offset_y| {
+                if let Some((x, y)) = gesture.start_point() {
+                    window.select_region(x + offset_x, y + offset_y);
+                    window.pick();
+                }
+            }
+        ));
+        magnifier.add_controller(drag);
+
+        window.set_child(Some(&magnifier));
+        window.imp().magnifier.set(magnifier).unwrap();
+        window
+    }
+
+    /// Shows the window fullscreen and waits until a color is picked.
+    ///
+    /// Returns [`None`] if the picking is cancelled.
+    pub async fn pick_color(&self) -> Option<Color> {
+        let (sender, receiver) = oneshot::channel();
+        self.imp().sender.replace(Some(sender));
+        match self.imp().monitor.borrow().as_ref() {
+            Some(monitor) => self.fullscreen_on_monitor(monitor),
+            None => self.fullscreen(),
+        }
+        self.present();
+
+        receiver.await.ok()
+    }
+
+    /// Selects the region between the start of the drag and the position.
+    ///
+    /// Without dragging across pixels, only the pixel at the position is selected.
+    fn select_region(&self, x: f64, y: f64) {
+        let imp = self.imp();
+        let Some(start) = imp.drag_start.get() else {
+            return;
+        };
+        let magnifier = imp.magnifier.get().unwrap();
+        magnifier.select_at(x, y);
+        let region = magnifier.pixel().filter(|end| *end != start).map(|end| {
+            let shape = RegionShape::from(imp.settings.int("region-shape") as u32);
+            Region::from_drag(shape, start, end)
+        });
+        let average = RegionAverage::from(imp.settings.int("region-average") as u32);
+        magnifier.set_selection(region, average);
+    }
+
+    /// Sends the color of the selected pixel or region and closes the window.
+    fn pick(&self) {
+        let imp = self.imp();
+        if let Some(color) = imp.magnifier.get().and_then(Magnifier::color)
+            && let Some(sender) = imp.sender.take()
+        {
+            let _ = sender.send(color);
+        }
+        self.close();
+    }
+
+    /// Moves the selection with the arrow keys, picks with enter and cancels with escape.
+    fn on_key_pressed(&self, key: gdk::Key, modifier: gdk::ModifierType) -> glib::Propagation {
+        let magnifier = self.imp().magnifier.get().unwrap();
+        let step = if modifier.contains(gdk::ModifierType::SHIFT_MASK) {
+            FAST_STEP
+        } else {
+            1
+        };
+
+        match key {
+            gdk::Key::Left | gdk::Key::KP_Left => magnifier.move_by(-step, 0),
+            gdk::Key::Right | gdk::Key::KP_Right => magnifier.move_by(step, 0),
+            gdk::Key::Up | gdk::Key::KP_Up => magnifier.move_by(0, -step),
+            gdk::Key::Down | gdk::Key::KP_Down => magnifier.move_by(0, step),
+            gdk::Key::Return | gdk::Key::KP_Enter | gdk::Key::space => self.pick(),
+            gdk::Key::Escape => self.close(),
+            _ => return glib::Propagation::Proceed,
+        }
+        glib::Propagation::Stop
+    }
+}
+
+/// Crops the screenshot of the whole desktop to the area shown by the monitor.
+///
+/// The screenshot spans all monitors, possibly with a different scale than the
+/// monitor geometry. Returns [`None`] if there is only one monitor.
+fn crop_to_monitor(screenshot: &gdk::Texture, monitor: &gdk::Monitor) -> Option<gdk::Texture> {
+    let monitors = monitor.display().monitors();
+    if monitors.n_items() < 2 {
+        return None;
+    }
+    let desktop = monitors
+        .iter::<gdk::Monitor>()
+        .filter_map(Result::ok)
+        .map(|monitor| monitor.geometry())
+        .reduce(|desktop, geometry| desktop.union(&geometry))?;
+    let geometry = monitor.geometry();
+
+    let scale_x = screenshot.width() as f64 / desktop.width() as f64;
+    let scale_y = screenshot.height() as f64 / desktop.height() as f64;
+    let x = (((geometry.x() - desktop.x()) as f64 * scale_x).round() as usize)
+        .min(screenshot.width() as usize - 1);
+    let y = (((geometry.y() - desktop.y()) as f64 * scale_y).round() as usize)
+        .min(screenshot.height() as usize - 1);
+    let width = ((geometry.width() as f64 * scale_x).round() as usize)
+        .clamp(1, screenshot.width() as usize - x);
+    let height = ((geometry.height() as f64 * scale_y).round() as usize)
+        .clamp(1, screenshot.height() as usize - y);
+
+    let mut downloader = gdk::TextureDownloader::new(screenshot);
+    downloader.set_format(gdk::MemoryFormat::R8g8b8a8);
+    let (bytes, stride) = downloader.download_bytes();
+    let start = y * stride + x * 4;
+    let end = start + (height - 1) * stride + width * 4;
+    let cropped = gdk::MemoryTexture::new(
+        width as i32,
+        height as i32,
+        gdk::MemoryFormat::R8g8b8a8,
+        &glib::Bytes::from_bytes(&bytes, start..end),
+        stride,
+    );
+    Some(cropped.upcast())
+}
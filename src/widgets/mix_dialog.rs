@@ -0,0 +1,252 @@
+use adw::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gdk, gio, glib};
+
+use crate::colors::Notation;
+use crate::colors::color::Color;
+use crate::colors::color_names::ColorNameSources;
+use crate::colors::gamut::GamutMapping;
+use crate::colors::mix::{self, HueInterpolation, MixSpace};
+use crate::config;
+use crate::model::history::ColorSource;
+
+mod imp {
+    use std::cell::Cell;
+
+    use adw::subclass::dialog::AdwDialogImpl;
+
+    use super::*;
+
+    #[derive(Debug, gtk::CompositeTemplate)]
+    #[template(resource = "/com/github/finefindus/eyedropper/ui/mix-dialog.ui")]
+    pub struct MixDialog {
+        pub(super) settings: gio::Settings,
+        #[template_child]
+        pub(super) first_button: TemplateChild<gtk::ColorDialogButton>,
+        #[template_child]
+        pub(super) second_button: TemplateChild<gtk::ColorDialogButton>,
+        #[template_child]
+        pub(super) second_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub(super) space_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(super) hue_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(super) ratio_scale: TemplateChild<gtk::Scale>,
+        #[template_child]
+        pub(super) result_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(super) css_row: TemplateChild<adw::ActionRow>,
+        pub(super) css_provider: gtk::CssProvider,
+        /// The mixed color, which may be outside of the sRGB gamut.
+        pub(super) result: Cell<Color>,
+        /// Colors with values outside of the sRGB gamut, which are lost in the color buttons.
+        pub(super) exact_colors: Cell<(Option<Color>, Option<Color>)>,
+    }
+
+    impl Default for MixDialog {
+        fn default() -> Self {
+            Self {
+                settings: gio::Settings::new(config::APP_ID),
+                first_button: TemplateChild::default(),
+                second_button: TemplateChild::default(),
+                second_entry: TemplateChild::default(),
+                space_row: TemplateChild::default(),
+                hue_row: TemplateChild::default(),
+                ratio_scale: TemplateChild::default(),
+                result_row: TemplateChild::default(),
+                css_row: TemplateChild::default(),
+                css_provider: Default::default(),
+                result: Default::default(),
+                exact_colors: Default::default(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for MixDialog {
+        const NAME: &'static str = "MixDialog";
+        type Type = super::MixDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for MixDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.settings
+                .bind("mix-color-space", &*self.space_row, "selected")
+                .build();
+
+            self.settings
+                .bind("mix-hue-interpolation", &*self.hue_row, "selected")
+                .build();
+
+            // setup CSS provider to update the colors of the preview
+            gtk::style_context_add_provider_for_display(
+                &self.obj().display(),
+                &self.css_provider,
+                gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+            );
+        }
+
+        fn dispose(&self) {
+            gtk::style_context_remove_provider_for_display(
+                &self.obj().display(),
+                &self.css_provider,
+            );
+            self.dispose_template();
+        }
+    }
+
+    impl WidgetImpl for MixDialog {}
+    impl AdwDialogImpl for MixDialog {}
+
+    #[gtk::template_callbacks]
+    impl MixDialog {
+        /// Returns both colors, preferring the exact colors if the buttons still show them.
+        fn colors(&self) -> (Color, Color) {
+            let exact = |exact: Option<Color>, button: &gtk::ColorDialogButton| {
+                let shown = Color::from(button.rgba());
+                exact
+                    .filter(|exact| Color::from(gdk::RGBA::from(*exact)) == shown)
+                    .unwrap_or(shown)
+            };
+            let (first, second) = self.exact_colors.get();
+            (
+                exact(first, &self.first_button),
+                exact(second, &self.second_button),
+            )
+        }
+
+        /// Mixes the colors with the selected ratio and space, and shows the result.
+        #[template_callback]
+        pub(super) fn update_mix(&self) {
+            let (first, second) = self.colors();
+            let space = MixSpace::from(self.space_row.selected());
+            let hue_interpolation = HueInterpolation::from(self.hue_row.selected());
+            let ratio = (self.ratio_scale.value() / 100.0) as f32;
+            self.hue_row.set_sensitive(space.is_polar());
+
+            let result = mix::mix(first, second, ratio, space, hue_interpolation);
+            self.result.set(result);
+
+            let gamut_mapping = GamutMapping::from(self.settings.int("gamut-mapping") as u32);
+            let preview = |color: Color| gdk::RGBA::from(gamut_mapping.apply(color));
+            self.css_provider.load_from_string(&format!(
+                ".mix-first {{ background-color: {}; }} .mix-result {{ background-color: {}; }} .mix-second {{ background-color: {}; }}",
+                preview(first),
+                preview(result),
+                preview(second)
+            ));
+
+            let mut hex = gamut_mapping.apply(result).hex();
+            if result.alpha >= 1.0 {
+                hex.truncate(7);
+            }
+            self.result_row.set_subtitle(&hex);
+            self.css_row.set_subtitle(&mix::css_function(
+                first,
+                second,
+                ratio,
+                space,
+                hue_interpolation,
+            ));
+        }
+
+        /// Swaps the first and second color.
+        #[template_callback]
+        fn swap_colors(&self) {
+            let (first, second) = self.exact_colors.get();
+            let first_rgba = self.first_button.rgba();
+            self.first_button.set_rgba(&self.second_button.rgba());
+            self.second_button.set_rgba(&first_rgba);
+            self.exact_colors.set((second, first));
+            self.update_mix();
+        }
+
+        /// Parses the entered color in any supported notation and uses it as second color.
+        #[template_callback]
+        async fn on_second_entry_apply(&self) {
+            let text = self.second_entry.text();
+            let name_sources = ColorNameSources::from_bits(self.settings.uint("name-sources-flag"))
+                .unwrap_or(ColorNameSources::empty());
+            match Notation::detect(&text, name_sources) {
+                Ok((_, color)) => {
+                    self.second_entry.set_text("");
+                    self.obj().set_second_color(color);
+                }
+                Err(_) => {
+                    tracing::debug!("Failed to parse color: {}", text);
+                    const STYLE_CLASS: &str = "error";
+                    self.second_entry.add_css_class(STYLE_CLASS);
+                    glib::timeout_future(std::time::Duration::from_millis(350)).await;
+                    self.second_entry.remove_css_class(STYLE_CLASS);
+                }
+            }
+        }
+
+        /// Pick the second color from the desktop using [ashpd].
+        #[template_callback]
+        async fn pick_second(&self) {
+            let root = self.obj().root().expect("Failed to get dialog root");
+            let identifier = ashpd::WindowIdentifier::from_native(&root).await;
+            let request = ashpd::desktop::screenshot::ColorRequest::default()
+                .identifier(identifier)
+                .send()
+                .await;
+
+            match request.and_then(|req| req.response()) {
+                Ok(color) => self.second_button.set_rgba(&gdk::RGBA::from(color)),
+                Err(err) => tracing::error!("Failed to pick second color: {}", err),
+            }
+        }
+
+        /// Sets the mixed color as the current color and closes the dialog.
+        #[template_callback]
+        fn set_as_color(&self) {
+            let obj = self.obj();
+            obj.activate_action(
+                "win.set-exact-color",
+                Some(&(self.result.get(), ColorSource::Editor).to_variant()),
+            )
+            .expect("Failed to call win.set-exact-color action");
+            obj.close();
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct MixDialog(ObjectSubclass<imp::MixDialog>)
+    @extends gtk::Widget, adw::Dialog,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl MixDialog {
+    /// Create a new dialog, mixing the second color into the first one.
+    pub fn new(first: Color, second: Color) -> Self {
+        let dialog: Self = glib::Object::new();
+        dialog.imp().exact_colors.set((Some(first), None));
+        dialog.imp().first_button.set_rgba(&first.into());
+        dialog.set_second_color(second);
+        dialog
+    }
+
+    /// Sets the color, which is mixed into the first color.
+    fn set_second_color(&self, color: Color) {
+        let imp = self.imp();
+        let (first, _) = imp.exact_colors.get();
+        imp.exact_colors.set((first, Some(color)));
+        imp.second_button.set_rgba(&color.into());
+        imp.update_mix();
+    }
+}
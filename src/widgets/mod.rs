@@ -1,5 +1,18 @@
 pub mod about_window;
 pub mod color_format_row;
+pub mod color_ramp;
+pub mod color_swatch;
+pub mod contrast_dialog;
+pub mod difference_dialog;
+pub mod extract_palette_dialog;
+pub mod gradient_dialog;
+pub mod harmony_dialog;
 pub mod history_item;
+pub mod image_picker_dialog;
+pub mod image_view;
+pub mod magnifier;
+pub mod magnifier_window;
+pub mod mix_dialog;
+pub mod palette_library_dialog;
 pub mod placeholder_page;
 pub mod preferences;
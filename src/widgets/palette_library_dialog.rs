@@ -0,0 +1,391 @@
+use std::cell::RefCell;
+
+use adw::prelude::*;
+use gettextrs::{gettext, ngettext};
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use crate::colors::color::Color;
+use crate::model::palette::Palette;
+use crate::model::palette_color::PaletteColor;
+use crate::widgets::color_swatch::ColorSwatch;
+
+mod imp {
+    use std::cell::OnceCell;
+
+    use adw::subclass::dialog::AdwDialogImpl;
+
+    use super::*;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(resource = "/com/github/finefindus/eyedropper/ui/palette-library-dialog.ui")]
+    pub struct PaletteLibraryDialog {
+        #[template_child]
+        pub(super) navigation_view: TemplateChild<adw::NavigationView>,
+        #[template_child]
+        pub(super) stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub(super) empty_page: TemplateChild<adw::StatusPage>,
+        #[template_child]
+        pub(super) library_page: TemplateChild<gtk::ScrolledWindow>,
+        #[template_child]
+        pub(super) palette_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub(super) palette_page: TemplateChild<adw::NavigationPage>,
+        #[template_child]
+        pub(super) name_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub(super) color_list: TemplateChild<gtk::ListBox>,
+        /// The [`Palette`]s of the library.
+        pub(super) palettes: OnceCell<gio::ListStore>,
+        /// The palette shown on the palette page.
+        pub(super) palette: RefCell<Option<Palette>>,
+        /// Handler updating the page, when palettes are added or removed from the library.
+        pub(super) palettes_handler: RefCell<Option<glib::SignalHandlerId>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PaletteLibraryDialog {
+        const NAME: &'static str = "PaletteLibraryDialog";
+        type Type = super::PaletteLibraryDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for PaletteLibraryDialog {
+        fn dispose(&self) {
+            // the library outlives the dialog
+            if let (Some(palettes), Some(handler)) =
+                (self.palettes.get(), self.palettes_handler.take())
+            {
+                palettes.disconnect(handler);
+            }
+            self.dispose_template();
+        }
+    }
+    impl WidgetImpl for PaletteLibraryDialog {}
+    impl AdwDialogImpl for PaletteLibraryDialog {}
+
+    #[gtk::template_callbacks]
+    impl PaletteLibraryDialog {
+        /// Creates a new palette and shows it, so it can be named.
+        #[template_callback]
+        fn on_new_palette(&self) {
+            let palettes = self.obj().palettes();
+            let palette = Palette::new(&Palette::unused_name(&palettes));
+            palettes.append(&palette);
+            self.obj().save();
+            self.obj().show_palette(&palette);
+        }
+
+        /// Renames the palette, unless the name is empty or used by another palette.
+        #[template_callback]
+        fn on_rename(&self) {
+            let Some(palette) = self.palette.borrow().clone() else {
+                return;
+            };
+            let name = self.name_row.text().trim().to_owned();
+            let used = self
+                .obj()
+                .palettes()
+                .snapshot()
+                .iter()
+                .filter_map(Cast::downcast_ref::<Palette>)
+                .any(|other| *other != palette && other.name() == name);
+            if name.is_empty() || used {
+                self.name_row.add_css_class("error");
+                return;
+            }
+            self.name_row.remove_css_class("error");
+            palette.set_name(name);
+            self.obj().save();
+        }
+
+        /// Adds the colors of the palette to the history.
+        #[template_callback]
+        fn on_add_to_history(&self) {
+            let Some(palette) = self.palette.borrow().clone() else {
+                return;
+            };
+            let colors = palette
+                .entries()
+                .into_iter()
+                .map(|(color, _)| color)
+                .collect::<Vec<Color>>();
+            self.obj()
+                .activate_action("win.add-colors", Some(&colors.to_variant()))
+                .expect("Failed to call win.add-colors action");
+        }
+
+        /// Asks for confirmation and deletes the palette.
+        #[template_callback]
+        fn on_delete_palette(&self) {
+            let Some(palette) = self.palette.borrow().clone() else {
+                return;
+            };
+
+            let dialog = adw::AlertDialog::builder()
+                .heading(gettext("Delete Palette?"))
+                .body(
+                    gettext("“{}” and its colors will be permanently deleted")
+                        .replace("{}", &palette.name()),
+                )
+                .default_response("cancel")
+                .close_response("cancel")
+                .build();
+            dialog.add_responses(&[
+                ("cancel", &gettext("_Cancel")),
+                ("delete", &gettext("_Delete")),
+            ]);
+            dialog.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
+            dialog.connect_response(
+                Some("delete"),
+                glib::clone!(
+                    #[weak(rename_to = library)]
+                    self.obj(),
+                    move |_, _| {
+                        let palettes = library.palettes();
+                        if let Some(index) = palettes.find(&palette) {
+                            palettes.remove(index);
+                        }
+                        library.save();
+                        library.imp().navigation_view.pop();
+                    }
+                ),
+            );
+            dialog.present(Some(&*self.obj()));
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct PaletteLibraryDialog(ObjectSubclass<imp::PaletteLibraryDialog>)
+    @extends gtk::Widget, adw::Dialog,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl PaletteLibraryDialog {
+    /// Create a new dialog, showing the palettes of the library.
+    pub fn new(palettes: &gio::ListStore) -> Self {
+        let dialog: Self = glib::Object::new();
+        let imp = dialog.imp();
+        imp.palettes
+            .set(palettes.clone())
+            .expect("Failed to set palettes");
+
+        imp.palette_list.bind_model(
+            Some(palettes),
+            glib::clone!(
+                #[weak]
+                dialog,
+                #[upgrade_or_panic]
+                move |obj| {
+                    let palette = obj
+                        .downcast_ref::<Palette>()
+                        .expect("The object is not of type `Palette`.");
+                    dialog.palette_row(palette).upcast()
+                }
+            ),
+        );
+        imp.color_list
+            .set_placeholder(Some(&Self::placeholder(&gettext("No Colors"))));
+
+        let handler = palettes.connect_items_changed(glib::clone!(
+            #[weak]
+            dialog,
+            move |_, _, _, _| dialog.update_stack()
+        ));
+        imp.palettes_handler.replace(Some(handler));
+        dialog.update_stack();
+        dialog
+    }
+
+    fn palettes(&self) -> gio::ListStore {
+        self.imp().palettes.get().expect("Palettes not set").clone()
+    }
+
+    /// Stores the palettes, so they are available in the next session.
+    fn save(&self) {
+        Palette::save_library(&self.palettes());
+    }
+
+    fn update_stack(&self) {
+        let imp = self.imp();
+        if self.palettes().n_items() == 0 {
+            imp.stack.set_visible_child(&*imp.empty_page);
+        } else {
+            imp.stack.set_visible_child(&*imp.library_page);
+        }
+    }
+
+    fn placeholder(text: &str) -> gtk::Label {
+        let label = gtk::Label::new(Some(text));
+        label.set_margin_top(12);
+        label.set_margin_bottom(12);
+        label.add_css_class("dim-label");
+        label
+    }
+
+    /// Creates a row showing the palette, with buttons to move it up or down.
+    fn palette_row(&self, palette: &Palette) -> adw::ActionRow {
+        let row = adw::ActionRow::builder().activatable(true).build();
+        palette
+            .bind_property("name", &row, "title")
+            .sync_create()
+            .build();
+
+        let update_subtitle = glib::clone!(
+            #[weak]
+            row,
+            move |colors: &gio::ListStore| {
+                let count = colors.n_items();
+                row.set_subtitle(
+                    &ngettext("{} color", "{} colors", count).replace("{}", &count.to_string()),
+                );
+            }
+        );
+        update_subtitle(&palette.colors());
+        let handler = palette
+            .colors()
+            .connect_items_changed(move |colors, _, _, _| update_subtitle(colors));
+        // the palette outlives the row, when the row is removed or the dialog is closed
+        let handler = RefCell::new(Some(handler));
+        row.connect_destroy(glib::clone!(
+            #[weak]
+            palette,
+            move |_| {
+                if let Some(handler) = handler.take() {
+                    palette.colors().disconnect(handler);
+                }
+            }
+        ));
+
+        for (icon, tooltip, offset) in [
+            ("go-up-symbolic", gettext("Move Up"), -1),
+            ("go-down-symbolic", gettext("Move Down"), 1),
+        ] {
+            let button = gtk::Button::builder()
+                .icon_name(icon)
+                .tooltip_text(tooltip)
+                .valign(gtk::Align::Center)
+                .css_classes(["flat"])
+                .build();
+            button.connect_clicked(glib::clone!(
+                #[weak(rename_to = library)]
+                self,
+                #[weak]
+                palette,
+                move |_| library.move_palette(&palette, offset)
+            ));
+            row.add_suffix(&button);
+        }
+        row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+
+        row.connect_activated(glib::clone!(
+            #[weak(rename_to = library)]
+            self,
+            #[weak]
+            palette,
+            move |_| library.show_palette(&palette)
+        ));
+        row
+    }
+
+    /// Moves the palette by the offset in the library.
+    fn move_palette(&self, palette: &Palette, offset: i32) {
+        let palettes = self.palettes();
+        let Some(index) = palettes.find(palette) else {
+            return;
+        };
+        let Some(target) = index
+            .checked_add_signed(offset)
+            .filter(|target| *target < palettes.n_items())
+        else {
+            return;
+        };
+        palettes.remove(index);
+        palettes.insert(target, palette);
+        self.save();
+    }
+
+    /// Shows the colors of the palette on the palette page.
+    fn show_palette(&self, palette: &Palette) {
+        let imp = self.imp();
+        imp.palette.replace(Some(palette.clone()));
+        imp.name_row.set_text(&palette.name());
+        imp.name_row.remove_css_class("error");
+        imp.color_list.bind_model(
+            Some(&palette.colors()),
+            glib::clone!(
+                #[weak(rename_to = library)]
+                self,
+                #[weak]
+                palette,
+                #[upgrade_or_panic]
+                move |obj| {
+                    let color = obj
+                        .downcast_ref::<PaletteColor>()
+                        .expect("The object is not of type `PaletteColor`.");
+                    library.color_row(&palette, color).upcast()
+                }
+            ),
+        );
+        imp.navigation_view.push(&*imp.palette_page);
+    }
+
+    /// Creates a row showing the color, whose label can be edited.
+    fn color_row(&self, palette: &Palette, color: &PaletteColor) -> adw::EntryRow {
+        let row = adw::EntryRow::builder()
+            .title(color.color().hex())
+            .text(color.label())
+            .show_apply_button(true)
+            .build();
+
+        let swatch = ColorSwatch::new(color.color());
+        swatch.set_valign(gtk::Align::Center);
+        row.add_prefix(&swatch);
+
+        row.connect_apply(glib::clone!(
+            #[weak(rename_to = library)]
+            self,
+            #[weak]
+            color,
+            move |row| {
+                color.set_label(row.text().trim());
+                library.save();
+            }
+        ));
+
+        let remove_button = gtk::Button::builder()
+            .icon_name("user-trash-symbolic")
+            .tooltip_text(gettext("Remove"))
+            .valign(gtk::Align::Center)
+            .css_classes(["flat"])
+            .build();
+        remove_button.connect_clicked(glib::clone!(
+            #[weak(rename_to = library)]
+            self,
+            #[weak]
+            palette,
+            #[weak]
+            color,
+            move |_| {
+                let colors = palette.colors();
+                if let Some(index) = colors.find(&color) {
+                    colors.remove(index);
+                }
+                library.save();
+            }
+        ));
+        row.add_suffix(&remove_button);
+        row
+    }
+}
@@ -7,19 +7,32 @@ mod imp {
     use adw::subclass::bin::BinImpl;
     use rand::seq::IndexedRandom;
 
-    use crate::colors::color::Color;
+    use crate::colors::{Notation, color::Color, color_names::ColorNameSources};
+    use crate::config;
+    use crate::model::history::ColorSource;
 
     use super::*;
 
-    #[derive(Default, Debug, gtk::CompositeTemplate)]
+    #[derive(Debug, gtk::CompositeTemplate)]
     #[template(resource = "/com/github/finefindus/eyedropper/ui/placeholder-page.ui")]
     pub struct PlaceholderPage {
+        settings: gtk::gio::Settings,
         #[template_child]
         initial_color_button: TemplateChild<gtk::Button>,
         #[template_child]
         initial_color_entry: TemplateChild<gtk::Entry>,
     }
 
+    impl Default for PlaceholderPage {
+        fn default() -> Self {
+            Self {
+                settings: gtk::gio::Settings::new(config::APP_ID),
+                initial_color_button: TemplateChild::default(),
+                initial_color_entry: TemplateChild::default(),
+            }
+        }
+    }
+
     #[glib::object_subclass]
     impl ObjectSubclass for PlaceholderPage {
         const NAME: &'static str = "PlaceholderPage";
@@ -70,11 +83,23 @@ mod imp {
         #[template_callback]
         async fn on_color_entry_active(&self) {
             let text = self.initial_color_entry.text();
-            match gtk::gdk::RGBA::parse(&text) {
+            let name_sources = ColorNameSources::from_bits(self.settings.uint("name-sources-flag"))
+                .unwrap_or(ColorNameSources::empty());
+            // GTK rounds the colors and does not support CSS Color 4, so it is only a fallback
+            let color = Notation::detect(&text, name_sources)
+                .map(|(_, color)| color)
+                .or_else(|err| {
+                    gtk::gdk::RGBA::parse(&text)
+                        .map(Color::from)
+                        .map_err(|_| err)
+                });
+            match color {
                 Ok(color) => {
-                    let color: Color = color.into();
                     self.obj()
-                        .activate_action("win.set-color", Some(&color.hex().to_variant()))
+                        .activate_action(
+                            "win.set-exact-color",
+                            Some(&(color, ColorSource::Typed).to_variant()),
+                        )
                         .expect("Failed to set color");
                 }
                 Err(_) => {
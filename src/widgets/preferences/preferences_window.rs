@@ -13,6 +13,9 @@ use gtk::subclass::prelude::*;
 
 use crate::colors::Notation;
 use crate::colors::color::Color;
+use crate::colors::gamut::GamutMapping;
+use crate::colors::illuminant::ReferenceWhite;
+use crate::colors::template::{CUSTOM_PREFIX, Template};
 
 use super::color_format::ColorFormatObject;
 use adw::prelude::ActionRowExt;
@@ -36,13 +39,33 @@ mod imp {
         #[template_child()]
         pub name_source_page: TemplateChild<adw::NavigationPage>,
         #[template_child()]
+        pub magnifier_row: TemplateChild<adw::SwitchRow>,
+        #[template_child()]
+        pub region_shape_box: TemplateChild<adw::ComboRow>,
+        #[template_child()]
+        pub region_average_box: TemplateChild<adw::ComboRow>,
+        #[template_child()]
         pub alpha_pos_box: TemplateChild<adw::ComboRow>,
         #[template_child()]
         pub rgb_format_box: TemplateChild<adw::ComboRow>,
         #[template_child()]
+        pub gamut_mapping_box: TemplateChild<adw::ComboRow>,
+        #[template_child()]
         pub precision_row: TemplateChild<adw::SpinRow>,
         #[template_child()]
+        pub illuminant_box: TemplateChild<adw::ComboRow>,
+        #[template_child()]
+        pub observer_box: TemplateChild<adw::ComboRow>,
+        #[template_child()]
+        pub severity_row: TemplateChild<adw::SpinRow>,
+        #[template_child()]
+        pub simulation_method_box: TemplateChild<adw::ComboRow>,
+        #[template_child()]
         pub order_list: TemplateChild<gtk::ListBox>,
+        #[template_child()]
+        pub custom_name_row: TemplateChild<adw::EntryRow>,
+        #[template_child()]
+        pub custom_template_row: TemplateChild<adw::EntryRow>,
         #[template_child]
         pub(super) name_source_basic: TemplateChild<adw::SwitchRow>,
         #[template_child]
@@ -64,10 +87,20 @@ mod imp {
             Self {
                 settings: gtk::gio::Settings::new(config::APP_ID),
                 name_source_page: TemplateChild::default(),
+                magnifier_row: TemplateChild::default(),
+                region_shape_box: TemplateChild::default(),
+                region_average_box: TemplateChild::default(),
                 alpha_pos_box: TemplateChild::default(),
                 rgb_format_box: TemplateChild::default(),
+                gamut_mapping_box: TemplateChild::default(),
                 precision_row: TemplateChild::default(),
+                illuminant_box: TemplateChild::default(),
+                observer_box: TemplateChild::default(),
+                severity_row: TemplateChild::default(),
+                simulation_method_box: TemplateChild::default(),
                 order_list: TemplateChild::default(),
+                custom_name_row: TemplateChild::default(),
+                custom_template_row: TemplateChild::default(),
                 name_source_basic: TemplateChild::default(),
                 name_source_extended: TemplateChild::default(),
                 name_source_gnome: TemplateChild::default(),
@@ -93,6 +126,18 @@ mod imp {
             obj.setup_order_list();
             obj.populate_formats();
 
+            self.settings
+                .bind("pick-magnifier", &*self.magnifier_row, "active")
+                .build();
+
+            self.settings
+                .bind("region-shape", &*self.region_shape_box, "selected")
+                .build();
+
+            self.settings
+                .bind("region-average", &*self.region_average_box, "selected")
+                .build();
+
             self.settings
                 .bind("alpha-position", &*self.alpha_pos_box, "selected")
                 .build();
@@ -101,10 +146,34 @@ mod imp {
                 .bind("rgb-notation", &*self.rgb_format_box, "selected")
                 .build();
 
+            self.settings
+                .bind("gamut-mapping", &*self.gamut_mapping_box, "selected")
+                .build();
+
             self.settings
                 .bind("precision-digits", &*self.precision_row, "value")
                 .build();
 
+            self.settings
+                .bind("cie-illuminants", &*self.illuminant_box, "selected")
+                .build();
+
+            self.settings
+                .bind("cie-standard-observer", &*self.observer_box, "selected")
+                .build();
+
+            self.settings
+                .bind("vision-severity", &*self.severity_row, "value")
+                .build();
+
+            self.settings
+                .bind(
+                    "vision-simulation-method",
+                    &*self.simulation_method_box,
+                    "selected",
+                )
+                .build();
+
             self.bind_setting(&self.name_source_basic, ColorNameSources::Html);
             self.bind_setting(&self.name_source_extended, ColorNameSources::Svg);
             self.bind_setting(&self.name_source_gnome, ColorNameSources::Gnome);
@@ -179,6 +248,78 @@ impl PreferencesWindow {
         self.push_subpage(&*self.imp().name_source_page);
     }
 
+    /// Adds a custom format with the name and template entered by the user.
+    ///
+    /// The new format is visible and appended to the format order.
+    #[template_callback]
+    fn on_custom_format_apply(&self, _row: &adw::EntryRow) {
+        let imp = self.imp();
+        let name = imp.custom_name_row.text().trim().to_owned();
+        let mut templates = Template::load(&imp.settings);
+        if name.is_empty() || templates.iter().any(|template| template.name() == name) {
+            imp.custom_name_row.add_css_class("error");
+            self.add_toast(adw::Toast::new(&gettext(
+                "Enter a name, which is not used by another format",
+            )));
+            return;
+        }
+        imp.custom_name_row.remove_css_class("error");
+
+        let template = match Template::new(name, &imp.custom_template_row.text()) {
+            Ok(template) => template,
+            Err(err) => {
+                tracing::debug!("Invalid template: {}", err);
+                imp.custom_template_row.add_css_class("error");
+                self.add_toast(adw::Toast::new(&err.to_string()));
+                return;
+            }
+        };
+        imp.custom_template_row.remove_css_class("error");
+
+        templates.push(template.clone());
+        if let Err(err) = Template::store(&imp.settings, &templates) {
+            tracing::error!("Failed to save custom formats: {}", err);
+            return;
+        }
+        let mut visible_formats = imp.settings.get::<Vec<String>>("visible-formats");
+        visible_formats.push(template.identifier());
+        imp.settings
+            .set("visible-formats", visible_formats)
+            .expect("Failed to save visible-formats");
+
+        self.formats()
+            .append(&custom_format_object(&template, Color::random()));
+        self.save_format_order();
+
+        imp.custom_name_row.set_text("");
+        imp.custom_template_row.set_text("");
+    }
+
+    /// Removes the custom format from the settings and the format list.
+    fn remove_custom_format(&self, item: &ColorFormatObject) {
+        let settings = &self.imp().settings;
+        let identifier = item.identifier();
+
+        let templates = Template::load(settings)
+            .into_iter()
+            .filter(|template| template.identifier() != identifier)
+            .collect::<Vec<_>>();
+        if let Err(err) = Template::store(settings, &templates) {
+            tracing::error!("Failed to save custom formats: {}", err);
+            return;
+        }
+        let mut visible_formats = settings.get::<Vec<String>>("visible-formats");
+        visible_formats.retain(|format| format != &identifier);
+        settings
+            .set("visible-formats", visible_formats)
+            .expect("Failed to save visible-formats");
+
+        if let Some(index) = self.formats().find(item) {
+            self.formats().remove(index);
+        }
+        self.save_format_order();
+    }
+
     /// Returns the formats list store object.
     fn formats(&self) -> gio::ListStore {
         self.imp()
@@ -357,6 +498,23 @@ impl PreferencesWindow {
         let down_item = MenuItem::new(Some(&gettext("Move Down")), Some("row.move-down"));
         menu.append_item(&down_item);
 
+        if item.identifier().starts_with(CUSTOM_PREFIX) {
+            let remove_action = gio::SimpleAction::new("remove", None);
+            remove_action.connect_activate(glib::clone!(
+                #[weak(rename_to = window)]
+                self,
+                #[weak]
+                item,
+                move |_, _| {
+                    tracing::debug!("Removing custom format {}", item.label());
+                    window.remove_custom_format(&item);
+                }
+            ));
+            actions.add_action(&remove_action);
+            let remove_item = MenuItem::new(Some(&gettext("Remove")), Some("row.remove"));
+            menu.append_item(&remove_item);
+        }
+
         let menu_button = gtk::MenuButton::builder()
             .valign(gtk::Align::Center)
             .icon_name("view-more-symbolic")
@@ -468,12 +626,45 @@ impl PreferencesWindow {
             }
         }
 
+        // custom formats are not part of the default order, so they are appended to it
+        let templates = Template::load(&self.imp().settings);
+        let mut missing_custom_formats = false;
+        for template in &templates {
+            if !order.contains(&template.identifier()) {
+                order.push(template.identifier());
+                missing_custom_formats = true;
+            }
+        }
+
         for item in order {
-            let format = Notation::from_str(&item)
-                .expect("Failed to create ColorFormatObject")
-                .to_color_format_object(item, example_color);
+            let format = if item.starts_with(CUSTOM_PREFIX) {
+                let Some(template) = templates
+                    .iter()
+                    .find(|template| template.identifier() == item)
+                else {
+                    continue;
+                };
+                custom_format_object(template, example_color)
+            } else {
+                Notation::from_str(&item)
+                    .expect("Failed to create ColorFormatObject")
+                    .to_color_format_object(item, example_color)
+            };
 
             self.formats().append(&format);
         }
+
+        if missing_custom_formats {
+            self.save_format_order();
+        }
     }
 }
+
+/// Creates the format list item of the custom format, with the color as example.
+fn custom_format_object(template: &Template, color: Color) -> ColorFormatObject {
+    ColorFormatObject::new(
+        template.identifier(),
+        template.name().to_owned(),
+        template.format(color, 2, ReferenceWhite::default(), GamutMapping::default()),
+    )
+}
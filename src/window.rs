@@ -1,6 +1,7 @@
 use std::str::FromStr;
 
-use gettextrs::gettext;
+use adw::prelude::{AdwDialogExt, AlertDialogExt, AlertDialogExtManual, ComboRowExt};
+use gettextrs::{gettext, ngettext};
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 use gtk::{gio, glib};
@@ -8,11 +9,27 @@ use palette::{GetHue, IntoColor, SetHue};
 
 use crate::application::App;
 use crate::colors::Notation;
-use crate::colors::color::Color;
+use crate::colors::color::{Color, ColorError};
+use crate::colors::color_names::ColorNameSources;
+use crate::colors::gamut::GamutMapping;
+use crate::colors::palette_file::PaletteFormat;
+use crate::colors::template::{CUSTOM_PREFIX, Template};
+use crate::colors::vision::Simulation;
 use crate::config::{APP_ID, PROFILE};
-use crate::model::history::HistoryObject;
+use crate::model::history::{self, ColorSource, HistoryObject};
+use crate::model::palette::Palette;
 use crate::widgets::color_format_row::ColorFormatRow;
+use crate::widgets::color_ramp::ColorRamp;
+use crate::widgets::contrast_dialog::ContrastDialog;
+use crate::widgets::difference_dialog::DifferenceDialog;
+use crate::widgets::extract_palette_dialog::ExtractPaletteDialog;
+use crate::widgets::gradient_dialog::GradientDialog;
+use crate::widgets::harmony_dialog::HarmonyDialog;
 use crate::widgets::history_item::HistoryItem;
+use crate::widgets::image_picker_dialog::ImagePickerDialog;
+use crate::widgets::magnifier_window::MagnifierWindow;
+use crate::widgets::mix_dialog::MixDialog;
+use crate::widgets::palette_library_dialog::PaletteLibraryDialog;
 use crate::widgets::placeholder_page::PlaceholderPage;
 
 mod imp {
@@ -35,8 +52,12 @@ mod imp {
         #[template_child]
         pub format_box: TemplateChild<gtk::Box>,
         #[template_child]
+        pub color_ramp: TemplateChild<ColorRamp>,
+        #[template_child]
         pub color_button: TemplateChild<gtk::ColorDialogButton>,
         #[template_child]
+        pub simulation_button: TemplateChild<gtk::ColorDialogButton>,
+        #[template_child]
         pub color_picker_button: TemplateChild<gtk::Button>,
         #[template_child]
         pub edit_sheet: TemplateChild<adw::BottomSheet>,
@@ -51,20 +72,27 @@ mod imp {
         #[template_child]
         pub history_list: TemplateChild<gtk::ListBox>,
         pub history: OnceCell<gio::ListStore>,
+        /// The [`Palette`]s of the palette library.
+        pub palettes: OnceCell<gio::ListStore>,
         pub settings: gio::Settings,
         pub color: Cell<Option<Color>>,
+        /// Simulated color vision deficiency selected in the settings.
+        pub simulation: Cell<Option<Simulation>>,
         pub css_provider: gtk::CssProvider,
     }
 
     impl Default for AppWindow {
         fn default() -> Self {
+            let settings = gio::Settings::new(APP_ID);
             Self {
                 placeholder: TemplateChild::default(),
                 stack: TemplateChild::default(),
                 color_button: TemplateChild::default(),
+                simulation_button: TemplateChild::default(),
                 color_picker_button: TemplateChild::default(),
                 toast_overlay: TemplateChild::default(),
                 format_box: TemplateChild::default(),
+                color_ramp: TemplateChild::default(),
                 edit_sheet: TemplateChild::default(),
                 hue_scale: TemplateChild::default(),
                 saturation_scale: TemplateChild::default(),
@@ -72,7 +100,9 @@ mod imp {
                 color_preview: TemplateChild::default(),
                 history_list: TemplateChild::default(),
                 history: Default::default(),
-                settings: gio::Settings::new(APP_ID),
+                palettes: Default::default(),
+                simulation: Cell::new(Simulation::from_settings(&settings)),
+                settings,
                 color: Cell::new(None),
                 css_provider: Default::default(),
             }
@@ -93,6 +123,44 @@ mod imp {
                 win.pick_color().await;
             });
 
+            klass.install_action_async("win.paste-color", None, move |win, _, _var| async move {
+                win.paste_color().await;
+            });
+            // bound to the window, so that focused entries can still paste text
+            klass.add_binding_action(
+                gtk::gdk::Key::v,
+                gtk::gdk::ModifierType::CONTROL_MASK,
+                "win.paste-color",
+            );
+
+            klass.install_action_async("win.open-image", None, move |win, _, _var| async move {
+                win.open_image(None).await;
+            });
+
+            klass.install_action_async(
+                "win.extract-palette",
+                None,
+                move |win, _, _var| async move {
+                    win.extract_screenshot_palette().await;
+                },
+            );
+
+            klass.install_action_async(
+                "win.import-palette",
+                None,
+                move |win, _, _var| async move {
+                    win.import_palette().await;
+                },
+            );
+
+            klass.install_action_async(
+                "win.export-history",
+                None,
+                move |win, _, _var| async move {
+                    win.export_history().await;
+                },
+            );
+
             klass.install_action(
                 "win.show-toast",
                 Some(glib::VariantTy::TUPLE),
@@ -105,31 +173,121 @@ mod imp {
 
             klass.install_action(
                 "win.set-color",
-                Some(glib::VariantTy::STRING),
+                Some(&Color::static_variant_type()),
                 move |win, _, var| {
-                    let Some(Ok(color)) = var
-                        .and_then(|v| v.get::<String>())
-                        .map(|v| Color::from_str(&v))
-                    else {
-                        return;
-                    };
-                    win.set_color(color);
+                    if let Some(color) = var.and_then(|v| v.get::<Color>()) {
+                        win.set_color(color, ColorSource::Editor);
+                    }
+                },
+            );
+
+            klass.install_action(
+                "win.select-history-item",
+                Some(&Color::static_variant_type()),
+                move |win, _, var| {
+                    if let Some(color) = var.and_then(|v| v.get::<Color>()) {
+                        win.select_history_item(color);
+                    }
+                },
+            );
+
+            // in contrast to `win.set-color`, this records where the color came from
+            klass.install_action(
+                "win.set-exact-color",
+                Some(&*<(Color, ColorSource)>::static_variant_type()),
+                move |win, _, var| {
+                    if let Some((color, source)) = var.and_then(|v| v.get::<(Color, ColorSource)>())
+                    {
+                        win.set_color(color, source);
+                    }
+                },
+            );
+
+            klass.install_action(
+                "win.add-colors",
+                Some(&Vec::<Color>::static_variant_type()),
+                move |win, _, var| {
+                    let colors = var.and_then(|v| v.get::<Vec<Color>>()).unwrap_or_default();
+                    win.add_colors(&colors, ColorSource::Editor);
+                },
+            );
+
+            klass.install_action("win.show-harmonies", None, move |win, _, _var| {
+                if let Some(color) = win.color() {
+                    HarmonyDialog::new(color).present(Some(win));
+                }
+            });
+
+            klass.install_action("win.show-contrast", None, move |win, _, _var| {
+                win.show_contrast_dialog(None);
+            });
+
+            klass.install_action(
+                "win.check-contrast",
+                Some(&Color::static_variant_type()),
+                move |win, _, var| {
+                    if let Some(background) = var.and_then(|v| v.get::<Color>()) {
+                        win.show_contrast_dialog(Some(background));
+                    }
+                },
+            );
+
+            klass.install_action("win.show-difference", None, move |win, _, _var| {
+                win.show_difference_dialog(None);
+            });
+
+            klass.install_action(
+                "win.compare-colors",
+                Some(&Color::static_variant_type()),
+                move |win, _, var| {
+                    if let Some(sample) = var.and_then(|v| v.get::<Color>()) {
+                        win.show_difference_dialog(Some(sample));
+                    }
+                },
+            );
+
+            klass.install_action("win.build-gradient", None, move |win, _, _var| {
+                win.show_gradient_dialog();
+            });
+
+            klass.install_action("win.show-mix", None, move |win, _, _var| {
+                win.show_mix_dialog(None);
+            });
+
+            klass.install_action(
+                "win.mix-colors",
+                Some(&Color::static_variant_type()),
+                move |win, _, var| {
+                    if let Some(other) = var.and_then(|v| v.get::<Color>()) {
+                        win.show_mix_dialog(Some(other));
+                    }
+                },
+            );
+
+            klass.install_action("win.show-palettes", None, move |win, _, _var| {
+                PaletteLibraryDialog::new(&win.palettes()).present(Some(win));
+            });
+
+            klass.install_action(
+                "win.add-to-palette",
+                Some(&Color::static_variant_type()),
+                move |win, _, var| {
+                    if let Some(color) = var.and_then(|v| v.get::<Color>()) {
+                        win.show_add_to_palette_dialog(color);
+                    }
                 },
             );
 
             klass.install_action(
                 "win.remove-item",
-                Some(glib::VariantTy::STRING),
+                Some(&Color::static_variant_type()),
                 |win, _, var| {
-                    let Some(Ok(color)) = var
-                        .and_then(|v| v.get::<String>())
-                        .map(|v| Color::from_str(&v))
-                    else {
+                    let Some(color) = var.and_then(|v| v.get::<Color>()) else {
                         return;
                     };
 
                     let Some(index) = win.history().find_with_equal_func(|item| {
-                        item.downcast_ref::<HistoryObject>().unwrap().color() == color.into()
+                        item.downcast_ref::<HistoryObject>().unwrap().color() == color
                     }) else {
                         return;
                     };
@@ -149,7 +307,7 @@ mod imp {
                                     .map(|item| item.color())
                             })
                             .unwrap();
-                        win.set_color(next_color.into());
+                        win.display_color(next_color);
                     }
                 },
             );
@@ -182,9 +340,49 @@ mod imp {
                 }
             ));
 
+            obj.add_action(&self.settings.create_action("vision-deficiency"));
+            self.settings.connect_changed(
+                None,
+                glib::clone!(
+                    #[weak]
+                    obj,
+                    move |settings, key| {
+                        if key.starts_with("vision-") {
+                            obj.set_simulation(Simulation::from_settings(settings));
+                        } else if key == "gamut-mapping"
+                            && let Some(color) = obj.color()
+                        {
+                            obj.display_color(color);
+                        }
+                    }
+                ),
+            );
+
+            // pick colors from images dropped onto the window
+            let drop_target =
+                gtk::DropTarget::new(gio::File::static_type(), gtk::gdk::DragAction::COPY);
+            drop_target.connect_drop(glib::clone!(
+                #[weak]
+                obj,
+                #[upgrade_or]
+                false,
+                move |_, value, _, _| {
+                    let Ok(file) = value.get::<gio::File>() else {
+                        return false;
+                    };
+                    glib::spawn_future_local(async move {
+                        obj.open_image(Some(file)).await;
+                    });
+                    true
+                }
+            ));
+            obj.add_controller(drop_target);
+
             // Load latest window state
             obj.setup_history();
+            obj.setup_palettes();
             obj.order_formats();
+            obj.restore_history();
 
             // setup CSS provider to update the edit sheet scale colors
             gtk::style_context_add_provider_for_display(
@@ -277,8 +475,24 @@ impl AppWindow {
             #[strong]
             items,
             move |_toast| {
-                window.history().extend_from_slice(&items);
-                tracing::debug!("Undo clicked: {}", items.len());
+                // colors kept or picked since clearing are not restored a second time
+                let history = window.history();
+                let restored = items
+                    .iter()
+                    .filter_map(Cast::downcast_ref::<HistoryObject>)
+                    .filter(|item| {
+                        history
+                            .find_with_equal_func(|other| {
+                                other.downcast_ref::<HistoryObject>().unwrap().color()
+                                    == item.color()
+                            })
+                            .is_none()
+                    })
+                    .cloned()
+                    .collect::<Vec<HistoryObject>>();
+                history.extend_from_slice(&restored);
+                history::truncate(history);
+                tracing::debug!("Undo clicked: {}", restored.len());
             }
         ));
 
@@ -287,7 +501,17 @@ impl AppWindow {
         self.imp().toast_overlay.add_toast(toast);
 
         if let Some(color) = self.color() {
-            let history_item = HistoryObject::new(color);
+            // keep the details of the current color, in a copy so the undo can restore the original
+            let current = items
+                .iter()
+                .filter_map(Cast::downcast_ref::<HistoryObject>)
+                .find(|item| item.color() == color);
+            let history_item = HistoryObject::new(color, ColorSource::default());
+            if let Some(current) = current {
+                history_item.set_picked(current.picked());
+                history_item.set_source(current.source());
+                history_item.set_label(current.label());
+            }
             self.history().insert(0, &history_item);
         }
     }
@@ -302,15 +526,29 @@ impl AppWindow {
             .expect("Failed to set history model");
 
         let selection_model = gtk::NoSelection::new(Some(self.history().clone()));
-        self.imp()
-            .history_list
-            .bind_model(Some(&selection_model), move |obj| {
-                let history_object = obj
-                    .downcast_ref::<HistoryObject>()
-                    .expect("The object is not of type `HistoryObject`.");
-                let history_item = HistoryItem::new(history_object.color());
-                history_item.upcast()
-            });
+        self.imp().history_list.bind_model(
+            Some(&selection_model),
+            glib::clone!(
+                #[weak(rename_to = window)]
+                self,
+                #[upgrade_or_panic]
+                move |obj| {
+                    let history_object = obj
+                        .downcast_ref::<HistoryObject>()
+                        .expect("The object is not of type `HistoryObject`.");
+                    let history_item =
+                        HistoryItem::new(history_object, window.imp().simulation.get());
+                    // labels are edited as note of the item, the history has to be saved manually,
+                    // which happens once the editing popover is closed
+                    history_item.connect_note_notify(glib::clone!(
+                        #[weak]
+                        window,
+                        move |_| window.save_history()
+                    ));
+                    history_item.upcast()
+                }
+            ),
+        );
 
         let gtk_settings = gtk::Settings::default().unwrap();
         gtk_settings.connect_gtk_overlay_scrolling_notify(glib::clone!(
@@ -323,6 +561,11 @@ impl AppWindow {
 
         // disable history clearing action before an item has been added
         self.action_set_enabled("app.clear-history", false);
+        self.action_set_enabled("win.export-history", false);
+        self.action_set_enabled("win.show-contrast", false);
+        self.action_set_enabled("win.show-difference", false);
+        self.action_set_enabled("win.show-harmonies", false);
+        self.action_set_enabled("win.build-gradient", false);
         self.history().connect_items_changed(glib::clone!(
             #[weak(rename_to = window)]
             self,
@@ -331,11 +574,433 @@ impl AppWindow {
                 let visible = items.n_items() > 1;
                 window.imp().history_list.set_visible(visible);
                 window.action_set_enabled("app.clear-history", visible);
+                window.action_set_enabled("win.export-history", items.n_items() > 0);
+                window.action_set_enabled("win.show-contrast", items.n_items() > 0);
+                window.action_set_enabled("win.show-difference", items.n_items() > 0);
+                window.action_set_enabled("win.show-harmonies", items.n_items() > 0);
+                window.action_set_enabled("win.build-gradient", items.n_items() > 0);
                 window.adjust_scrollbar_offset(&gtk_settings);
+                window.save_history();
             }
         ));
     }
 
+    /// Loads the palette library.
+    fn setup_palettes(&self) {
+        let palettes = gio::ListStore::new::<Palette>();
+        palettes.extend_from_slice(&Palette::load_library());
+        self.imp()
+            .palettes
+            .set(palettes)
+            .expect("Failed to set palettes model");
+    }
+
+    /// Returns the palettes of the palette library.
+    pub fn palettes(&self) -> gio::ListStore {
+        self.imp()
+            .palettes
+            .get()
+            .expect("Could not get palettes")
+            .clone()
+    }
+
+    /// Shows a dialog for adding the color to a palette of the library.
+    ///
+    /// A new palette is created, if the user selects none of the existing palettes.
+    pub fn show_add_to_palette_dialog(&self, color: Color) {
+        let palettes = self
+            .palettes()
+            .snapshot()
+            .iter()
+            .filter_map(Cast::downcast_ref::<Palette>)
+            .cloned()
+            .collect::<Vec<Palette>>();
+
+        let names = gtk::StringList::new(&[]);
+        for palette in &palettes {
+            names.append(&palette.name());
+        }
+        names.append(&gettext("New Palette"));
+        let palette_row = adw::ComboRow::builder()
+            .title(gettext("Palette"))
+            .model(&names)
+            .build();
+        let label_row = adw::EntryRow::builder()
+            .title(gettext("Label"))
+            .activates_default(true)
+            .build();
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .css_classes(["boxed-list"])
+            .build();
+        list.append(&palette_row);
+        list.append(&label_row);
+
+        let dialog = adw::AlertDialog::builder()
+            .heading(gettext("Add to Palette"))
+            .extra_child(&list)
+            .default_response("add")
+            .close_response("cancel")
+            .build();
+        dialog.add_responses(&[("cancel", &gettext("_Cancel")), ("add", &gettext("_Add"))]);
+        dialog.set_response_appearance("add", adw::ResponseAppearance::Suggested);
+        dialog.connect_response(
+            Some("add"),
+            glib::clone!(
+                #[weak(rename_to = window)]
+                self,
+                move |_, _| {
+                    let palette = match palettes.get(palette_row.selected() as usize) {
+                        Some(palette) => palette.clone(),
+                        None => {
+                            let palette = Palette::new(&Palette::unused_name(&window.palettes()));
+                            window.palettes().append(&palette);
+                            palette
+                        }
+                    };
+                    palette.add_color(color, label_row.text().trim());
+                    Palette::save_library(&window.palettes());
+                    window.show_toast(
+                        gettext("Added to {}").replace("{}", &palette.name()),
+                        adw::ToastPriority::Normal,
+                    );
+                }
+            ),
+        );
+        dialog.present(Some(self));
+    }
+
+    /// Saves the colors of the history to the settings, so they can be restored in the next session.
+    ///
+    /// Each color is stored with when it was picked as unix timestamp (0 if unknown), its
+    /// source and its label. The history is written as a whole, so it is limited to
+    /// [`history::HISTORY_LIMIT`] colors.
+    fn save_history(&self) {
+        let history = self
+            .history()
+            .snapshot()
+            .iter()
+            .filter_map(Cast::downcast_ref::<HistoryObject>)
+            .map(|item| {
+                let picked = item.picked().map_or(0, |picked| picked.to_unix());
+                (item.color(), picked, item.source(), item.label())
+            })
+            .collect::<Vec<(Color, i64, ColorSource, String)>>();
+
+        if let Err(err) = self.imp().settings.set("history", history) {
+            tracing::error!("Failed to save history: {}", err);
+        }
+    }
+
+    /// Restores the history saved in the previous session.
+    ///
+    /// The most recent color of the saved history will be set as the current color.
+    fn restore_history(&self) {
+        // sources are read as strings, so unknown sources do not discard the whole history
+        let history: Vec<(Color, i64, String, String)> = self.imp().settings.get("history");
+        tracing::debug!("Restoring {} colors from history", history.len());
+
+        let Some(&(current_color, ..)) = history.first() else {
+            return;
+        };
+
+        let items = history
+            .into_iter()
+            .map(|(color, picked, source, label)| {
+                let source = source.to_variant().get::<ColorSource>().unwrap_or_default();
+                let item = HistoryObject::new(color, source);
+                item.set_picked(
+                    (picked != 0)
+                        .then(|| glib::DateTime::from_unix_local(picked).ok())
+                        .flatten(),
+                );
+                item.set_label(label);
+                item
+            })
+            .collect::<Vec<HistoryObject>>();
+        self.history().extend_from_slice(&items);
+        history::truncate(self.history());
+        self.display_color(current_color);
+    }
+
+    /// Imports the colors of a palette file chosen by the user into the history.
+    ///
+    /// The format of the palette is determined by the extension of the file, unknown
+    /// files are read as plain text. Entries that could not be imported are summarized
+    /// in a toast.
+    pub async fn import_palette(&self) {
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        let all_filter = gtk::FileFilter::new();
+        all_filter.set_name(Some(&gettext("All Palettes")));
+        filters.append(&all_filter);
+        for format in PaletteFormat::IMPORTABLE {
+            all_filter.add_suffix(format.extension());
+            let filter = gtk::FileFilter::new();
+            filter.set_name(Some(&format.display_name()));
+            filter.add_suffix(format.extension());
+            filters.append(&filter);
+        }
+
+        let dialog = gtk::FileDialog::builder()
+            .title(gettext("Import Palette"))
+            .filters(&filters)
+            .default_filter(&all_filter)
+            .modal(true)
+            .build();
+
+        let file = match dialog.open_future(Some(self)).await {
+            Ok(file) => file,
+            Err(err) => {
+                tracing::debug!("Failed to choose palette file: {}", err);
+                return;
+            }
+        };
+
+        let format = file
+            .path()
+            .as_deref()
+            .and_then(PaletteFormat::from_path)
+            .filter(|format| PaletteFormat::IMPORTABLE.contains(format))
+            .unwrap_or(PaletteFormat::Text);
+        let name_sources =
+            ColorNameSources::from_bits(self.imp().settings.uint("name-sources-flag"))
+                .unwrap_or(ColorNameSources::empty());
+        let (alpha_position, reference_white) = Notation::parse_settings();
+
+        let palette = file
+            .load_contents_future()
+            .await
+            .map_err(|err| ColorError::ParsingError(err.to_string()))
+            .and_then(|(contents, _etag)| {
+                format.import(&contents, alpha_position, name_sources, reference_white)
+            });
+        let palette = match palette {
+            Ok(palette) => palette,
+            Err(err) => {
+                tracing::error!("Failed to import palette: {:?}", err);
+                self.show_toast(
+                    gettext("Failed to import palette"),
+                    adw::ToastPriority::Normal,
+                );
+                return;
+            }
+        };
+
+        for entry in &palette.invalid {
+            tracing::warn!("Skipped invalid palette entry: {}", entry);
+        }
+
+        self.add_colors(&palette.colors, ColorSource::Import);
+
+        let imported = ngettext(
+            "Imported {} color",
+            "Imported {} colors",
+            palette.colors.len() as u32,
+        )
+        .replace("{}", &palette.colors.len().to_string());
+        let toast = if palette.invalid.is_empty() {
+            imported
+        } else {
+            let skipped = ngettext(
+                "skipped {} invalid entry",
+                "skipped {} invalid entries",
+                palette.invalid.len() as u32,
+            )
+            .replace("{}", &palette.invalid.len().to_string());
+            format!("{imported}, {skipped}")
+        };
+        self.show_toast(toast, adw::ToastPriority::Normal);
+    }
+
+    /// Adds the colors to the history, in the given order and including duplicates.
+    ///
+    /// The colors are inserted after the current color, which stays unchanged.
+    /// Without a current color, the first color becomes the current color.
+    pub fn add_colors(&self, colors: &[Color], source: ColorSource) {
+        let Some(&first) = colors.first() else {
+            return;
+        };
+        let items = colors
+            .iter()
+            .map(|color| HistoryObject::new(*color, source))
+            .collect::<Vec<HistoryObject>>();
+        // the current color is the first item of the history
+        let position = self.history().n_items().min(1);
+        // a single change, so the history is only saved once
+        self.history().splice(position, 0, &items);
+        history::truncate(self.history());
+        if self.color().is_none() {
+            self.display_color(first);
+        }
+    }
+
+    /// Shows a dialog comparing the contrast of the current color on the background color.
+    ///
+    /// Without a background color, the previous color of the history is used, or white if
+    /// there is none.
+    pub fn show_contrast_dialog(&self, background: Option<Color>) {
+        let Some(color) = self.color() else {
+            return;
+        };
+
+        let background = background
+            .or_else(|| {
+                self.history()
+                    .item(1)
+                    .and_downcast::<HistoryObject>()
+                    .map(|item| item.color())
+            })
+            .unwrap_or(Color::rgba(255, 255, 255, 255));
+
+        ContrastDialog::new(color, background).present(Some(self));
+    }
+
+    /// Shows a dialog comparing the sample color to the current color.
+    ///
+    /// Without a sample color, the previous color of the history is used, or the current
+    /// color if there is none.
+    pub fn show_difference_dialog(&self, sample: Option<Color>) {
+        let Some(color) = self.color() else {
+            return;
+        };
+
+        let sample = sample
+            .or_else(|| {
+                self.history()
+                    .item(1)
+                    .and_downcast::<HistoryObject>()
+                    .map(|item| item.color())
+            })
+            .unwrap_or(color);
+
+        DifferenceDialog::new(color, sample).present(Some(self));
+    }
+
+    /// Shows a dialog for picking a color from the image file.
+    ///
+    /// Without a file, the user is asked to choose one.
+    pub async fn open_image(&self, file: Option<gio::File>) {
+        let dialog = ImagePickerDialog::new();
+        dialog.present(Some(self));
+        match file {
+            Some(file) => dialog.load_image(&file).await,
+            None => dialog.choose_image().await,
+        }
+    }
+
+    /// Shows a dialog building a gradient from the colors of the history.
+    pub fn show_gradient_dialog(&self) {
+        let colors = self
+            .history()
+            .snapshot()
+            .iter()
+            .filter_map(Cast::downcast_ref::<HistoryObject>)
+            .map(|item| item.color())
+            .collect::<Vec<Color>>();
+
+        GradientDialog::new(&colors).present(Some(self));
+    }
+
+    /// Shows a dialog mixing the other color into the current color.
+    ///
+    /// Without another color, the previous color of the history is used, or the current
+    /// color if there is none.
+    pub fn show_mix_dialog(&self, other: Option<Color>) {
+        let Some(color) = self.color() else {
+            return;
+        };
+
+        let other = other
+            .or_else(|| {
+                self.history()
+                    .item(1)
+                    .and_downcast::<HistoryObject>()
+                    .map(|item| item.color())
+            })
+            .unwrap_or(color);
+
+        MixDialog::new(color, other).present(Some(self));
+    }
+
+    /// Exports the colors of the history to a palette file chosen by the user.
+    ///
+    /// The format of the palette is determined by the extension of the file.
+    pub async fn export_history(&self) {
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        for format in PaletteFormat::EXPORTABLE {
+            let filter = gtk::FileFilter::new();
+            filter.set_name(Some(&format.display_name()));
+            filter.add_suffix(format.extension());
+            filters.append(&filter);
+        }
+
+        let dialog = gtk::FileDialog::builder()
+            .title(gettext("Export History"))
+            .initial_name(format!("palette.{}", PaletteFormat::Gpl.extension()))
+            .filters(&filters)
+            .modal(true)
+            .build();
+
+        let file = match dialog.save_future(Some(self)).await {
+            Ok(file) => file,
+            Err(err) => {
+                tracing::debug!("Failed to choose export file: {}", err);
+                return;
+            }
+        };
+
+        let Some(format) = file.path().as_deref().and_then(PaletteFormat::from_path) else {
+            self.show_toast(
+                gettext("Unsupported palette format"),
+                adw::ToastPriority::Normal,
+            );
+            return;
+        };
+
+        let colors = self
+            .history()
+            .snapshot()
+            .iter()
+            .filter_map(Cast::downcast_ref::<HistoryObject>)
+            .map(|item| item.color())
+            .collect::<Vec<Color>>();
+        let name_sources =
+            ColorNameSources::from_bits(self.imp().settings.uint("name-sources-flag"))
+                .unwrap_or(ColorNameSources::empty());
+
+        let gamut_mapping = GamutMapping::from(self.imp().settings.int("gamut-mapping") as u32);
+
+        let contents = match format.export(&colors, name_sources, gamut_mapping) {
+            Ok(contents) => contents,
+            Err(err) => {
+                tracing::error!("Failed to export history: {}", err);
+                self.show_toast(
+                    gettext("Unsupported palette format"),
+                    adw::ToastPriority::Normal,
+                );
+                return;
+            }
+        };
+        match file
+            .replace_contents_future(
+                contents,
+                None,
+                false,
+                gio::FileCreateFlags::REPLACE_DESTINATION,
+            )
+            .await
+        {
+            Ok(_) => self.show_toast(gettext("Exported history"), adw::ToastPriority::Normal),
+            Err((_, err)) => {
+                tracing::error!("Failed to export history: {}", err);
+                self.show_toast(
+                    gettext("Failed to export history"),
+                    adw::ToastPriority::Normal,
+                );
+            }
+        }
+    }
+
     fn adjust_scrollbar_offset(&self, settings: &gtk::Settings) {
         // FIXME: remove this workaround once https://gitlab.gnome.org/GNOME/gtk/-/issues/6384 is fixed
         // calculate how many history items fit into the list, before it begins scrolling
@@ -372,17 +1037,31 @@ impl AppWindow {
         tracing::debug!("Formats: {:?}", order);
         tracing::debug!("Visible: {:?}", visible);
 
+        let templates = Template::load(&imp.settings);
         order
             .iter()
             .filter(|item| visible.contains(item))
-            .flat_map(|item| Notation::from_str(item))
-            .map(|notation| ColorFormatRow::new(&notation))
+            .filter_map(|item| {
+                if item.starts_with(CUSTOM_PREFIX) {
+                    templates
+                        .iter()
+                        .find(|template| &template.identifier() == item)
+                        .map(|template| ColorFormatRow::with_template(template.clone()))
+                } else {
+                    Notation::from_str(item)
+                        .ok()
+                        .map(|notation| ColorFormatRow::new(&notation))
+                }
+            })
             .for_each(|widget| {
                 format_box.append(&widget);
                 if let Some(color) = self.color() {
                     widget.display_color(color);
                 }
             });
+
+        // keep the ramp below the format rows
+        format_box.reorder_child_after(&*imp.color_ramp, format_box.last_child().as_ref());
     }
 
     /// Pick a color from the desktop using [ashpd].
@@ -390,6 +1069,11 @@ impl AppWindow {
     /// It will show a toast when failing to pick a color, for example when the user cancels the action.
     #[template_callback]
     pub async fn pick_color(&self) {
+        if self.imp().settings.boolean("pick-magnifier") {
+            self.pick_color_magnified().await;
+            return;
+        }
+
         tracing::debug!("Picking a color using the color picker");
         let root = self.root().expect("Failed to get window root");
         let identifier = ashpd::WindowIdentifier::from_native(&root).await;
@@ -399,7 +1083,10 @@ impl AppWindow {
             .await;
 
         match request.and_then(|req| req.response()) {
-            Ok(color) => self.set_color(Color::from(gtk::gdk::RGBA::from(color))),
+            Ok(color) => self.set_color(
+                Color::from(gtk::gdk::RGBA::from(color)),
+                ColorSource::Screen,
+            ),
             Err(err) => {
                 tracing::error!("{}", err);
                 if !matches!(
@@ -419,33 +1106,161 @@ impl AppWindow {
         };
     }
 
+    /// Pick a color from a screenshot of the desktop, which is shown with a magnifier.
+    ///
+    /// To pick the exact pixel, the screenshot is shown fullscreen with a loupe showing
+    /// the pixel grid around the cursor.
+    async fn pick_color_magnified(&self) {
+        tracing::debug!("Picking a color using the magnifier");
+        let Some(texture) = self.take_screenshot().await else {
+            return;
+        };
+
+        if let Some(color) = MagnifierWindow::new(self, &texture).pick_color().await {
+            self.set_color(color, ColorSource::Screen);
+        }
+    }
+
+    /// Shows a dialog extracting the palette of a screenshot of the desktop.
+    pub async fn extract_screenshot_palette(&self) {
+        if let Some(texture) = self.take_screenshot().await {
+            ExtractPaletteDialog::new(&texture).present(Some(self));
+        }
+    }
+
+    /// Takes a screenshot of the desktop using [ashpd].
+    ///
+    /// Returns [`None`] and shows a toast if the screenshot failed, unless the user cancelled it.
+    async fn take_screenshot(&self) -> Option<gtk::gdk::Texture> {
+        let root = self.root().expect("Failed to get window root");
+        let identifier = ashpd::WindowIdentifier::from_native(&root).await;
+        let request = ashpd::desktop::screenshot::Screenshot::request()
+            .identifier(identifier)
+            .interactive(false)
+            .modal(true)
+            .send()
+            .await;
+
+        let screenshot = match request.and_then(|req| req.response()) {
+            Ok(screenshot) => screenshot,
+            Err(err) => {
+                tracing::error!("{}", err);
+                if !matches!(
+                    err,
+                    ashpd::Error::Response(ashpd::desktop::ResponseError::Cancelled)
+                ) {
+                    self.show_toast(
+                        gettext("Failed to take a screenshot"),
+                        adw::ToastPriority::Normal,
+                    );
+                }
+                return None;
+            }
+        };
+
+        let file = gio::File::for_uri(screenshot.uri().as_str());
+        let texture = match file.load_bytes_future().await {
+            Ok((bytes, _etag)) => gtk::gdk::Texture::from_bytes(&bytes),
+            Err(err) => Err(err),
+        };
+        // the screenshot shows the whole desktop, so it must not be left behind
+        if let Err(err) = file.delete_future(glib::Priority::DEFAULT).await {
+            tracing::warn!("Failed to delete screenshot: {}", err);
+        }
+        match texture {
+            Ok(texture) => Some(texture),
+            Err(err) => {
+                tracing::error!("Failed to load screenshot: {}", err);
+                self.show_toast(
+                    gettext("Failed to take a screenshot"),
+                    adw::ToastPriority::Normal,
+                );
+                None
+            }
+        }
+    }
+
+    /// Set the current color to the color in the clipboard.
+    ///
+    /// The color can be in any supported notation. If the clipboard does not contain a
+    /// valid color, a toast will be shown.
+    pub async fn paste_color(&self) {
+        let text = match self.clipboard().read_text_future().await {
+            Ok(Some(text)) => text,
+            Ok(None) => return,
+            Err(err) => {
+                tracing::error!("Failed to read clipboard: {}", err);
+                return;
+            }
+        };
+
+        let name_sources =
+            ColorNameSources::from_bits(self.imp().settings.uint("name-sources-flag"))
+                .unwrap_or(ColorNameSources::empty());
+        match Notation::detect(&text, name_sources) {
+            Ok((notation, color)) => {
+                tracing::debug!("Pasted color in notation {:?}", notation);
+                self.set_color(color, ColorSource::Typed);
+            }
+            Err(_) => {
+                tracing::debug!("Failed to parse pasted text: {}", text);
+                self.show_toast(
+                    gettext("Clipboard does not contain a color"),
+                    adw::ToastPriority::Normal,
+                );
+            }
+        }
+    }
+
     /// Set the current color to the given color.
     ///
     /// If the given color is different from the current color,
-    /// it will be added to the history. If the history includes the given
-    /// color, the preceding occurrence will be removed.
-    pub fn set_color(&self, color: Color) {
+    /// it will be added to the history with its source. If the history includes the given
+    /// color, the preceding occurrence will be moved to the front. It keeps its label, but
+    /// is marked as picked just now from the source, so the history stays ordered by the
+    /// time colors were picked.
+    pub fn set_color(&self, color: Color, source: ColorSource) {
         if self.color() != Some(color) {
-            // TODO: remove check once bug is fixed
-            if self.history().n_items() > 0 {
-                if let Some(i) = self.history().find_with_equal_func(|item| {
-                    item.downcast_ref::<HistoryObject>().unwrap().color() == color.into()
-                }) {
-                    self.history().remove(i);
+            let history_item = match history::take_entry(self.history(), color) {
+                Some(item) => {
+                    item.set_picked(glib::DateTime::now_local().ok());
+                    item.set_source(source);
+                    item
                 }
-            }
-
-            let history_item = HistoryObject::new(color);
+                None => HistoryObject::new(color, source),
+            };
             self.history().insert(0, &history_item);
+            history::truncate(self.history());
         }
 
+        self.display_color(color);
+    }
+
+    /// Set the current color to the color of a history entry.
+    ///
+    /// In contrast to [`AppWindow::set_color`], the entry keeps where and when it was
+    /// picked, as it is only moved to the front of the history.
+    pub fn select_history_item(&self, color: Color) {
+        if self.color() != Some(color) && !history::select_entry(self.history(), color) {
+            self.history()
+                .insert(0, &HistoryObject::new(color, ColorSource::default()));
+        }
+
+        self.display_color(color);
+    }
+
+    /// Shows the color as the current color, without changing the history.
+    fn display_color(&self, color: Color) {
         let imp = self.imp();
         imp.color.replace(Some(color));
 
         //stop showing placeholder page, when a color is set
         imp.stack.set_visible_child_name("main");
 
-        imp.color_button.set_rgba(&color.into());
+        let gamut_mapping = GamutMapping::from(imp.settings.int("gamut-mapping") as u32);
+        imp.color_button
+            .set_rgba(&gamut_mapping.apply(color).into());
+        self.update_simulation();
 
         imp.format_box
             .observe_children()
@@ -453,6 +1268,32 @@ impl AppWindow {
             .iter()
             .filter_map(Cast::downcast_ref::<ColorFormatRow>)
             .for_each(|row| row.display_color(color));
+        imp.color_ramp.display_color(color);
+    }
+
+    /// Changes the simulated color vision deficiency of the current color and the history.
+    fn set_simulation(&self, simulation: Option<Simulation>) {
+        let imp = self.imp();
+        imp.simulation.set(simulation);
+        let mut index = 0;
+        while let Some(row) = imp.history_list.row_at_index(index) {
+            if let Some(item) = row.child().and_downcast::<HistoryItem>() {
+                item.set_simulation(simulation);
+            }
+            index += 1;
+        }
+        self.update_simulation();
+    }
+
+    /// Shows the current color as seen with the color vision deficiency selected in the settings.
+    fn update_simulation(&self) {
+        let imp = self.imp();
+        let simulation = imp.simulation.get();
+        imp.simulation_button.set_visible(simulation.is_some());
+        if let (Some(simulation), Some(color)) = (simulation, self.color()) {
+            imp.simulation_button
+                .set_rgba(&simulation.apply(color).into());
+        }
     }
 
     /// Opens a bottom sheet with an HSL color picker.
@@ -495,7 +1336,7 @@ impl AppWindow {
     #[template_callback]
     fn on_color_preview_select(&self) {
         let color = Color::from(self.imp().color_preview.rgba());
-        self.set_color(color);
+        self.set_color(color, ColorSource::Editor);
         self.imp().edit_sheet.set_open(false);
     }
 